@@ -0,0 +1,29 @@
+#[cfg(feature = "capi")]
+extern crate cbindgen;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "capi")]
+    generate_header();
+}
+
+/// Generate the C header for the functions in the `capi` module in
+/// `OUT_DIR`. The tests check that `include/mudi.h` matches this header.
+#[cfg(feature = "capi")]
+fn generate_header() {
+    use std::env;
+    use std::path::PathBuf;
+
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect("missing CARGO_MANIFEST_DIR"));
+    let out = PathBuf::from(env::var("OUT_DIR").expect("missing OUT_DIR"));
+    let config = cbindgen::Config::from_file(root.join("cbindgen.toml")).expect("invalid cbindgen.toml");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(root.join("src").join("capi.rs"))
+        .generate()
+        .expect("could not generate the C header")
+        .write_to_file(out.join("mudi.h"));
+}
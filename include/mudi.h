@@ -0,0 +1,116 @@
+/* Generated by the mudi build script with cbindgen, do not edit */
+
+#ifndef MUDI_H
+#define MUDI_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Maximal number of axes in an array, the same as `MAX_DYN_RANK`
+#define MUDI_MAX_RANK 7
+
+// Status code returned by the functions of the C API
+typedef enum mudi_status_t {
+  // The function succeeded
+  MUDI_SUCCESS = 0,
+  // One of the pointers given to the function is null
+  MUDI_NULL_POINTER = 1,
+  // The axis is not smaller than the array rank
+  MUDI_INVALID_AXIS = 2,
+  // The index is outside of the array bounds
+  MUDI_OUT_OF_BOUNDS = 3,
+} mudi_status_t;
+
+// Type of the elements in a `mudi_array_t`. Functions taking a type from C
+// use an `uint32_t` containing one of these values.
+typedef enum mudi_dtype_t {
+  // `double` elements
+  MUDI_F64 = 0,
+  // `float` elements
+  MUDI_F32 = 1,
+  // `int32_t` elements
+  MUDI_I32 = 2,
+  // `int64_t` elements
+  MUDI_I64 = 3,
+  // `uint8_t` elements
+  MUDI_U8 = 4,
+} mudi_dtype_t;
+
+// An array with dynamic dimensions, and one of the element types in
+// `mudi_dtype_t`. This type is opaque in C.
+typedef struct mudi_array_t mudi_array_t;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create a new array of `dtype` elements with `rank` axes, all the
+// elements being set to 0. `dtype` must be one of the `mudi_dtype_t`
+// values. `extents` must contain `rank` values, and `lbounds` must be `NULL`
+// or contain `rank` values. If `lbounds` is `NULL`, all the lower bounds
+// are 0.
+//
+// This function returns `NULL` if `dtype` is not a valid type, if `rank` is
+// larger than `MUDI_MAX_RANK`, if `extents` is `NULL`, if the upper bound
+// of an axis does not fit in `ptrdiff_t`, or if the array would be larger
+// than `PTRDIFF_MAX` bytes. The array must be released with
+// `mudi_array_free`.
+struct mudi_array_t *mudi_array_new(uint32_t dtype,
+                                    size_t rank,
+                                    const ptrdiff_t *lbounds,
+                                    const size_t *extents);
+
+// Release the memory used by `array`. `array` can be `NULL`.
+void mudi_array_free(struct mudi_array_t *array);
+
+// Get the type of the elements in `array` in `dtype`.
+enum mudi_status_t mudi_array_dtype(const struct mudi_array_t *array, enum mudi_dtype_t *dtype);
+
+// Get the number of axes of `array` in `rank`.
+enum mudi_status_t mudi_array_rank(const struct mudi_array_t *array, size_t *rank);
+
+// Get the total number of elements in `array` in `size`.
+enum mudi_status_t mudi_array_size(const struct mudi_array_t *array, size_t *size);
+
+// Get the lower bound of `array` along `axis` in `lbound`, with axes
+// numbered from 0.
+enum mudi_status_t mudi_array_lbound(const struct mudi_array_t *array,
+                                     size_t axis,
+                                     ptrdiff_t *lbound);
+
+// Get the upper bound of `array` along `axis` in `ubound`, with axes
+// numbered from 0. The upper bound is the last valid index along the axis.
+enum mudi_status_t mudi_array_ubound(const struct mudi_array_t *array,
+                                     size_t axis,
+                                     ptrdiff_t *ubound);
+
+// Get the number of elements of `array` along `axis` in `extent`, with
+// axes numbered from 0.
+enum mudi_status_t mudi_array_extent(const struct mudi_array_t *array, size_t axis, size_t *extent);
+
+// Get the position in storage of the element at `index` in `offset`.
+// `index` must contain one value for each axis of `array`.
+enum mudi_status_t mudi_array_offset(const struct mudi_array_t *array,
+                                     const ptrdiff_t *index,
+                                     size_t *offset);
+
+// Get a pointer to the elements of `array` in `data`. The elements are
+// stored in row-major order, and have the type given by
+// `mudi_array_dtype`. The pointer is valid until the array is released.
+enum mudi_status_t mudi_array_data(struct mudi_array_t *array, void **data);
+
+// Copy the element at `index` in `value`, which must point to a value of
+// the type of the array elements.
+enum mudi_status_t mudi_array_get(struct mudi_array_t *array, const ptrdiff_t *index, void *value);
+
+// Set the element at `index` to `value`, which must point to a value of
+// the type of the array elements.
+enum mudi_status_t mudi_array_set(struct mudi_array_t *array,
+                                  const ptrdiff_t *index,
+                                  const void *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MUDI_H */
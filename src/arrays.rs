@@ -1,12 +1,22 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::iter;
+use std::mem::MaybeUninit;
 use std::ops::{Index, IndexMut};
 use std::slice::{Iter, IterMut};
+use std::vec::IntoIter;
 
 use Dimensions;
-use Storage;
+use {Storage, AlignedStorage};
+use {ArrayView, ArrayViewMut};
 
 /// An array with owned storage.
 pub type Array<T, D> = ArrayBase<Box<[T]>, D>;
 
+/// An array with owned storage, starting on an `ALIGN` bytes boundary. See
+/// [`AlignedStorage`](struct.AlignedStorage.html) for more information.
+pub type AlignedArray<T, D, const ALIGN: usize> = ArrayBase<AlignedStorage<T, ALIGN>, D>;
+
 #[derive(Debug)]
 /// ArrayBase implements all the operations on arrays, using a `Storage` and
 /// some `Dimensions`.
@@ -37,6 +47,7 @@ impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
     /// // The dimension size is 14 here, and the array only contains 4 elements
     /// let array = Array::from_vector(vec![1, 2, 3, 4], (2, 7));
     /// ```
+    #[allow(clippy::redundant_field_names)]
     pub fn from_vector(vec: Vec<S::Item>, dims: D) -> ArrayBase<S, D> {
         assert!(vec.len() == dims.size(), "Data length does not match the dimensions");
         ArrayBase {
@@ -45,6 +56,61 @@ impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
         }
     }
 
+    /// Create a new array by calling `function` with the index of each
+    /// element, in storage order. Each element is written exactly once.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_fn((2, -1..2), |(i, j)| 10 * i as i32 + j);
+    /// assert_eq!(array[(0, -1)], -1);
+    /// assert_eq!(array[(1, 1)], 11);
+    /// ```
+    pub fn from_fn<F>(dims: D, mut function: F) -> ArrayBase<S, D>
+        where F: FnMut(D::Index) -> S::Item {
+        let data = S::from_iter(dims.size(), (0..dims.size()).map(|offset| function(dims.index(offset))));
+        ArrayBase {
+            data,
+            dims,
+        }
+    }
+
+    /// Create a new array using the values from an iterator, in storage
+    /// order. Each element is written exactly once.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_shape_iter((2, 3), 0..6);
+    /// assert_eq!(array[(1, 0)], 3);
+    /// ```
+    ///
+    /// # Panics
+    /// If the length of the iterator does not match the size of the dimensions.
+    ///
+    /// ```should_panic
+    /// use mudi::Array;
+    /// let array = Array::from_shape_iter((2, 3), 0..4);
+    /// ```
+    pub fn from_shape_iter<I>(dims: D, iter: I) -> ArrayBase<S, D>
+        where I: IntoIterator<Item = S::Item> {
+        let mut iter = iter.into_iter();
+        let data = S::from_iter(dims.size(), iter.by_ref());
+        assert!(iter.next().is_none(), "Iterator length does not match the dimensions");
+        ArrayBase {
+            data,
+            dims,
+        }
+    }
+
+    /// Create an array from its storage, which must contain `dims.size()`
+    /// elements.
+    pub(crate) fn from_storage(data: S, dims: D) -> ArrayBase<S, D> {
+        debug_assert_eq!(data.as_ref().len(), dims.size());
+        ArrayBase {
+            data,
+            dims,
+        }
+    }
+
     /// Get the shape of the array.
     ///
     /// ```
@@ -59,6 +125,118 @@ impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
         self.dims.clone()
     }
 
+    /// Get the number of axes of the array.
+    pub fn rank(&self) -> usize {
+        self.dims.rank()
+    }
+
+    /// Get the lower bound of the array along `axis`, like Fortran
+    /// `LBOUND(array, axis)`. Axes are numbered from 0.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_element(0.0, (3, -10..10));
+    /// assert_eq!(array.lbound(0), 0);
+    /// assert_eq!(array.lbound(1), -10);
+    /// ```
+    pub fn lbound(&self, axis: usize) -> isize {
+        self.dims.lbound(axis)
+    }
+
+    /// Get the upper bound of the array along `axis`, like Fortran
+    /// `UBOUND(array, axis)`. This is the last valid index along `axis`.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_element(0.0, (3, -10..10));
+    /// assert_eq!(array.ubound(0), 2);
+    /// assert_eq!(array.ubound(1), 9);
+    /// ```
+    pub fn ubound(&self, axis: usize) -> isize {
+        self.dims.ubound(axis)
+    }
+
+    /// Get the number of elements of the array along `axis`, like Fortran
+    /// `SIZE(array, axis)`.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_element(0.0, (3, -10..10));
+    /// assert_eq!(array.extent(0), 3);
+    /// assert_eq!(array.extent(1), 20);
+    /// ```
+    pub fn extent(&self, axis: usize) -> usize {
+        self.dims.extent(axis)
+    }
+
+    /// Get the lower bounds of the array along all axes, like Fortran
+    /// `LBOUND(array)`.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_element(0.0, (3, -10..10, 1..=4));
+    /// assert_eq!(array.lbounds(), [0, -10, 1]);
+    /// ```
+    pub fn lbounds(&self) -> D::Bounds {
+        self.dims.lbounds()
+    }
+
+    /// Get the upper bounds of the array along all axes, like Fortran
+    /// `UBOUND(array)`.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_element(0.0, (3, -10..10, 1..=4));
+    /// assert_eq!(array.ubounds(), [2, 9, 4]);
+    /// ```
+    pub fn ubounds(&self) -> D::Bounds {
+        self.dims.ubounds()
+    }
+
+    /// Get the number of elements of the array along all axes, like Fortran
+    /// `SHAPE(array)`.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_element(0.0, (3, -10..10, 1..=4));
+    /// assert_eq!(array.extents(), [3, 20, 4]);
+    /// ```
+    pub fn extents(&self) -> D::Extents {
+        self.dims.extents()
+    }
+
+    /// Get a view of the whole array.
+    pub fn view(&self) -> ArrayView<'_, S::Item, D> {
+        ArrayView::new(self.data.as_ref(), self.dims.clone(), self.dims.clone())
+    }
+
+    /// Get a mutable view of the whole array.
+    pub fn view_mut(&mut self) -> ArrayViewMut<'_, S::Item, D> {
+        ArrayViewMut::new(self.data.as_mut(), self.dims.clone(), self.dims.clone())
+    }
+
+    /// Get a view of the `dims` region of the array. The view is indexed
+    /// with the same indexes as the array.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_fn((-1..3, 0..4), |(i, j)| i * j);
+    /// let view = array.subview((0..2, 1..3));
+    /// assert_eq!(view[(1, 2)], 2);
+    /// ```
+    ///
+    /// # Panics
+    /// If `dims` is not contained in the array dimensions.
+    pub fn subview(&self, dims: D) -> ArrayView<'_, S::Item, D> {
+        ArrayView::new(self.data.as_ref(), self.dims.clone(), dims)
+    }
+
+    /// Get a mutable view of the `dims` region of the array. See
+    /// [`Array::subview`](#method.subview).
+    pub fn subview_mut(&mut self, dims: D) -> ArrayViewMut<'_, S::Item, D> {
+        ArrayViewMut::new(self.data.as_mut(), self.dims.clone(), dims)
+    }
+
     /// Flat (linear) iteration over array elements.
     ///
     /// ```
@@ -75,12 +253,14 @@ impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
     /// }
     /// // This will print "0 0 1 1 2 2"
     /// ```
+    #[allow(unknown_lints, mismatched_lifetime_syntaxes)]
     pub fn flat_iter(&self) -> Iter<S::Item> {
         self.data.as_ref().iter()
     }
 
     /// Flat (linear) iteration over mutable array elements. See the
     /// documentation for [`Array::flat_iter`](#method.flat_iter).
+    #[allow(unknown_lints, mismatched_lifetime_syntaxes)]
     pub fn flat_iter_mut(&mut self) -> IterMut<S::Item> {
         self.data.as_mut().iter_mut()
     }
@@ -95,13 +275,103 @@ impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage, S::Item: Clone {
     /// let array = Array::from_element(42, (2, 6));
     /// assert_eq!(array[(1, 4)], 42);
     /// ```
+    #[allow(clippy::redundant_field_names)]
     pub fn from_element(element: S::Item, dims: D) -> ArrayBase<S, D> {
-        let data = vec![element; dims.size()];
         ArrayBase {
-            data: S::from_vec(data),
+            data: S::from_iter(dims.size(), iter::repeat(element)),
             dims: dims,
         }
     }
+
+    /// Copy the array in a new array with the same indexes, stored with the
+    /// `dims` layout. This converts between row-major, column-major, tiled
+    /// and Morton storage.
+    ///
+    /// ```
+    /// use mudi::{Array, ColumnMajor, Tiled};
+    /// let array = Array::from_fn(Tiled::new((4, 4), [2, 2]), |(i, j)| 10 * i + j);
+    /// let column = array.to_layout(ColumnMajor((4, 4)));
+    /// assert_eq!(column[(2, 1)], 21);
+    /// assert_eq!(column.flat_iter().take(4).cloned().collect::<Vec<_>>(), vec![0, 10, 20, 30]);
+    /// ```
+    ///
+    /// # Panics
+    /// If `dims` do not have the same lower bounds and extents as the array.
+    pub fn to_layout<D2>(&self, dims: D2) -> Array<S::Item, D2> where D2: Dimensions<Index = D::Index> {
+        assert!(dims.lbounds().as_ref() == self.dims.lbounds().as_ref() &&
+                dims.extents().as_ref() == self.dims.extents().as_ref(),
+                "the new layout must have the same bounds as the array: expected {:?} to {:?}, got {:?} to {:?}",
+                self.dims.lbounds(), self.dims.ubounds(), dims.lbounds(), dims.ubounds());
+        Array::from_fn(dims, |index| self[index].clone())
+    }
+}
+
+impl<T, D> Array<T, D> where D: Dimensions {
+    /// Create a new array without initializing the elements. The elements can
+    /// then be written through indexing, and the array converted back with
+    /// [`assume_init`](#method.assume_init).
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let mut array = Array::<f64, _>::uninit((2, -1..1));
+    /// for i in 0..2 {
+    ///     for j in -1..1 {
+    ///         array[(i, j)].write(i as f64 * j as f64);
+    ///     }
+    /// }
+    /// let array = unsafe { array.assume_init() };
+    /// assert_eq!(array[(1, -1)], -1.0);
+    /// ```
+    pub fn uninit(dims: D) -> Array<MaybeUninit<T>, D> {
+        ArrayBase {
+            data: Box::new_uninit_slice(dims.size()),
+            dims,
+        }
+    }
+
+    /// Get the elements of this array in storage order, and the dimensions
+    #[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+    pub(crate) fn into_raw_parts(self) -> (Vec<T>, D) {
+        (self.data.into_vec(), self.dims)
+    }
+}
+
+impl<T, D> Array<MaybeUninit<T>, D> where D: Dimensions {
+    /// Convert an array of `MaybeUninit<T>` to an array of `T`.
+    ///
+    /// # Safety
+    /// All the elements of the array must have been initialized.
+    pub unsafe fn assume_init(self) -> Array<T, D> {
+        ArrayBase {
+            data: self.data.assume_init(),
+            dims: self.dims,
+        }
+    }
+}
+
+impl<T, D, const ALIGN: usize> AlignedArray<T, D, ALIGN> where D: Dimensions {
+    /// Create a new aligned array without initializing the elements. See
+    /// [`Array::uninit`](#method.uninit).
+    pub fn uninit(dims: D) -> AlignedArray<MaybeUninit<T>, D, ALIGN> {
+        ArrayBase {
+            data: AlignedStorage::uninit(dims.size()),
+            dims,
+        }
+    }
+}
+
+impl<T, D, const ALIGN: usize> AlignedArray<MaybeUninit<T>, D, ALIGN> where D: Dimensions {
+    /// Convert an aligned array of `MaybeUninit<T>` to an aligned array of
+    /// `T`.
+    ///
+    /// # Safety
+    /// All the elements of the array must have been initialized.
+    pub unsafe fn assume_init(self) -> AlignedArray<T, D, ALIGN> {
+        ArrayBase {
+            data: self.data.assume_init(),
+            dims: self.dims,
+        }
+    }
 }
 
 impl<S, D, I: Copy> Index<I> for ArrayBase<S, D>
@@ -119,20 +389,164 @@ impl<S, D, I: Copy> IndexMut<I> for ArrayBase<S, D>
     }
 }
 
+impl<T, D> IntoIterator for Array<T, D> where D: Dimensions {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Consume the array, iterating over its elements in storage order.
+    fn into_iter(self) -> IntoIter<T> {
+        self.data.into_vec().into_iter()
+    }
+}
+
+impl<'a, S, D> IntoIterator for &'a ArrayBase<S, D> where D: Dimensions, S: Storage {
+    type Item = &'a S::Item;
+    type IntoIter = Iter<'a, S::Item>;
+
+    fn into_iter(self) -> Iter<'a, S::Item> {
+        self.flat_iter()
+    }
+}
+
+impl<'a, S, D> IntoIterator for &'a mut ArrayBase<S, D> where D: Dimensions, S: Storage {
+    type Item = &'a mut S::Item;
+    type IntoIter = IterMut<'a, S::Item>;
+
+    fn into_iter(self) -> IterMut<'a, S::Item> {
+        self.flat_iter_mut()
+    }
+}
+
+/// Arrays are equal if their dimensions have the same lower bounds and
+/// extents, and if they have equal elements at the same indexes. Arrays with
+/// equal dimensions are compared in storage order, other arrays element by
+/// element.
 impl<S, D> PartialEq for ArrayBase<S, D>
     where D: Dimensions, S: Storage, S::Item: PartialEq {
     fn eq(&self, other: &ArrayBase<S, D>) -> bool {
-        if self.dims != other.dims {
+        if self.dims == other.dims {
+            self.data.as_ref() == other.data.as_ref()
+        } else if self.dims_cmp(other) != Ordering::Equal || self.dims.size() != other.dims.size() {
             false
         } else {
-            self.data.as_ref() == other.data.as_ref()
+            let (data, other_data) = (self.data.as_ref(), other.data.as_ref());
+            self.logical_order().iter().zip(&other.logical_order()).all(|(a, b)| {
+                a.0.as_ref() == b.0.as_ref() && data[a.1] == other_data[b.1]
+            })
         }
     }
 }
 
+impl<S, D> Eq for ArrayBase<S, D> where D: Dimensions + Eq, S: Storage, S::Item: Eq {}
+
+impl<S, D> Clone for ArrayBase<S, D> where D: Dimensions, S: Storage + Clone {
+    fn clone(&self) -> ArrayBase<S, D> {
+        ArrayBase {
+            data: self.data.clone(),
+            dims: self.dims.clone(),
+        }
+    }
+}
+
+/// The hash covers the lower bounds and extents of the dimensions, and the
+/// elements of the array sorted by index.
+impl<S, D> Hash for ArrayBase<S, D> where D: Dimensions, S: Storage, S::Item: Hash {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.dims.lbounds().as_ref().hash(state);
+        self.dims.extents().as_ref().hash(state);
+        let data = self.data.as_ref();
+        for &(_, offset) in &self.logical_order() {
+            data[offset].hash(state);
+        }
+    }
+}
+
+/// The default array uses the default dimensions, filled with the default
+/// element. For `usize` and ranges dimensions, this is an empty array.
+///
+/// ```
+/// use mudi::Array;
+/// let array = Array::<f64, (usize, usize)>::default();
+/// assert_eq!(array.shape(), (0, 0));
+/// ```
+impl<S, D> Default for ArrayBase<S, D> where D: Dimensions + Default, S: Storage, S::Item: Default {
+    fn default() -> ArrayBase<S, D> {
+        ArrayBase::from_fn(D::default(), |_| S::Item::default())
+    }
+}
+
+/// Arrays are ordered by comparing their elements lexicographically, sorted
+/// by index, then by comparing the indexes of the elements, and finally the
+/// lower bounds and the extents of their dimensions.
+///
+/// ```
+/// use mudi::Array;
+/// let a = Array::from_vector(vec![1, 2, 3, 4], (2, 2));
+/// let b = Array::from_vector(vec![1, 3], (1, 2));
+/// assert!(a < b);
+///
+/// let c = Array::from_vector(vec![1, 2, 3, 4], (2, 1..3));
+/// assert!(Array::from_vector(vec![1, 2, 3, 4], (2, 0..2)) < c);
+/// ```
+impl<S, D> PartialOrd for ArrayBase<S, D> where D: Dimensions, S: Storage, S::Item: PartialOrd {
+    fn partial_cmp(&self, other: &ArrayBase<S, D>) -> Option<Ordering> {
+        let (order, other_order) = (self.logical_order(), other.logical_order());
+        let (data, other_data) = (self.data.as_ref(), other.data.as_ref());
+        let elements = order.iter().map(|&(_, offset)| &data[offset]);
+        match elements.partial_cmp(other_order.iter().map(|&(_, offset)| &other_data[offset])) {
+            Some(Ordering::Equal) => {}
+            ordering => return ordering,
+        }
+        Some(self.indexes_cmp(&order, other, &other_order))
+    }
+}
+
+impl<S, D> Ord for ArrayBase<S, D> where D: Dimensions + Eq, S: Storage, S::Item: Ord {
+    fn cmp(&self, other: &ArrayBase<S, D>) -> Ordering {
+        let (order, other_order) = (self.logical_order(), other.logical_order());
+        let (data, other_data) = (self.data.as_ref(), other.data.as_ref());
+        let elements = order.iter().map(|&(_, offset)| &data[offset]);
+        elements.cmp(other_order.iter().map(|&(_, offset)| &other_data[offset]))
+                .then_with(|| self.indexes_cmp(&order, other, &other_order))
+    }
+}
+
+impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
+    /// Get the positions and the storage offset of all the elements, sorted
+    /// by positions. This order does not depend on how the dimensions store
+    /// the elements.
+    fn logical_order(&self) -> Vec<(D::Extents, usize)> {
+        let mut order = (0..self.dims.size())
+            .map(|offset| (self.dims.positions(self.dims.index(offset)), offset))
+            .collect::<Vec<_>>();
+        order.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()));
+        order
+    }
+
+    /// Compare the indexes of the elements of two arrays, given in logical
+    /// order, and then their dimensions.
+    fn indexes_cmp(&self, order: &[(D::Extents, usize)], other: &ArrayBase<S, D>,
+                   other_order: &[(D::Extents, usize)]) -> Ordering {
+        let positions = order.iter().map(|position| position.0.as_ref());
+        positions.cmp(other_order.iter().map(|position| position.0.as_ref()))
+                 .then_with(|| self.dims_cmp(other))
+    }
+
+    /// Compare the dimensions of two arrays, using the lower bounds and then
+    /// the extents of the axes.
+    fn dims_cmp(&self, other: &ArrayBase<S, D>) -> Ordering {
+        let lbounds = self.dims.lbounds();
+        let extents = self.dims.extents();
+        lbounds.as_ref().cmp(other.dims.lbounds().as_ref())
+               .then_with(|| extents.as_ref().cmp(other.dims.extents().as_ref()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashMap;
+    use {Banded, Tiled};
 
     #[test]
     fn indexing() {
@@ -154,6 +568,91 @@ mod tests {
         assert_eq!(a[3], 42);
     }
 
+    #[test]
+    fn into_iter() {
+        let mut a = Array::from_vector(vec![1, 2, 3, 4], (2, -1..1));
+        for value in &mut a {
+            *value *= 10;
+        }
+        assert_eq!((&a).into_iter().sum::<i32>(), 100);
+        assert_eq!(a.into_iter().collect::<Vec<_>>(), vec![10, 20, 30, 40]);
+
+        let a = Array::from_vector(vec![String::from("a"), String::from("b")], 2);
+        let strings: Vec<String> = a.into_iter().collect();
+        assert_eq!(strings, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn clone() {
+        let a = Array::from_fn((2, -1..1), |(i, j)| vec![i as i32, j]);
+        let mut b = a.clone();
+        b[(0, 0)].push(5);
+        assert_eq!(a[(0, 0)], vec![0, 0]);
+        assert_eq!(b[(0, 0)], vec![0, 0, 5]);
+
+        let a = AlignedArray::<_, _, 64>::from_element(3, (2, 2));
+        assert_eq!(a.clone(), a);
+    }
+
+    #[test]
+    fn hash() {
+        let mut map = HashMap::new();
+        map.insert(Array::from_vector(vec![1, 2], (1, 0..2)), "a");
+        map.insert(Array::from_vector(vec![1, 2], (1, 1..3)), "b");
+        map.insert(Array::from_vector(vec![1, 2], (1, 0..2)), "c");
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&Array::from_vector(vec![1, 2], (1, 0..2))], "c");
+    }
+
+    #[test]
+    fn default() {
+        let a = Array::<String, (usize, ::std::ops::Range<i32>)>::default();
+        assert_eq!(a.shape(), (0, 0..0));
+        assert_eq!(a.flat_iter().count(), 0);
+    }
+
+    #[test]
+    fn ordering() {
+        let a = Array::from_vector(vec![1, 2, 3], -1..2);
+        let b = Array::from_vector(vec![1, 2, 4], -1..2);
+        let c = Array::from_vector(vec![1, 2, 3], 0..3);
+        let d = Array::from_vector(vec![1, 2], 0..2);
+        assert!(a < b);
+        assert!(a < c);
+        assert!(d < a);
+        assert_eq!(a.cmp(&a), Ordering::Equal);
+
+        let mut arrays = vec![b.clone(), c.clone(), a.clone(), d.clone()];
+        arrays.sort();
+        assert_eq!(arrays, vec![d, a, c, b]);
+
+        let e = Array::from_vector(vec![1.0, f64::NAN], 2);
+        assert_eq!(e.partial_cmp(&e), None);
+
+        // Dimensions storing the same indexes in a different order compare
+        // the elements at each index
+        let f = Array::from_vector(vec![0, 1, 2, 3], Tiled::new((2, 2), [1, 2]));
+        let g = Array::from_vector(vec![0, 1, 2, 3], Tiled::new((2, 2), [2, 1]));
+        assert_eq!(f[(0, 1)], 1);
+        assert_eq!(g[(0, 1)], 2);
+        assert_ne!(f, g);
+        assert_eq!(f.cmp(&g), Ordering::Less);
+
+        let h = Array::from_fn(Tiled::new((2, 2), [2, 1]), |index| f[index]);
+        assert_eq!(f, h);
+        assert_eq!(f.cmp(&h), Ordering::Equal);
+        let mut map = HashMap::new();
+        map.insert(f.clone(), "f");
+        map.insert(h, "h");
+        assert_eq!(map.len(), 1);
+
+        // Dimensions with different indexes are never equal
+        let lower = Array::from_element(0, Banded { n: 4, kl: 2, ku: 0 });
+        let upper = Array::from_element(0, Banded { n: 4, kl: 0, ku: 2 });
+        assert_ne!(lower, upper);
+        assert_ne!(lower.cmp(&upper), Ordering::Equal);
+    }
+
     #[test]
     fn from_element() {
         let mut a = Array::from_element(678, (7, 7));
@@ -170,4 +669,88 @@ mod tests {
         let a = Array::from_element(678, (7, 7..10));
         assert_eq!(a.shape(), (7, 7..10));
     }
+
+    #[test]
+    fn bounds() {
+        let a = Array::from_element(0, (2, -3..3, 1_u64..=5, 4_i8..4));
+        assert_eq!(a.rank(), 4);
+        assert_eq!(a.lbounds(), [0, -3, 1, 4]);
+        assert_eq!(a.ubounds(), [1, 2, 5, 3]);
+        assert_eq!(a.extents(), [2, 6, 5, 0]);
+        assert_eq!(a.lbound(1), -3);
+        assert_eq!(a.ubound(3), 3);
+        assert_eq!(a.extent(2), 5);
+
+        let a = Array::from_element(0, 5);
+        assert_eq!(a.rank(), 1);
+        assert_eq!(a.ubounds(), [4]);
+    }
+
+    #[test]
+    #[should_panic(expected = "axis 2 is out of bound for 2-dimensional dimensions")]
+    fn bounds_invalid_axis() {
+        let a = Array::from_element(0, (2, 3));
+        a.lbound(2);
+    }
+
+    #[test]
+    fn from_fn() {
+        let a = Array::from_fn((3, -2..2), |(i, j)| (i, j));
+        for i in 0..3 {
+            for j in -2..2 {
+                assert_eq!(a[(i, j)], (i, j));
+            }
+        }
+
+        let mut calls = 0;
+        let a = AlignedArray::<_, _, 32>::from_fn(5..9, |i| {
+            calls += 1;
+            i * i
+        });
+        assert_eq!(calls, 4);
+        assert_eq!(a[7], 49);
+    }
+
+    #[test]
+    fn from_shape_iter() {
+        let a = Array::from_shape_iter((2, 2), vec!["a", "b", "c", "d"]);
+        assert_eq!(a[(1, 0)], "c");
+    }
+
+    #[test]
+    #[should_panic(expected = "Iterator length does not match the dimensions")]
+    fn from_shape_iter_too_long() {
+        let _ = Array::from_shape_iter((2, 2), 0..5);
+    }
+
+    #[test]
+    fn uninit() {
+        let mut a = Array::<String, _>::uninit((2, 3));
+        for value in a.flat_iter_mut() {
+            value.write(String::from("mudi"));
+        }
+        let a = unsafe { a.assume_init() };
+        assert_eq!(a[(1, 2)], "mudi");
+
+        let mut a = AlignedArray::<u16, _, 64>::uninit(-3..3);
+        for i in -3..3 {
+            a[i].write(i as u16);
+        }
+        let a = unsafe { a.assume_init() };
+        assert_eq!(a.flat_iter().as_slice().as_ptr() as usize % 64, 0);
+        assert_eq!(a[2], 2);
+    }
+
+    #[test]
+    fn aligned() {
+        let mut a = AlignedArray::<f64, _, 64>::from_element(2.0, (3, -4..4));
+        assert_eq!(a.flat_iter().as_slice().as_ptr() as usize % 64, 0);
+        assert_eq!(a[(2, -4)], 2.0);
+        a[(1, 3)] = 42.0;
+        assert_eq!(a[(1, 3)], 42.0);
+
+        let a = AlignedArray::<u8, _, 4096>::from_vector(vec![1, 2, 3, 4], (2, 2));
+        assert_eq!(a.flat_iter().as_slice().as_ptr() as usize % 4096, 0);
+        assert_eq!(a[(1, 0)], 3);
+    }
 }
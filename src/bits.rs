@@ -0,0 +1,446 @@
+use masked::Mask;
+use {Array, ArrayBase, Dimensions, Storage};
+
+/// Boolean values packed as bits, with 8 values in each byte. The values are
+/// stored from the lowest to the highest bit of each byte, and the unused
+/// bits of the last byte are always 0.
+///
+/// `BitStorage` does not implement [`Storage`](trait.Storage.html), which
+/// needs a slice of elements: use [`BitArray`](struct.BitArray.html) for
+/// multi-dimensional bit arrays.
+///
+/// ```
+/// use mudi::BitStorage;
+/// let mut bits = BitStorage::new(10);
+/// bits.set(9, true);
+/// assert!(bits.get(9));
+/// assert_eq!(bits.as_bytes(), &[0, 2]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitStorage {
+    bytes: Box<[u8]>,
+    len: usize,
+}
+
+/// Get the byte containing the bit at `offset`, and the mask for this bit
+#[inline(always)]
+fn locate(offset: usize) -> (usize, u8) {
+    (offset / 8, 1 << (offset % 8))
+}
+
+impl BitStorage {
+    /// Create a new storage for `len` values, all set to `false`
+    pub fn new(len: usize) -> BitStorage {
+        BitStorage {
+            bytes: vec![0; len.div_ceil(8)].into_boxed_slice(),
+            len,
+        }
+    }
+
+    /// Get the number of values in this storage
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if this storage does not contain any value
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get the bytes containing the values
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[inline(always)]
+    fn check(&self, offset: usize) {
+        assert!(offset < self.len, "offset out of bound: size is {} but offset is {}", self.len, offset);
+    }
+
+    /// Get the value at `offset`
+    #[inline(always)]
+    pub fn get(&self, offset: usize) -> bool {
+        self.check(offset);
+        let (byte, mask) = locate(offset);
+        self.bytes[byte] & mask != 0
+    }
+
+    /// Set the value at `offset`
+    #[inline(always)]
+    pub fn set(&mut self, offset: usize, value: bool) {
+        self.get_mut(offset).set(value);
+    }
+
+    /// Get a proxy to read and write the value at `offset`
+    #[inline(always)]
+    pub fn get_mut(&mut self, offset: usize) -> BitMut<'_> {
+        self.check(offset);
+        let (byte, mask) = locate(offset);
+        BitMut {
+            byte: &mut self.bytes[byte],
+            mask,
+        }
+    }
+
+    /// Count the values set to `true`
+    pub fn count_ones(&self) -> usize {
+        self.bytes.iter().map(|byte| byte.count_ones() as usize).sum()
+    }
+
+    /// Iterate over the offsets of the values set to `true`, in increasing
+    /// order.
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bytes.iter().enumerate().filter(|&(_, &byte)| byte != 0).flat_map(|(i, &byte)| {
+            (0..8).filter(move |bit| byte & (1 << bit) != 0).map(move |bit| 8 * i + bit)
+        })
+    }
+
+    /// Combine the bytes of two storages with the same length
+    fn zip_with<F>(&self, other: &BitStorage, function: F) -> BitStorage where F: Fn(u8, u8) -> u8 {
+        assert!(self.len == other.len, "bit storages lengths do not match: {} and {}", self.len, other.len);
+        BitStorage {
+            bytes: self.bytes.iter().zip(other.bytes.iter()).map(|(&a, &b)| function(a, b)).collect(),
+            len: self.len,
+        }
+    }
+
+    /// Get the element-wise logical and of two storages.
+    ///
+    /// # Panics
+    /// If the storages do not have the same length.
+    pub fn and(&self, other: &BitStorage) -> BitStorage {
+        self.zip_with(other, |a, b| a & b)
+    }
+
+    /// Get the element-wise logical or of two storages.
+    ///
+    /// # Panics
+    /// If the storages do not have the same length.
+    pub fn or(&self, other: &BitStorage) -> BitStorage {
+        self.zip_with(other, |a, b| a | b)
+    }
+
+    /// Get the element-wise logical exclusive or of two storages.
+    ///
+    /// # Panics
+    /// If the storages do not have the same length.
+    pub fn xor(&self, other: &BitStorage) -> BitStorage {
+        self.zip_with(other, |a, b| a ^ b)
+    }
+
+    /// Get the element-wise logical negation of this storage.
+    pub fn not(&self) -> BitStorage {
+        let mut bits = BitStorage {
+            bytes: self.bytes.iter().map(|&byte| !byte).collect(),
+            len: self.len,
+        };
+        // Keep the unused bits of the last byte to 0
+        let used = self.len % 8;
+        if used != 0 {
+            let last = bits.bytes.len() - 1;
+            bits.bytes[last] &= (1 << used) - 1;
+        }
+        bits
+    }
+}
+
+impl ::std::iter::FromIterator<bool> for BitStorage {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> BitStorage {
+        let mut bytes = Vec::new();
+        let mut len = 0;
+        for value in iter {
+            if len % 8 == 0 {
+                bytes.push(0);
+            }
+            if value {
+                let (byte, mask) = locate(len);
+                bytes[byte] |= mask;
+            }
+            len += 1;
+        }
+        BitStorage {
+            bytes: bytes.into_boxed_slice(),
+            len,
+        }
+    }
+}
+
+/// A mutable reference to a single bit in a
+/// [`BitStorage`](struct.BitStorage.html) or a
+/// [`BitArray`](struct.BitArray.html). This is used instead of `&mut bool`,
+/// which can not point inside of a byte.
+#[derive(Debug)]
+pub struct BitMut<'a> {
+    byte: &'a mut u8,
+    mask: u8,
+}
+
+impl<'a> BitMut<'a> {
+    /// Get the value of the bit
+    #[inline(always)]
+    pub fn get(&self) -> bool {
+        *self.byte & self.mask != 0
+    }
+
+    /// Set the value of the bit
+    #[inline(always)]
+    pub fn set(&mut self, value: bool) {
+        if value {
+            *self.byte |= self.mask;
+        } else {
+            *self.byte &= !self.mask;
+        }
+    }
+}
+
+/// Multi-dimensional array of booleans, packed in a
+/// [`BitStorage`](struct.BitStorage.html) with 8 values per byte. Values
+/// are read and written with `get` and `set`, since `Index` can not return a
+/// reference to a single bit. Bit arrays can be used as masks for the
+/// masked operations, like
+/// [`ArrayBase::where_assign`](struct.ArrayBase.html#method.where_assign).
+///
+/// ```
+/// use mudi::{Array, BitArray};
+/// let mut mask = BitArray::from_fn((-1..2, 3), |(i, j)| i + j as i32 == 1);
+/// mask.set((-1, 0), true);
+/// assert!(mask.get((0, 1)));
+/// assert_eq!(mask.count_ones(), 4);
+/// assert_eq!(mask.ones().collect::<Vec<_>>(), vec![(-1, 0), (-1, 2), (0, 1), (1, 0)]);
+///
+/// let mut array = Array::from_element(0, (-1..2, 3));
+/// array.where_assign(&mask, 1).elsewhere(-1);
+/// assert_eq!(array[(1, 0)], 1);
+/// assert_eq!(array[(1, 1)], -1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitArray<D> where D: Dimensions {
+    bits: BitStorage,
+    dims: D,
+}
+
+impl<D: Dimensions> BitArray<D> {
+    /// Create a new bit array with all the values set to `value`.
+    pub fn from_element(value: bool, dims: D) -> BitArray<D> {
+        let bits = BitStorage::new(dims.size());
+        let bits = if value { bits.not() } else { bits };
+        BitArray {
+            bits,
+            dims,
+        }
+    }
+
+    /// Create a new bit array by calling `function` with the index of each
+    /// value, in storage order.
+    pub fn from_fn<F>(dims: D, mut function: F) -> BitArray<D> where F: FnMut(D::Index) -> bool {
+        let bits = (0..dims.size()).map(|offset| function(dims.index(offset))).collect();
+        BitArray {
+            bits,
+            dims,
+        }
+    }
+
+    /// Get the shape of the array
+    pub fn shape(&self) -> D {
+        self.dims.clone()
+    }
+
+    /// Get the packed values, in storage order
+    pub fn bits(&self) -> &BitStorage {
+        &self.bits
+    }
+
+    /// Get the value at `index`
+    #[inline(always)]
+    pub fn get(&self, index: D::Index) -> bool {
+        self.bits.get(self.dims.offset(index))
+    }
+
+    /// Set the value at `index`
+    #[inline(always)]
+    pub fn set(&mut self, index: D::Index, value: bool) {
+        self.bits.set(self.dims.offset(index), value);
+    }
+
+    /// Get a proxy to read and write the value at `index`
+    ///
+    /// ```
+    /// use mudi::BitArray;
+    /// let mut flags = BitArray::from_element(false, 1..=8);
+    /// let mut flag = flags.get_mut(3);
+    /// flag.set(!flag.get());
+    /// assert!(flags.get(3));
+    /// ```
+    #[inline(always)]
+    pub fn get_mut(&mut self, index: D::Index) -> BitMut<'_> {
+        self.bits.get_mut(self.dims.offset(index))
+    }
+
+    /// Count the values set to `true`
+    pub fn count_ones(&self) -> usize {
+        self.bits.count_ones()
+    }
+
+    /// Iterate over the indexes of the values set to `true`, in storage order
+    pub fn ones(&self) -> impl Iterator<Item = D::Index> + '_ {
+        self.bits.ones().map(move |offset| self.dims.index(offset))
+    }
+
+    /// Check that `other` has the same dimensions as this array
+    fn check(&self, other: &BitArray<D>) {
+        assert!(self.dims == other.dims, "bit array dimensions do not match");
+    }
+
+    /// Get the element-wise logical and of two arrays.
+    ///
+    /// # Panics
+    /// If the arrays do not have the same dimensions.
+    pub fn and(&self, other: &BitArray<D>) -> BitArray<D> {
+        self.check(other);
+        BitArray {
+            bits: self.bits.and(&other.bits),
+            dims: self.shape(),
+        }
+    }
+
+    /// Get the element-wise logical or of two arrays.
+    ///
+    /// # Panics
+    /// If the arrays do not have the same dimensions.
+    pub fn or(&self, other: &BitArray<D>) -> BitArray<D> {
+        self.check(other);
+        BitArray {
+            bits: self.bits.or(&other.bits),
+            dims: self.shape(),
+        }
+    }
+
+    /// Get the element-wise logical exclusive or of two arrays.
+    ///
+    /// # Panics
+    /// If the arrays do not have the same dimensions.
+    pub fn xor(&self, other: &BitArray<D>) -> BitArray<D> {
+        self.check(other);
+        BitArray {
+            bits: self.bits.xor(&other.bits),
+            dims: self.shape(),
+        }
+    }
+
+    /// Get the element-wise logical negation of this array.
+    pub fn not(&self) -> BitArray<D> {
+        BitArray {
+            bits: self.bits.not(),
+            dims: self.shape(),
+        }
+    }
+
+    /// Convert the bit array to an array of `bool`
+    pub fn to_array(&self) -> Array<bool, D> {
+        Array::from_shape_iter(self.shape(), (0..self.bits.len()).map(|offset| self.bits.get(offset)))
+    }
+}
+
+impl<'a, S, D> From<&'a ArrayBase<S, D>> for BitArray<D> where D: Dimensions, S: Storage<Item = bool> {
+    fn from(array: &'a ArrayBase<S, D>) -> BitArray<D> {
+        BitArray {
+            bits: array.flat_iter().cloned().collect(),
+            dims: array.shape(),
+        }
+    }
+}
+
+impl<D: Dimensions> Mask<D> for BitArray<D> {
+    fn dims(&self) -> D {
+        self.shape()
+    }
+
+    #[inline(always)]
+    fn is_set(&self, offset: usize) -> bool {
+        self.bits.get(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn storage() {
+        let mut bits = BitStorage::new(13);
+        assert_eq!(bits.as_bytes().len(), 2);
+        bits.set(0, true);
+        bits.set(8, true);
+        bits.set(12, true);
+        bits.set(8, false);
+        assert_eq!(bits.as_bytes(), &[1, 16]);
+        assert_eq!(bits.count_ones(), 2);
+        assert_eq!(bits.ones().collect::<Vec<_>>(), vec![0, 12]);
+
+        let not = bits.not();
+        assert_eq!(not.as_bytes(), &[254, 15]);
+        assert_eq!(not.count_ones(), 11);
+        assert_eq!(bits.or(&not).count_ones(), 13);
+        assert_eq!(bits.and(&not).count_ones(), 0);
+        assert_eq!(bits.xor(&BitStorage::new(13)), bits);
+
+        let collected = (0..13).map(|i| i == 0 || i == 12).collect::<BitStorage>();
+        assert_eq!(collected, bits);
+        assert!(BitStorage::new(0).is_empty());
+    }
+
+    #[test]
+    fn array() {
+        let even = BitArray::from_fn((2, -2..3), |(i, j)| (i as i32 + j) % 2 == 0);
+        let positive = BitArray::from_fn((2, -2..3), |(_, j)| j > 0);
+        assert_eq!(even.count_ones(), 5);
+        assert_eq!(even.and(&positive).ones().collect::<Vec<_>>(), vec![(0, 2), (1, 1)]);
+        assert_eq!(even.or(&positive).count_ones(), 7);
+        assert_eq!(even.xor(&positive).count_ones(), 5);
+        assert_eq!(even.not().ones().collect::<Vec<_>>(), vec![(0, -1), (0, 1), (1, -2), (1, 0), (1, 2)]);
+        assert_eq!(BitArray::from_element(true, (2, -2..3)), even.or(&even.not()));
+
+        let array = even.to_array();
+        assert!(array[(1, -1)]);
+        assert_eq!(BitArray::from(&array), even);
+    }
+
+    #[test]
+    fn proxy() {
+        let mut flags = BitArray::from_element(false, (3, 3));
+        {
+            let mut flag = flags.get_mut((1, 2));
+            assert!(!flag.get());
+            flag.set(true);
+        }
+        assert!(flags.get((1, 2)));
+        flags.set((1, 2), false);
+        assert_eq!(flags.count_ones(), 0);
+    }
+
+    #[test]
+    fn masks() {
+        let mask = BitArray::from_fn(4, |i| i % 2 == 0);
+        let array = Array::from_vector(vec![1, 2, 3, 4], 4);
+        assert_eq!(array.pack(&mask), Array::from_vector(vec![1, 3], 2));
+
+        let mut array = Array::from_element(0, 4);
+        array.where_assign(&mask, 1).elsewhere(-1);
+        assert_eq!(array, Array::from_vector(vec![1, -1, 1, -1], 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "bit array dimensions do not match")]
+    fn dimensions() {
+        let a = BitArray::from_element(true, 0..4);
+        let b = BitArray::from_element(true, 1..5);
+        let _ = a.and(&b);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bound")]
+    fn out_of_bounds() {
+        let mut flags = BitArray::from_element(true, -1..1);
+        flags.set(1, false);
+    }
+}
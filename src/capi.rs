@@ -0,0 +1,476 @@
+//! C API for arrays with dynamic dimensions, enabled by the `capi` feature.
+//!
+//! The functions in this module are declared in the `include/mudi.h` header.
+//! The build script generates this header with cbindgen in `OUT_DIR`, and
+//! the tests check that the copy in `include/` is up to date; after changing
+//! this module, copy `$OUT_DIR/mudi.h` to `include/mudi.h`. To get a shared library usable
+//! from C, C++ or Python's `ctypes`, build the crate with
+//! `cargo rustc --release --features capi --crate-type cdylib`.
+//!
+//! Arrays are handled through pointers to the opaque `mudi_array_t` type,
+//! which can contain an `Array<T, DynDims>` for any of the element types in
+//! `mudi_dtype_t`. Indexes are given as one `ptrdiff_t` value per axis, and
+//! all the functions but `mudi_array_new` and `mudi_array_free` return a
+//! `mudi_status_t`. Arrays created in Rust can be given to C code with
+//! `Box::into_raw`:
+//!
+//! ```
+//! use mudi::{Array, DynDims};
+//! use mudi::capi::{mudi_array_t, mudi_array_free};
+//!
+//! let array = Array::from_element(1.0, DynDims::from_extents(&[3, 4]));
+//! let pointer = Box::into_raw(Box::new(mudi_array_t::from(array)));
+//! // ... give `pointer` to C code, which then calls
+//! unsafe { mudi_array_free(pointer) };
+//! ```
+//!
+//! # Safety
+//!
+//! All the functions are `unsafe`, since they can not check the pointers
+//! they are given beyond `NULL`. The arrays must come from `mudi_array_new`
+//! or `Box::into_raw`, and the other pointers must point to enough values
+//! for the rank of the array.
+
+#![allow(non_camel_case_types, clippy::missing_safety_doc)]
+
+use std::convert::TryFrom;
+use std::os::raw::c_void;
+use std::ptr;
+use std::slice;
+
+use {Array, Dimensions, DynDims};
+
+/// Maximal number of axes in an array, the same as `MAX_DYN_RANK`
+pub const MUDI_MAX_RANK: usize = 7;
+
+/// Status code returned by the functions of the C API
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum mudi_status_t {
+    /// The function succeeded
+    MUDI_SUCCESS = 0,
+    /// One of the pointers given to the function is null
+    MUDI_NULL_POINTER = 1,
+    /// The axis is not smaller than the array rank
+    MUDI_INVALID_AXIS = 2,
+    /// The index is outside of the array bounds
+    MUDI_OUT_OF_BOUNDS = 3,
+}
+
+/// Type of the elements in a `mudi_array_t`. Functions taking a type from C
+/// use an `uint32_t` containing one of these values.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum mudi_dtype_t {
+    /// `double` elements
+    MUDI_F64 = 0,
+    /// `float` elements
+    MUDI_F32 = 1,
+    /// `int32_t` elements
+    MUDI_I32 = 2,
+    /// `int64_t` elements
+    MUDI_I64 = 3,
+    /// `uint8_t` elements
+    MUDI_U8 = 4,
+}
+
+impl mudi_dtype_t {
+    /// Get the type with the given value, if any
+    fn from_raw(value: u32) -> Option<mudi_dtype_t> {
+        match value {
+            0 => Some(mudi_dtype_t::MUDI_F64),
+            1 => Some(mudi_dtype_t::MUDI_F32),
+            2 => Some(mudi_dtype_t::MUDI_I32),
+            3 => Some(mudi_dtype_t::MUDI_I64),
+            4 => Some(mudi_dtype_t::MUDI_U8),
+            _ => None,
+        }
+    }
+
+    /// Get the size of the elements of this type, in bytes
+    fn size(self) -> usize {
+        match self {
+            mudi_dtype_t::MUDI_F64 | mudi_dtype_t::MUDI_I64 => 8,
+            mudi_dtype_t::MUDI_F32 | mudi_dtype_t::MUDI_I32 => 4,
+            mudi_dtype_t::MUDI_U8 => 1,
+        }
+    }
+}
+
+enum Data {
+    F64(Array<f64, DynDims>),
+    F32(Array<f32, DynDims>),
+    I32(Array<i32, DynDims>),
+    I64(Array<i64, DynDims>),
+    U8(Array<u8, DynDims>),
+}
+
+/// An array with dynamic dimensions, and one of the element types in
+/// `mudi_dtype_t`. This type is opaque in C.
+pub struct mudi_array_t {
+    data: Data,
+}
+
+/// Call `$body` with `$array` bound to the array inside of `$data`,
+/// whatever its element type.
+macro_rules! dispatch {
+    ($data: expr, $array: ident => $body: expr) => {
+        match $data {
+            Data::F64($array) => $body,
+            Data::F32($array) => $body,
+            Data::I32($array) => $body,
+            Data::I64($array) => $body,
+            Data::U8($array) => $body,
+        }
+    };
+}
+
+macro_rules! from_array {
+    ($($ty: ty => $variant: ident),*) => {$(
+        impl From<Array<$ty, DynDims>> for mudi_array_t {
+            fn from(array: Array<$ty, DynDims>) -> mudi_array_t {
+                mudi_array_t {
+                    data: Data::$variant(array),
+                }
+            }
+        }
+    )*};
+}
+
+from_array!(f64 => F64, f32 => F32, i32 => I32, i64 => I64, u8 => U8);
+
+impl mudi_array_t {
+    fn dims(&self) -> DynDims {
+        dispatch!(&self.data, array => array.shape())
+    }
+
+    /// Get the storage offset of the element at `index`, which must contain
+    /// one value for each axis.
+    unsafe fn offset(&self, index: *const isize) -> Result<usize, mudi_status_t> {
+        let dims = self.dims();
+        if index.is_null() {
+            return Err(mudi_status_t::MUDI_NULL_POINTER);
+        }
+        let index = slice::from_raw_parts(index, dims.rank());
+        let mut offset = 0;
+        for (axis, &value) in index.iter().enumerate() {
+            // The subtraction can not overflow when `value >= lbound`
+            let position = value.wrapping_sub(dims.lbound(axis)) as usize;
+            if value < dims.lbound(axis) || position >= dims.extent(axis) {
+                return Err(mudi_status_t::MUDI_OUT_OF_BOUNDS);
+            }
+            offset = offset * dims.extent(axis) + position;
+        }
+        Ok(offset)
+    }
+
+    /// Get a pointer to the element at `offset` in storage, and the size of
+    /// the elements.
+    fn element(&mut self, offset: usize) -> (*mut c_void, usize) {
+        dispatch!(&mut self.data, array => {
+            let element = &mut array.flat_iter_mut().into_slice()[offset];
+            (element as *mut _ as *mut c_void, ::std::mem::size_of_val(element))
+        })
+    }
+}
+
+/// Check for null pointers in the arguments of a function, returning
+/// `MUDI_NULL_POINTER` if any of them is null.
+macro_rules! check_pointers {
+    ($($pointer: expr),+) => {
+        if $($pointer.is_null())||+ {
+            return mudi_status_t::MUDI_NULL_POINTER;
+        }
+    };
+}
+
+/// Create a new array of `dtype` elements with `rank` axes, all the
+/// elements being set to 0. `dtype` must be one of the `mudi_dtype_t`
+/// values. `extents` must contain `rank` values, and `lbounds` must be `NULL`
+/// or contain `rank` values. If `lbounds` is `NULL`, all the lower bounds
+/// are 0.
+///
+/// This function returns `NULL` if `dtype` is not a valid type, if `rank` is
+/// larger than `MUDI_MAX_RANK`, if `extents` is `NULL`, if the upper bound
+/// of an axis does not fit in `ptrdiff_t`, or if the array would be larger
+/// than `PTRDIFF_MAX` bytes. The array must be released with
+/// `mudi_array_free`.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_new(dtype: u32, rank: usize, lbounds: *const isize, extents: *const usize) -> *mut mudi_array_t {
+    let dtype = match mudi_dtype_t::from_raw(dtype) {
+        Some(dtype) => dtype,
+        None => return ptr::null_mut(),
+    };
+    if rank > MUDI_MAX_RANK || extents.is_null() {
+        return ptr::null_mut();
+    }
+    let extents = slice::from_raw_parts(extents, rank);
+    let zeros = [0; MUDI_MAX_RANK];
+    let lbounds = if lbounds.is_null() {
+        &zeros[..rank]
+    } else {
+        slice::from_raw_parts(lbounds, rank)
+    };
+
+    let mut bytes = Some(dtype.size());
+    for (&lbound, &extent) in lbounds.iter().zip(extents) {
+        let upper = isize::try_from(extent).ok().and_then(|extent| lbound.checked_add(extent));
+        if upper.is_none() {
+            return ptr::null_mut();
+        }
+        bytes = bytes.and_then(|bytes| bytes.checked_mul(extent));
+    }
+    if bytes.filter(|&bytes| bytes <= isize::MAX as usize).is_none() {
+        return ptr::null_mut();
+    }
+
+    let dims = DynDims::new(lbounds, extents);
+    let array = match dtype {
+        mudi_dtype_t::MUDI_F64 => mudi_array_t::from(Array::from_element(0.0, dims)),
+        mudi_dtype_t::MUDI_F32 => mudi_array_t::from(Array::from_element(0.0, dims)),
+        mudi_dtype_t::MUDI_I32 => mudi_array_t::from(Array::from_element(0, dims)),
+        mudi_dtype_t::MUDI_I64 => mudi_array_t::from(Array::from_element(0, dims)),
+        mudi_dtype_t::MUDI_U8 => mudi_array_t::from(Array::from_element(0, dims)),
+    };
+    Box::into_raw(Box::new(array))
+}
+
+/// Release the memory used by `array`. `array` can be `NULL`.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_free(array: *mut mudi_array_t) {
+    if !array.is_null() {
+        drop(Box::from_raw(array));
+    }
+}
+
+/// Get the type of the elements in `array` in `dtype`.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_dtype(array: *const mudi_array_t, dtype: *mut mudi_dtype_t) -> mudi_status_t {
+    check_pointers!(array, dtype);
+    *dtype = match (*array).data {
+        Data::F64(_) => mudi_dtype_t::MUDI_F64,
+        Data::F32(_) => mudi_dtype_t::MUDI_F32,
+        Data::I32(_) => mudi_dtype_t::MUDI_I32,
+        Data::I64(_) => mudi_dtype_t::MUDI_I64,
+        Data::U8(_) => mudi_dtype_t::MUDI_U8,
+    };
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Get the number of axes of `array` in `rank`.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_rank(array: *const mudi_array_t, rank: *mut usize) -> mudi_status_t {
+    check_pointers!(array, rank);
+    *rank = (*array).dims().rank();
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Get the total number of elements in `array` in `size`.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_size(array: *const mudi_array_t, size: *mut usize) -> mudi_status_t {
+    check_pointers!(array, size);
+    *size = (*array).dims().size();
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Get the lower bound of `array` along `axis` in `lbound`, with axes
+/// numbered from 0.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_lbound(array: *const mudi_array_t, axis: usize, lbound: *mut isize) -> mudi_status_t {
+    check_pointers!(array, lbound);
+    let dims = (*array).dims();
+    if axis >= dims.rank() {
+        return mudi_status_t::MUDI_INVALID_AXIS;
+    }
+    *lbound = dims.lbound(axis);
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Get the upper bound of `array` along `axis` in `ubound`, with axes
+/// numbered from 0. The upper bound is the last valid index along the axis.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_ubound(array: *const mudi_array_t, axis: usize, ubound: *mut isize) -> mudi_status_t {
+    check_pointers!(array, ubound);
+    let dims = (*array).dims();
+    if axis >= dims.rank() {
+        return mudi_status_t::MUDI_INVALID_AXIS;
+    }
+    *ubound = dims.ubound(axis);
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Get the number of elements of `array` along `axis` in `extent`, with
+/// axes numbered from 0.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_extent(array: *const mudi_array_t, axis: usize, extent: *mut usize) -> mudi_status_t {
+    check_pointers!(array, extent);
+    let dims = (*array).dims();
+    if axis >= dims.rank() {
+        return mudi_status_t::MUDI_INVALID_AXIS;
+    }
+    *extent = dims.extent(axis);
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Get the position in storage of the element at `index` in `offset`.
+/// `index` must contain one value for each axis of `array`.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_offset(array: *const mudi_array_t, index: *const isize, offset: *mut usize) -> mudi_status_t {
+    check_pointers!(array, offset);
+    match (*array).offset(index) {
+        Ok(value) => *offset = value,
+        Err(status) => return status,
+    }
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Get a pointer to the elements of `array` in `data`. The elements are
+/// stored in row-major order, and have the type given by
+/// `mudi_array_dtype`. The pointer is valid until the array is released.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_data(array: *mut mudi_array_t, data: *mut *mut c_void) -> mudi_status_t {
+    check_pointers!(array, data);
+    *data = dispatch!(&mut (*array).data, array => array.flat_iter_mut().into_slice().as_mut_ptr() as *mut c_void);
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Copy the element at `index` in `value`, which must point to a value of
+/// the type of the array elements.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_get(array: *mut mudi_array_t, index: *const isize, value: *mut c_void) -> mudi_status_t {
+    check_pointers!(array, value);
+    let offset = match (*array).offset(index) {
+        Ok(offset) => offset,
+        Err(status) => return status,
+    };
+    let (element, size) = (*array).element(offset);
+    ptr::copy_nonoverlapping(element as *const u8, value as *mut u8, size);
+    mudi_status_t::MUDI_SUCCESS
+}
+
+/// Set the element at `index` to `value`, which must point to a value of
+/// the type of the array elements.
+#[no_mangle]
+pub unsafe extern "C" fn mudi_array_set(array: *mut mudi_array_t, index: *const isize, value: *const c_void) -> mudi_status_t {
+    check_pointers!(array, value);
+    let offset = match (*array).offset(index) {
+        Ok(offset) => offset,
+        Err(status) => return status,
+    };
+    let (element, size) = (*array).element(offset);
+    ptr::copy_nonoverlapping(value as *const u8, element as *mut u8, size);
+    mudi_status_t::MUDI_SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::mudi_status_t::*;
+    use DynIndex;
+
+    #[test]
+    fn max_rank() {
+        assert_eq!(MUDI_MAX_RANK, ::MAX_DYN_RANK);
+    }
+
+    #[test]
+    fn create() {
+        unsafe {
+            let array = mudi_array_new(mudi_dtype_t::MUDI_F64 as u32, 2, [1, -1].as_ptr(), [3, 2].as_ptr());
+            assert!(!array.is_null());
+
+            let mut dtype = mudi_dtype_t::MUDI_U8;
+            assert_eq!(mudi_array_dtype(array, &mut dtype), MUDI_SUCCESS);
+            assert_eq!(dtype, mudi_dtype_t::MUDI_F64);
+
+            let mut value = 0;
+            assert_eq!(mudi_array_rank(array, &mut value), MUDI_SUCCESS);
+            assert_eq!(value, 2);
+            assert_eq!(mudi_array_size(array, &mut value), MUDI_SUCCESS);
+            assert_eq!(value, 6);
+            assert_eq!(mudi_array_extent(array, 1, &mut value), MUDI_SUCCESS);
+            assert_eq!(value, 2);
+            assert_eq!(mudi_array_extent(array, 2, &mut value), MUDI_INVALID_AXIS);
+
+            let mut bound = 0;
+            assert_eq!(mudi_array_lbound(array, 0, &mut bound), MUDI_SUCCESS);
+            assert_eq!(bound, 1);
+            assert_eq!(mudi_array_ubound(array, 1, &mut bound), MUDI_SUCCESS);
+            assert_eq!(bound, 0);
+
+            mudi_array_free(array);
+            mudi_array_free(ptr::null_mut());
+
+            assert!(mudi_array_new(mudi_dtype_t::MUDI_F64 as u32, 8, ptr::null(), [1; 8].as_ptr()).is_null());
+            assert!(mudi_array_new(mudi_dtype_t::MUDI_F64 as u32, 2, ptr::null(), ptr::null()).is_null());
+        }
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        unsafe {
+            assert!(mudi_array_new(5, 1, ptr::null(), [3].as_ptr()).is_null());
+            assert!(mudi_array_new(u32::MAX, 1, ptr::null(), [3].as_ptr()).is_null());
+            // Upper bound overflow
+            assert!(mudi_array_new(mudi_dtype_t::MUDI_U8 as u32, 1, [isize::MAX].as_ptr(), [2].as_ptr()).is_null());
+            assert!(mudi_array_new(mudi_dtype_t::MUDI_U8 as u32, 1, ptr::null(), [usize::MAX].as_ptr()).is_null());
+            // Size overflow
+            let extents = [1 << 20, 1 << 20, 1 << 20, 1 << 20];
+            assert!(mudi_array_new(mudi_dtype_t::MUDI_U8 as u32, 4, ptr::null(), extents.as_ptr()).is_null());
+            assert!(mudi_array_new(mudi_dtype_t::MUDI_F64 as u32, 1, ptr::null(), [isize::MAX as usize / 4].as_ptr()).is_null());
+
+            let array = mudi_array_new(mudi_dtype_t::MUDI_U8 as u32, 1, [isize::MAX - 3].as_ptr(), [3].as_ptr());
+            assert!(!array.is_null());
+            let mut offset = 0;
+            assert_eq!(mudi_array_offset(array, [isize::MAX - 1].as_ptr(), &mut offset), MUDI_SUCCESS);
+            assert_eq!(offset, 2);
+            assert_eq!(mudi_array_offset(array, [isize::MAX].as_ptr(), &mut offset), MUDI_OUT_OF_BOUNDS);
+            assert_eq!(mudi_array_offset(array, [isize::MIN].as_ptr(), &mut offset), MUDI_OUT_OF_BOUNDS);
+            mudi_array_free(array);
+        }
+    }
+
+    #[test]
+    fn header() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/mudi.h"));
+        let committed = include_str!("../include/mudi.h");
+        assert!(generated == committed, "include/mudi.h is out of date, copy it from $OUT_DIR/mudi.h");
+    }
+
+    #[test]
+    fn indexing() {
+        unsafe {
+            let array = mudi_array_new(mudi_dtype_t::MUDI_I32 as u32, 2, [1, -1].as_ptr(), [3, 2].as_ptr());
+
+            let mut offset = 0;
+            assert_eq!(mudi_array_offset(array, [2, 0].as_ptr(), &mut offset), MUDI_SUCCESS);
+            assert_eq!(offset, 3);
+            assert_eq!(mudi_array_offset(array, [4, 0].as_ptr(), &mut offset), MUDI_OUT_OF_BOUNDS);
+            assert_eq!(mudi_array_offset(array, ptr::null(), &mut offset), MUDI_NULL_POINTER);
+
+            let value: i32 = 42;
+            assert_eq!(mudi_array_set(array, [2, 0].as_ptr(), &value as *const i32 as *const c_void), MUDI_SUCCESS);
+            let mut value: i32 = 0;
+            assert_eq!(mudi_array_get(array, [2, 0].as_ptr(), &mut value as *mut i32 as *mut c_void), MUDI_SUCCESS);
+            assert_eq!(value, 42);
+
+            let mut data = ptr::null_mut();
+            assert_eq!(mudi_array_data(array, &mut data), MUDI_SUCCESS);
+            assert_eq!(*(data as *const i32).add(3), 42);
+
+            mudi_array_free(array);
+        }
+    }
+
+    #[test]
+    fn from_rust() {
+        let array = Array::from_fn(DynDims::new(&[-2], &[4]), |index: DynIndex| index[0] as f32);
+        let array = Box::into_raw(Box::new(mudi_array_t::from(array)));
+        unsafe {
+            let mut value = 0.0_f32;
+            assert_eq!(mudi_array_get(array, [1].as_ptr(), &mut value as *mut f32 as *mut c_void), MUDI_SUCCESS);
+            assert_eq!(value, 1.0);
+            mudi_array_free(array);
+        }
+    }
+}
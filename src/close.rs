@@ -0,0 +1,283 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+use {ArrayBase, Dimensions, Storage};
+
+/// Error returned by `ArrayBase::check_close` when two arrays are not
+/// approximately equal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CloseError<D: Dimensions, T> {
+    /// The arrays have different dimensions
+    Dimensions {
+        /// Dimensions of the first array
+        left: D,
+        /// Dimensions of the second array
+        right: D,
+    },
+    /// Some elements of the arrays are not close
+    Mismatch {
+        /// Number of elements which are not close
+        count: usize,
+        /// Index of the worst mismatch, with the largest absolute difference
+        index: D::Index,
+        /// Value of the worst mismatch in the first array
+        left: T,
+        /// Value of the worst mismatch in the second array
+        right: T,
+    },
+}
+
+impl<D, T> fmt::Display for CloseError<D, T> where D: Dimensions + fmt::Debug, D::Index: fmt::Debug, T: fmt::Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CloseError::Dimensions { ref left, ref right } => {
+                write!(f, "arrays dimensions do not match: {:?} and {:?}", left, right)
+            }
+            CloseError::Mismatch { count, ref index, ref left, ref right } => {
+                write!(f, "{} elements are not close, the largest difference is at index {:?}: {} and {}",
+                       count, index, left, right)
+            }
+        }
+    }
+}
+
+impl<D, T> Error for CloseError<D, T>
+    where D: Dimensions + fmt::Debug, D::Index: fmt::Debug, T: fmt::Debug + fmt::Display {}
+
+/// Floating point types, which can be compared with a tolerance in
+/// [`ArrayBase::all_close`](struct.ArrayBase.html#method.all_close).
+pub trait Float: Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    /// Positive infinity
+    const INFINITY: Self;
+    /// Get the absolute value of `self`
+    fn abs(self) -> Self;
+    /// Check if `self` is neither infinite nor `NaN`
+    fn is_finite(self) -> bool;
+}
+
+macro_rules! float_impl {
+    ($($float: ident),*) => {$(
+        impl Float for $float {
+            const INFINITY: $float = $float::INFINITY;
+
+            fn abs(self) -> $float {
+                $float::abs(self)
+            }
+
+            fn is_finite(self) -> bool {
+                $float::is_finite(self)
+            }
+        }
+    )*};
+}
+
+float_impl!(f32, f64);
+
+impl<F, S, D> ArrayBase<S, D> where D: Dimensions, S: Storage<Item = F>, F: Float {
+    /// Check if all the elements of this array are close to the elements of
+    /// `other`, *i.e.* if `|a - b| <= atol + rtol * |b|` for all pairs of
+    /// elements. `NaN` values are never close to anything, and infinite values
+    /// are only close to themselves. Arrays with different dimensions are
+    /// never close.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let a = Array::from_vector(vec![1.0, 2.0, 3.0], -1..2);
+    /// let b = Array::from_vector(vec![1.0, 2.0001, 3.0], -1..2);
+    /// assert!(a.all_close(&b, 1e-4, 0.0));
+    /// assert!(!a.all_close(&b, 1e-6, 1e-6));
+    /// ```
+    pub fn all_close<R>(&self, other: &ArrayBase<R, D>, rtol: F, atol: F) -> bool
+        where R: Storage<Item = F> {
+        self.check_close(other, rtol, atol).is_ok()
+    }
+
+    /// Check if all the elements of this array are close to the elements of
+    /// `other`, like [`ArrayBase::all_close`](#method.all_close). If they are
+    /// not, the error contains the number of mismatched elements, and the
+    /// index and values of the mismatch with the largest difference.
+    ///
+    /// ```
+    /// use mudi::{Array, CloseError};
+    /// let a = Array::from_vector(vec![1.0, 2.0, 3.0, 4.0], (2, -1..1));
+    /// let b = Array::from_vector(vec![1.5, 2.0, 3.0, 5.0], (2, -1..1));
+    ///
+    /// let error = a.check_close(&b, 0.0, 0.1).unwrap_err();
+    /// assert_eq!(error, CloseError::Mismatch { count: 2, index: (1, 0), left: 4.0, right: 5.0 });
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "2 elements are not close, the largest difference is at index (1, 0): 4 and 5"
+    /// );
+    /// ```
+    pub fn check_close<R>(&self, other: &ArrayBase<R, D>, rtol: F, atol: F) -> Result<(), CloseError<D, F>>
+        where R: Storage<Item = F> {
+        let dims = self.shape();
+        if dims != other.shape() {
+            return Err(CloseError::Dimensions {
+                left: dims,
+                right: other.shape(),
+            });
+        }
+
+        let mut count = 0;
+        let mut worst: Option<(usize, F)> = None;
+        for (offset, (&a, &b)) in self.flat_iter().zip(other.flat_iter()).enumerate() {
+            let difference = (a - b).abs();
+            let close = a == b || (difference.is_finite() && difference <= atol + rtol * b.abs());
+            if close {
+                continue;
+            }
+            count += 1;
+            let difference = if difference.is_finite() { difference } else { F::INFINITY };
+            match worst {
+                Some((_, largest)) if largest >= difference => {}
+                _ => worst = Some((offset, difference)),
+            }
+        }
+
+        match worst {
+            None => Ok(()),
+            Some((offset, _)) => Err(CloseError::Mismatch {
+                count,
+                index: dims.index(offset),
+                left: self.flat_iter().as_slice()[offset],
+                right: other.flat_iter().as_slice()[offset],
+            }),
+        }
+    }
+}
+
+
+#[cfg(feature = "approx")]
+mod approx_impl {
+    use approx::{AbsDiffEq, RelativeEq, UlpsEq};
+    use {ArrayBase, Dimensions, Storage};
+
+    /// Arrays are equal if they have the same dimensions, and if all the
+    /// elements are equal.
+    impl<S, D> AbsDiffEq for ArrayBase<S, D>
+        where D: Dimensions, S: Storage, S::Item: AbsDiffEq, <S::Item as AbsDiffEq>::Epsilon: Clone {
+        type Epsilon = <S::Item as AbsDiffEq>::Epsilon;
+
+        fn default_epsilon() -> Self::Epsilon {
+            S::Item::default_epsilon()
+        }
+
+        fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+            self.shape() == other.shape() &&
+            self.flat_iter().zip(other.flat_iter()).all(|(a, b)| a.abs_diff_eq(b, epsilon.clone()))
+        }
+    }
+
+    impl<S, D> RelativeEq for ArrayBase<S, D>
+        where D: Dimensions, S: Storage, S::Item: RelativeEq, <S::Item as AbsDiffEq>::Epsilon: Clone {
+        fn default_max_relative() -> Self::Epsilon {
+            S::Item::default_max_relative()
+        }
+
+        fn relative_eq(&self, other: &Self, epsilon: Self::Epsilon, max_relative: Self::Epsilon) -> bool {
+            self.shape() == other.shape() &&
+            self.flat_iter().zip(other.flat_iter()).all(|(a, b)| {
+                a.relative_eq(b, epsilon.clone(), max_relative.clone())
+            })
+        }
+    }
+
+    impl<S, D> UlpsEq for ArrayBase<S, D>
+        where D: Dimensions, S: Storage, S::Item: UlpsEq, <S::Item as AbsDiffEq>::Epsilon: Clone {
+        fn default_max_ulps() -> u32 {
+            S::Item::default_max_ulps()
+        }
+
+        fn ulps_eq(&self, other: &Self, epsilon: Self::Epsilon, max_ulps: u32) -> bool {
+            self.shape() == other.shape() &&
+            self.flat_iter().zip(other.flat_iter()).all(|(a, b)| a.ulps_eq(b, epsilon.clone(), max_ulps))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use Array;
+
+        #[test]
+        fn approx() {
+            let a = Array::from_vector(vec![1.0, 2.0, 3.0], 1..=3);
+            let b = Array::from_vector(vec![1.0, 2.0 + 1e-12, 3.0], 1..=3);
+            assert_abs_diff_eq!(a, b, epsilon = 1e-10);
+            assert_relative_eq!(a, b, max_relative = 1e-10);
+            assert_ulps_ne!(a, b);
+            assert_abs_diff_ne!(a, b, epsilon = 1e-14);
+
+            let c = Array::from_vector(vec![1.0, 2.0, 3.0], 0..=2);
+            assert_abs_diff_ne!(a, c);
+            assert_relative_ne!(a, c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Array, CloseError};
+
+    #[test]
+    fn all_close() {
+        let a = Array::from_fn((2, -1..2), |(i, j)| i as f64 + j as f64 / 10.0);
+        let mut b = a.mapv(|value| value * (1.0 + 1e-9));
+        assert!(a.all_close(&b, 1e-8, 0.0));
+        assert!(!a.all_close(&b, 1e-10, 0.0));
+        assert!(a.all_close(&a, 0.0, 0.0));
+
+        b[(0, 0)] = 1e-7;
+        assert!(a.all_close(&b, 1e-8, 1e-6));
+        assert!(!a.all_close(&b, 1e-8, 1e-8));
+
+        let c = Array::from_element(0.0_f32, (2, 0..3));
+        let d = Array::from_element(0.0_f32, (2, -1..2));
+        assert!(!c.all_close(&c.mapv(|v| v + 1.0), 0.1, 0.1));
+        assert_eq!(c.check_close(&Array::from_element(0.0, (2, 0..3)), 0.0, 0.0), Ok(()));
+
+        // Same extents, but different bounds
+        assert!(!c.all_close(&d, 1.0, 1.0));
+        assert_eq!(c.check_close(&d, 1.0, 1.0), Err(CloseError::Dimensions { left: (2, 0..3), right: (2, -1..2) }));
+    }
+
+    #[test]
+    fn special_values() {
+        let a = Array::from_vector(vec![f64::INFINITY, f64::NAN, 0.0], 3);
+        let b = Array::from_vector(vec![f64::INFINITY, 1.0, 0.0], 3);
+        let error = a.check_close(&b, 1.0, 1.0).unwrap_err();
+        match error {
+            CloseError::Mismatch { count, index, right, .. } => {
+                assert_eq!(count, 1);
+                assert_eq!(index, 1);
+                assert_eq!(right, 1.0);
+            }
+            _ => panic!("expected a mismatch"),
+        }
+        assert!(!a.all_close(&a, 1.0, 1.0));
+
+        let c = Array::from_vector(vec![-f64::INFINITY, 0.0, 0.0], 3);
+        assert!(!b.all_close(&c, 1.0, 1.0));
+    }
+
+    #[test]
+    fn worst_mismatch() {
+        let a = Array::from_element(0.0, (1..=2, 1..=3));
+        let mut b = Array::from_element(0.0, (1..=2, 1..=3));
+        b[(1, 2)] = 0.5;
+        b[(2, 3)] = -2.0;
+        b[(2, 1)] = 1.0;
+        let error = a.check_close(&b, 0.0, 0.1).unwrap_err();
+        assert_eq!(error, CloseError::Mismatch { count: 3, index: (2, 3), left: 0.0, right: -2.0 });
+    }
+
+    #[test]
+    fn dimensions_mismatch() {
+        let a = Array::from_element(0.0, (2, 0..3));
+        let b = Array::from_element(0.0, (2, 1..4));
+        let error = a.check_close(&b, 1.0, 1.0).unwrap_err();
+        assert_eq!(error.to_string(), "arrays dimensions do not match: (2, 0..3) and (2, 1..4)");
+        assert!(!a.all_close(&b, 1.0, 1.0));
+    }
+}
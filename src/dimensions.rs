@@ -1,20 +1,151 @@
-use std::ops::Range;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::convert::TryFrom;
+use std::ops::{Range, RangeInclusive};
+
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+use LayoutError;
 
 /// A (set of) dimensions in an array. `Dimensions` objects carry informations
 /// about there size, and how to convert a multi-dimensional index to a linear
 /// offset.
+///
+/// Implementations must provide `index` in addition to `offset` and `size`:
+/// there is no generic way to invert `offset`, and the arrays use `index` to
+/// visit the elements in storage order. This is a breaking change for
+/// implementations written against mudi 0.1, which only required `offset`
+/// and `size`.
 pub trait Dimensions: Clone + PartialEq {
     /// The type to use to index this dimension
     type Index: Copy;
+    /// Fixed-size array containing one bound for each axis
+    type Bounds: AsRef<[isize]> + AsMut<[isize]> + Default + Clone + fmt::Debug + PartialEq;
+    /// Fixed-size array containing one extent for each axis
+    type Extents: AsRef<[usize]> + AsMut<[usize]> + Default + Clone + fmt::Debug + PartialEq;
     /// Convert an index to a linear offset for this dimension size
     fn offset(&self, index: Self::Index) -> usize;
+    /// Convert a linear offset back to the corresponding index. This is the
+    /// inverse of `Dimensions::offset`.
+    fn index(&self, offset: usize) -> Self::Index;
     /// Get the number of elements in this dimension
     fn size(&self) -> usize;
+
+    /// Get the number of axes in this dimension, like Fortran `RANK(array)`.
+    /// The components of tuple dimensions are each a single axis.
+    fn rank(&self) -> usize {
+        Self::Bounds::default().as_ref().len()
+    }
+    /// Get the lower bound of `axis`, *i.e.* the first valid index along this
+    /// axis. This is the equivalent of Fortran `LBOUND(array, axis)`, with
+    /// axes numbered from 0.
+    fn lbound(&self, axis: usize) -> isize;
+    /// Get the upper bound of `axis`, *i.e.* the last valid index along this
+    /// axis, or `lbound - 1` if the axis is empty. This is the equivalent of
+    /// Fortran `UBOUND(array, axis)`, with axes numbered from 0.
+    fn ubound(&self, axis: usize) -> isize {
+        self.lbound(axis) + self.extent(axis) as isize - 1
+    }
+    /// Get the number of elements along `axis`. This is the equivalent of
+    /// Fortran `SIZE(array, axis)`, with axes numbered from 0.
+    fn extent(&self, axis: usize) -> usize;
+    /// Get the same dimensions, with `extent` elements along `axis`. The lower
+    /// bound of `axis` is kept, and the upper bound is moved accordingly.
+    fn with_extent(&self, axis: usize, extent: usize) -> Self;
+
+    /// Get the position of `index` along each axis, counted from the lower
+    /// bound of the axis.
+    fn positions(&self, index: Self::Index) -> Self::Extents;
+    /// Get the index at the given `positions` along each axis, counted from
+    /// the lower bound of the axis. This is the inverse of
+    /// `Dimensions::positions`.
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index;
+
+    /// Get the lower bounds of all the axes.
+    fn lbounds(&self) -> Self::Bounds {
+        let mut bounds = Self::Bounds::default();
+        for (axis, bound) in bounds.as_mut().iter_mut().enumerate() {
+            *bound = self.lbound(axis);
+        }
+        bounds
+    }
+    /// Get the upper bounds of all the axes.
+    fn ubounds(&self) -> Self::Bounds {
+        let mut bounds = Self::Bounds::default();
+        for (axis, bound) in bounds.as_mut().iter_mut().enumerate() {
+            *bound = self.ubound(axis);
+        }
+        bounds
+    }
+    /// Get the extents of all the axes.
+    fn extents(&self) -> Self::Extents {
+        let mut extents = Self::Extents::default();
+        for (axis, extent) in extents.as_mut().iter_mut().enumerate() {
+            *extent = self.extent(axis);
+        }
+        extents
+    }
+
+    /// Get the distance in storage between consecutive elements along each
+    /// axis, or `None` if the elements are not stored with constant strides.
+    /// Axes with less than two elements, where the distance does not matter,
+    /// use 0.
+    ///
+    /// The default implementation measures the distances with
+    /// `Dimensions::offset`, and must be overridden by dimensions without
+    /// strided storage, like packed matrices.
+    fn strides(&self) -> Option<Self::Extents> {
+        let mut strides = self.extents();
+        let mut positions = self.extents();
+        for position in positions.as_mut() {
+            *position = 0;
+        }
+        for (axis, stride) in strides.as_mut().iter_mut().enumerate() {
+            *stride = 0;
+            if self.extent(axis) > 1 && self.size() != 0 {
+                positions.as_mut()[axis] = 1;
+                *stride = self.offset(self.index_from_positions(positions.as_ref()));
+                positions.as_mut()[axis] = 0;
+            }
+        }
+        Some(strides)
+    }
+}
+
+/// Check that `axis` is a valid axis for dimensions of the given `rank`
+#[inline(always)]
+pub(crate) fn check_axis(axis: usize, rank: usize) {
+    assert!(axis < rank, "axis {} is out of bound for {}-dimensional dimensions", axis, rank);
+}
+
+/// Convert the lower bound of a range to `isize`
+#[inline(always)]
+fn lower_bound<T>(start: T) -> isize where isize: TryFrom<T> {
+    isize::try_from(start).unwrap_or_else(|_| panic!("lower bound overflows isize"))
+}
+
+/// Check that data with the given `strides` (in elements) is stored
+/// contiguously in the storage order of `dims`.
+#[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+pub(crate) fn check_strides<D: Dimensions>(dims: &D, found: &[isize]) -> Result<(), LayoutError> {
+    if dims.size() == 0 {
+        return Ok(());
+    }
+    let expected = dims.strides().expect("dimensions created from bounds should have strided storage");
+    for (axis, (&expected, &found)) in expected.as_ref().iter().zip(found).enumerate() {
+        let expected = expected as isize;
+        if dims.extent(axis) > 1 && found != expected {
+            return Err(LayoutError::Strides { axis, expected, found });
+        }
+    }
+    Ok(())
 }
 
 
 impl Dimensions for usize {
     type Index = usize;
+    type Bounds = [isize; 1];
+    type Extents = [usize; 1];
 
     #[inline(always)]
     fn offset(&self, index: usize) -> usize {
@@ -22,64 +153,639 @@ impl Dimensions for usize {
         index
     }
 
+    #[inline(always)]
+    fn index(&self, offset: usize) -> usize {
+        assert!(offset < *self, "offset out of bound: len is {} but offset is {}", self, offset);
+        offset
+    }
+
     #[inline(always)]
     fn size(&self) -> usize {
         *self
     }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, 1);
+        0
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, 1);
+        *self
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> usize {
+        check_axis(axis, 1);
+        extent
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 1] {
+        [self.offset(index)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 1, "expected 1 position, got {}", positions.len());
+        self.index(positions[0])
+    }
+}
+
+macro_rules! range_dimensions {
+    ($($int: ty => $unsigned: ty),*) => {$(
+        impl Dimensions for Range<$int> {
+            type Index = $int;
+            type Bounds = [isize; 1];
+            type Extents = [usize; 1];
+
+            #[inline(always)]
+            fn offset(&self, index: $int) -> usize {
+                assert!(self.start <= index && index < self.end,
+                        "index out of bound: range is ({}..{}) but index is {}",
+                        self.start, self.end, index);
+                // The difference always fits in the unsigned type, even if it
+                // overflows the signed one.
+                usize::try_from(index.wrapping_sub(self.start) as $unsigned).expect("range offset overflows usize")
+            }
+
+            #[inline(always)]
+            fn index(&self, offset: usize) -> $int {
+                assert!(offset < self.size(), "offset out of bound: size is {} but offset is {}",
+                        self.size(), offset);
+                self.start.wrapping_add(offset as $unsigned as $int)
+            }
+
+            #[inline(always)]
+            fn size(&self) -> usize {
+                if self.start < self.end {
+                    let size = self.end.wrapping_sub(self.start) as $unsigned;
+                    usize::try_from(size).expect("range size overflows usize")
+                } else {
+                    0
+                }
+            }
+
+            #[inline(always)]
+            fn lbound(&self, axis: usize) -> isize {
+                check_axis(axis, 1);
+                lower_bound(self.start)
+            }
+
+            #[inline(always)]
+            fn extent(&self, axis: usize) -> usize {
+                check_axis(axis, 1);
+                self.size()
+            }
+
+            fn with_extent(&self, axis: usize, extent: usize) -> Range<$int> {
+                check_axis(axis, 1);
+                let end = <$int>::try_from(self.start as i128 + extent as i128)
+                    .unwrap_or_else(|_| panic!("upper bound overflows {}", stringify!($int)));
+                self.start..end
+            }
+
+            #[inline(always)]
+            fn positions(&self, index: Self::Index) -> [usize; 1] {
+                [self.offset(index)]
+            }
+
+            #[inline(always)]
+            fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+                assert!(positions.len() == 1, "expected 1 position, got {}", positions.len());
+                self.index(positions[0])
+            }
+        }
+
+        /// Inclusive ranges follow the Fortran `lbound:ubound` convention,
+        /// where both bounds are valid indexes.
+        impl Dimensions for RangeInclusive<$int> {
+            type Index = $int;
+            type Bounds = [isize; 1];
+            type Extents = [usize; 1];
+
+            #[inline(always)]
+            fn offset(&self, index: $int) -> usize {
+                assert!(*self.start() <= index && index <= *self.end(),
+                        "index out of bound: range is ({}..={}) but index is {}",
+                        self.start(), self.end(), index);
+                usize::try_from(index.wrapping_sub(*self.start()) as $unsigned).expect("range offset overflows usize")
+            }
+
+            #[inline(always)]
+            fn index(&self, offset: usize) -> $int {
+                assert!(offset < self.size(), "offset out of bound: size is {} but offset is {}",
+                        self.size(), offset);
+                self.start().wrapping_add(offset as $unsigned as $int)
+            }
+
+            #[inline(always)]
+            fn size(&self) -> usize {
+                if self.start() <= self.end() {
+                    let size = self.end().wrapping_sub(*self.start()) as $unsigned;
+                    usize::try_from(size).ok()
+                        .and_then(|size| size.checked_add(1))
+                        .expect("range size overflows usize")
+                } else {
+                    0
+                }
+            }
+
+            #[inline(always)]
+            fn lbound(&self, axis: usize) -> isize {
+                check_axis(axis, 1);
+                lower_bound(*self.start())
+            }
+
+            #[inline(always)]
+            fn extent(&self, axis: usize) -> usize {
+                check_axis(axis, 1);
+                self.size()
+            }
+
+            fn with_extent(&self, axis: usize, extent: usize) -> RangeInclusive<$int> {
+                check_axis(axis, 1);
+                let start = *self.start();
+                if extent == 0 && start == <$int>::MIN {
+                    // There is no upper bound below the minimal value, so
+                    // the lower bound moves up by one for empty ranges
+                    return (start + 1)..=start;
+                }
+                let end = <$int>::try_from(start as i128 + extent as i128 - 1)
+                    .unwrap_or_else(|_| panic!("upper bound overflows {}", stringify!($int)));
+                start..=end
+            }
+
+            #[inline(always)]
+            fn positions(&self, index: Self::Index) -> [usize; 1] {
+                [self.offset(index)]
+            }
+
+            #[inline(always)]
+            fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+                assert!(positions.len() == 1, "expected 1 position, got {}", positions.len());
+                self.index(positions[0])
+            }
+        }
+    )*};
 }
 
-impl Dimensions for Range<usize> {
+range_dimensions!(
+    i8 => u8, i16 => u16, i32 => u32, i64 => u64, isize => usize,
+    u8 => u8, u16 => u16, u32 => u32, u64 => u64, usize => usize
+);
+
+/// A periodic dimension, wrapping out of bound indexes around instead of
+/// panicking. The index is taken modulo the dimension size, so that the
+/// index right after the last one is the first one, and the index right
+/// before the first one is the last one.
+///
+/// `Periodic` can be used as part of tuple dimensions, to get an array which
+/// is only periodic along some axes.
+///
+/// ```
+/// use mudi::{Array, Periodic};
+/// let array = Array::from_fn((Periodic(0..4), 0..3), |(i, j)| 10 * i + j);
+/// // Periodic along the first axis
+/// assert_eq!(array[(4, 2)], array[(0, 2)]);
+/// assert_eq!(array[(-1, 1)], array[(3, 1)]);
+/// ```
+///
+/// ```should_panic
+/// # use mudi::{Array, Periodic};
+/// # let array = Array::from_fn((Periodic(0..4), 0..3), |(i, j)| 10 * i + j);
+/// // But not along the second one
+/// array[(0, 3)];
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Periodic<D>(pub D);
+
+impl Dimensions for Periodic<usize> {
     type Index = usize;
+    type Bounds = [isize; 1];
+    type Extents = [usize; 1];
 
     #[inline(always)]
     fn offset(&self, index: usize) -> usize {
-        assert!(self.start <= index && index < self.end,
-                "index out of bound: range is ({}..{}) but index is {}",
-                self.start, self.end, index);
-        index - self.start
+        assert!(self.0 != 0, "can not index an empty periodic dimension");
+        index % self.0
+    }
+
+    #[inline(always)]
+    fn index(&self, offset: usize) -> usize {
+        self.0.index(offset)
     }
 
     #[inline(always)]
     fn size(&self) -> usize {
-        self.end - self.start
+        self.0.size()
+    }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        self.0.lbound(axis)
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        self.0.extent(axis)
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Periodic<usize> {
+        Periodic(self.0.with_extent(axis, extent))
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 1] {
+        [self.offset(index)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 1, "expected 1 position, got {}", positions.len());
+        self.index(positions[0])
+    }
+}
+
+macro_rules! periodic_range {
+    ($($int: ty),*) => {$(
+        impl Dimensions for Periodic<Range<$int>> {
+            type Index = $int;
+            type Bounds = [isize; 1];
+            type Extents = [usize; 1];
+
+            #[inline(always)]
+            fn offset(&self, index: $int) -> usize {
+                periodic_offset(index as i128, self.0.start as i128, self.size())
+            }
+
+            #[inline(always)]
+            fn index(&self, offset: usize) -> $int {
+                self.0.index(offset)
+            }
+
+            #[inline(always)]
+            fn size(&self) -> usize {
+                self.0.size()
+            }
+
+            #[inline(always)]
+            fn lbound(&self, axis: usize) -> isize {
+                self.0.lbound(axis)
+            }
+
+            #[inline(always)]
+            fn extent(&self, axis: usize) -> usize {
+                self.0.extent(axis)
+            }
+
+            fn with_extent(&self, axis: usize, extent: usize) -> Self {
+                Periodic(self.0.with_extent(axis, extent))
+            }
+
+            #[inline(always)]
+            fn positions(&self, index: Self::Index) -> [usize; 1] {
+                [self.offset(index)]
+            }
+
+            #[inline(always)]
+            fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+                assert!(positions.len() == 1, "expected 1 position, got {}", positions.len());
+                self.index(positions[0])
+            }
+        }
+
+        impl Dimensions for Periodic<RangeInclusive<$int>> {
+            type Index = $int;
+            type Bounds = [isize; 1];
+            type Extents = [usize; 1];
+
+            #[inline(always)]
+            fn offset(&self, index: $int) -> usize {
+                periodic_offset(index as i128, *self.0.start() as i128, self.size())
+            }
+
+            #[inline(always)]
+            fn index(&self, offset: usize) -> $int {
+                self.0.index(offset)
+            }
+
+            #[inline(always)]
+            fn size(&self) -> usize {
+                self.0.size()
+            }
+
+            #[inline(always)]
+            fn lbound(&self, axis: usize) -> isize {
+                self.0.lbound(axis)
+            }
+
+            #[inline(always)]
+            fn extent(&self, axis: usize) -> usize {
+                self.0.extent(axis)
+            }
+
+            fn with_extent(&self, axis: usize, extent: usize) -> Self {
+                Periodic(self.0.with_extent(axis, extent))
+            }
+
+            #[inline(always)]
+            fn positions(&self, index: Self::Index) -> [usize; 1] {
+                [self.offset(index)]
+            }
+
+            #[inline(always)]
+            fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+                assert!(positions.len() == 1, "expected 1 position, got {}", positions.len());
+                self.index(positions[0])
+            }
+        }
+    )*};
+}
+
+/// Get the offset of `index` in a periodic dimension starting at `start`
+/// with `size` elements.
+#[inline(always)]
+fn periodic_offset(index: i128, start: i128, size: usize) -> usize {
+    assert!(size != 0, "can not index an empty periodic dimension");
+    (index - start).rem_euclid(size as i128) as usize
+}
+
+periodic_range!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Column-major storage for the `D` dimensions, where the first axis varies
+/// the fastest in memory, like Fortran arrays. Indexing is the same as with
+/// `D`; only the order of the elements in storage changes.
+///
+/// ```
+/// use mudi::{Array, ColumnMajor};
+/// let array = Array::from_vector(vec![1, 2, 3, 4, 5, 6], ColumnMajor((1..=2, 3)));
+/// assert_eq!(array[(1, 0)], 1);
+/// assert_eq!(array[(2, 0)], 2);
+/// assert_eq!(array[(1, 1)], 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ColumnMajor<D>(pub D);
+
+impl<D: Dimensions> Dimensions for ColumnMajor<D> {
+    type Index = D::Index;
+    type Bounds = D::Bounds;
+    type Extents = D::Extents;
+
+    #[inline(always)]
+    fn offset(&self, index: Self::Index) -> usize {
+        let positions = self.0.positions(index);
+        let mut offset = 0;
+        let mut stride = 1;
+        for (axis, &position) in positions.as_ref().iter().enumerate() {
+            offset += stride * position;
+            stride *= self.0.extent(axis);
+        }
+        offset
+    }
+
+    #[inline(always)]
+    fn index(&self, mut offset: usize) -> Self::Index {
+        assert!(offset < self.size(), "offset out of bound: size is {} but offset is {}",
+                self.size(), offset);
+        let mut positions = self.0.extents();
+        for (axis, position) in positions.as_mut().iter_mut().enumerate() {
+            let extent = self.0.extent(axis);
+            *position = offset % extent;
+            offset /= extent;
+        }
+        self.0.index_from_positions(positions.as_ref())
+    }
+
+    #[inline(always)]
+    fn size(&self) -> usize {
+        self.0.size()
+    }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        self.0.lbound(axis)
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        self.0.extent(axis)
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        ColumnMajor(self.0.with_extent(axis, extent))
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> Self::Extents {
+        self.0.positions(index)
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        self.0.index_from_positions(positions)
+    }
+}
+
+/// Types with a finite number of values, which can be used as index for an
+/// `Enumerated` dimension. The easiest way to implement this trait is the
+/// [`index_enum!`](macro.index_enum.html) macro.
+pub trait IndexEnum: Copy + PartialEq {
+    /// The number of different values of this type
+    const COUNT: usize;
+    /// Get the position of this value, between 0 and `COUNT - 1`
+    fn ordinal(self) -> usize;
+    /// Get the value at the given position. This panics if `ordinal` is not
+    /// smaller than `COUNT`.
+    fn from_ordinal(ordinal: usize) -> Self;
+}
+
+/// A dimension indexed by the values of an `IndexEnum` type, such as an
+/// enum listing the components of a vector. The size of the dimension is the
+/// number of values of the type.
+///
+/// ```
+/// #[macro_use]
+/// extern crate mudi;
+/// use mudi::{Array, Enumerated};
+///
+/// index_enum! {
+///     enum Component { X, Y, Z }
+/// }
+///
+/// # fn main() {
+/// let mut array = Array::from_element(0.0, (Enumerated::<Component>::new(), 0..10));
+/// array[(Component::Y, 4)] = 3.0;
+/// assert_eq!(array[(Component::Y, 4)], 3.0);
+/// assert_eq!(array.flat_iter().count(), 30);
+/// # }
+/// ```
+pub struct Enumerated<E>(PhantomData<E>);
+
+impl<E> Enumerated<E> {
+    /// Create a new dimension for the `E` type
+    pub fn new() -> Enumerated<E> {
+        Enumerated(PhantomData)
+    }
+}
+
+impl<E> Default for Enumerated<E> {
+    fn default() -> Enumerated<E> {
+        Enumerated::new()
+    }
+}
+
+impl<E> Clone for Enumerated<E> {
+    fn clone(&self) -> Enumerated<E> {
+        *self
+    }
+}
+
+impl<E> Copy for Enumerated<E> {}
+
+impl<E> PartialEq for Enumerated<E> {
+    fn eq(&self, _: &Enumerated<E>) -> bool {
+        true
+    }
+}
+
+impl<E> Eq for Enumerated<E> {}
+
+impl<E> Hash for Enumerated<E> {
+    fn hash<H: Hasher>(&self, _: &mut H) {}
+}
+
+impl<E> fmt::Debug for Enumerated<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Enumerated<{}>", std::any::type_name::<E>())
     }
 }
 
-impl Dimensions for Range<i32> {
-    type Index = i32;
+impl<E: IndexEnum> Dimensions for Enumerated<E> {
+    type Index = E;
+    type Bounds = [isize; 1];
+    type Extents = [usize; 1];
 
     #[inline(always)]
-    fn offset(&self, index: i32) -> usize {
-        assert!(self.start <= index && index < self.end,
-                "index out of bound: range is ({}..{}) but index is {}",
-                self.start, self.end, index);
-        (index - self.start) as usize
+    fn offset(&self, index: E) -> usize {
+        let ordinal = index.ordinal();
+        assert!(ordinal < E::COUNT, "ordinal out of bound: count is {} but ordinal is {}", E::COUNT, ordinal);
+        ordinal
+    }
+
+    #[inline(always)]
+    fn index(&self, offset: usize) -> E {
+        assert!(offset < E::COUNT, "offset out of bound: size is {} but offset is {}", E::COUNT, offset);
+        E::from_ordinal(offset)
     }
 
     #[inline(always)]
     fn size(&self) -> usize {
-        (self.end - self.start) as usize
+        E::COUNT
+    }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, 1);
+        0
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, 1);
+        E::COUNT
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Enumerated<E> {
+        check_axis(axis, 1);
+        assert!(extent == E::COUNT, "can not change the extent of an enumerated dimension");
+        *self
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 1] {
+        [self.offset(index)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 1, "expected 1 position, got {}", positions.len());
+        self.index(positions[0])
     }
 }
 
 
-impl<A, B> Dimensions for (A, B) where A: Dimensions, B: Dimensions {
+impl<A, B> Dimensions for (A, B)
+    where A: Dimensions<Bounds = [isize; 1]>, B: Dimensions<Bounds = [isize; 1]> {
     type Index = (A::Index, B::Index);
+    type Bounds = [isize; 2];
+    type Extents = [usize; 2];
 
     #[inline(always)]
     fn offset(&self, index: Self::Index) -> usize {
         self.1.size() * self.0.offset(index.0) + self.1.offset(index.1)
     }
 
+    #[inline(always)]
+    fn index(&self, offset: usize) -> Self::Index {
+        (self.0.index(offset / self.1.size()), self.1.index(offset % self.1.size()))
+    }
+
     #[inline(always)]
     fn size(&self) -> usize {
         self.0.size() * self.1.size()
     }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, 2);
+        match axis {
+            0 => self.0.lbound(0),
+            1 => self.1.lbound(0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, 2);
+        match axis {
+            0 => self.0.extent(0),
+            1 => self.1.extent(0),
+            _ => unreachable!(),
+        }
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        check_axis(axis, 2);
+        let mut dims = self.clone();
+        match axis {
+            0 => dims.0 = self.0.with_extent(0, extent),
+            1 => dims.1 = self.1.with_extent(0, extent),
+            _ => unreachable!(),
+        }
+        dims
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 2] {
+        [self.0.offset(index.0), self.1.offset(index.1)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 2, "expected 2 positions, got {}", positions.len());
+        (self.0.index(positions[0]), self.1.index(positions[1]))
+    }
 }
 
 impl<A, B, C> Dimensions for (A, B, C)
-    where A: Dimensions, B: Dimensions, C: Dimensions {
+    where A: Dimensions<Bounds = [isize; 1]>, B: Dimensions<Bounds = [isize; 1]>,
+          C: Dimensions<Bounds = [isize; 1]> {
     type Index = (A::Index, B::Index, C::Index);
+    type Bounds = [isize; 3];
+    type Extents = [usize; 3];
 
     #[inline(always)]
     fn offset(&self, index: Self::Index) -> usize {
@@ -88,15 +794,74 @@ impl<A, B, C> Dimensions for (A, B, C)
         )
     }
 
+    #[inline(always)]
+    fn index(&self, offset: usize) -> Self::Index {
+        let rest = offset;
+        let c = self.2.index(rest % self.2.size());
+        let rest = rest / self.2.size();
+        let b = self.1.index(rest % self.1.size());
+        let rest = rest / self.1.size();
+        let a = self.0.index(rest);
+        (a, b, c)
+    }
+
     #[inline(always)]
     fn size(&self) -> usize {
         self.0.size() * self.1.size() * self.2.size()
     }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, 3);
+        match axis {
+            0 => self.0.lbound(0),
+            1 => self.1.lbound(0),
+            2 => self.2.lbound(0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, 3);
+        match axis {
+            0 => self.0.extent(0),
+            1 => self.1.extent(0),
+            2 => self.2.extent(0),
+            _ => unreachable!(),
+        }
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        check_axis(axis, 3);
+        let mut dims = self.clone();
+        match axis {
+            0 => dims.0 = self.0.with_extent(0, extent),
+            1 => dims.1 = self.1.with_extent(0, extent),
+            2 => dims.2 = self.2.with_extent(0, extent),
+            _ => unreachable!(),
+        }
+        dims
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 3] {
+        [self.0.offset(index.0), self.1.offset(index.1), self.2.offset(index.2)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 3, "expected 3 positions, got {}", positions.len());
+        (self.0.index(positions[0]), self.1.index(positions[1]), self.2.index(positions[2]))
+    }
 }
 
 impl<A, B, C, D> Dimensions for (A, B, C, D)
-    where A: Dimensions, B: Dimensions, C: Dimensions, D:Dimensions {
+    where A: Dimensions<Bounds = [isize; 1]>, B: Dimensions<Bounds = [isize; 1]>,
+          C: Dimensions<Bounds = [isize; 1]>, D: Dimensions<Bounds = [isize; 1]> {
     type Index = (A::Index, B::Index, C::Index, D::Index);
+    type Bounds = [isize; 4];
+    type Extents = [usize; 4];
 
     #[inline(always)]
     fn offset(&self, index: Self::Index) -> usize {
@@ -107,15 +872,80 @@ impl<A, B, C, D> Dimensions for (A, B, C, D)
         )
     }
 
+    #[inline(always)]
+    fn index(&self, offset: usize) -> Self::Index {
+        let rest = offset;
+        let d = self.3.index(rest % self.3.size());
+        let rest = rest / self.3.size();
+        let c = self.2.index(rest % self.2.size());
+        let rest = rest / self.2.size();
+        let b = self.1.index(rest % self.1.size());
+        let rest = rest / self.1.size();
+        let a = self.0.index(rest);
+        (a, b, c, d)
+    }
+
     #[inline(always)]
     fn size(&self) -> usize {
         self.0.size() * self.1.size() * self.2.size() * self.3.size()
     }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, 4);
+        match axis {
+            0 => self.0.lbound(0),
+            1 => self.1.lbound(0),
+            2 => self.2.lbound(0),
+            3 => self.3.lbound(0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, 4);
+        match axis {
+            0 => self.0.extent(0),
+            1 => self.1.extent(0),
+            2 => self.2.extent(0),
+            3 => self.3.extent(0),
+            _ => unreachable!(),
+        }
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        check_axis(axis, 4);
+        let mut dims = self.clone();
+        match axis {
+            0 => dims.0 = self.0.with_extent(0, extent),
+            1 => dims.1 = self.1.with_extent(0, extent),
+            2 => dims.2 = self.2.with_extent(0, extent),
+            3 => dims.3 = self.3.with_extent(0, extent),
+            _ => unreachable!(),
+        }
+        dims
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 4] {
+        [self.0.offset(index.0), self.1.offset(index.1), self.2.offset(index.2), self.3.offset(index.3)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 4, "expected 4 positions, got {}", positions.len());
+        (self.0.index(positions[0]), self.1.index(positions[1]), self.2.index(positions[2]), self.3.index(positions[3]))
+    }
 }
 
 impl<A, B, C, D, E> Dimensions for (A, B, C, D, E)
-    where A: Dimensions, B: Dimensions, C: Dimensions, D:Dimensions, E:Dimensions {
+    where A: Dimensions<Bounds = [isize; 1]>, B: Dimensions<Bounds = [isize; 1]>,
+          C: Dimensions<Bounds = [isize; 1]>, D: Dimensions<Bounds = [isize; 1]>,
+          E: Dimensions<Bounds = [isize; 1]> {
     type Index = (A::Index, B::Index, C::Index, D::Index, E::Index);
+    type Bounds = [isize; 5];
+    type Extents = [usize; 5];
 
     #[inline(always)]
     fn offset(&self, index: Self::Index) -> usize {
@@ -128,16 +958,85 @@ impl<A, B, C, D, E> Dimensions for (A, B, C, D, E)
         )
     }
 
+    #[inline(always)]
+    fn index(&self, offset: usize) -> Self::Index {
+        let rest = offset;
+        let e = self.4.index(rest % self.4.size());
+        let rest = rest / self.4.size();
+        let d = self.3.index(rest % self.3.size());
+        let rest = rest / self.3.size();
+        let c = self.2.index(rest % self.2.size());
+        let rest = rest / self.2.size();
+        let b = self.1.index(rest % self.1.size());
+        let rest = rest / self.1.size();
+        let a = self.0.index(rest);
+        (a, b, c, d, e)
+    }
+
     #[inline(always)]
     fn size(&self) -> usize {
         self.0.size() * self.1.size() * self.2.size() * self.3.size() * self.4.size()
     }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, 5);
+        match axis {
+            0 => self.0.lbound(0),
+            1 => self.1.lbound(0),
+            2 => self.2.lbound(0),
+            3 => self.3.lbound(0),
+            4 => self.4.lbound(0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, 5);
+        match axis {
+            0 => self.0.extent(0),
+            1 => self.1.extent(0),
+            2 => self.2.extent(0),
+            3 => self.3.extent(0),
+            4 => self.4.extent(0),
+            _ => unreachable!(),
+        }
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        check_axis(axis, 5);
+        let mut dims = self.clone();
+        match axis {
+            0 => dims.0 = self.0.with_extent(0, extent),
+            1 => dims.1 = self.1.with_extent(0, extent),
+            2 => dims.2 = self.2.with_extent(0, extent),
+            3 => dims.3 = self.3.with_extent(0, extent),
+            4 => dims.4 = self.4.with_extent(0, extent),
+            _ => unreachable!(),
+        }
+        dims
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 5] {
+        [self.0.offset(index.0), self.1.offset(index.1), self.2.offset(index.2), self.3.offset(index.3), self.4.offset(index.4)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 5, "expected 5 positions, got {}", positions.len());
+        (self.0.index(positions[0]), self.1.index(positions[1]), self.2.index(positions[2]), self.3.index(positions[3]), self.4.index(positions[4]))
+    }
 }
 
 impl<A, B, C, D, E, F> Dimensions for (A, B, C, D, E, F)
-    where A: Dimensions, B: Dimensions, C: Dimensions, D:Dimensions,
-          E: Dimensions, F:Dimensions{
+    where A: Dimensions<Bounds = [isize; 1]>, B: Dimensions<Bounds = [isize; 1]>,
+          C: Dimensions<Bounds = [isize; 1]>, D: Dimensions<Bounds = [isize; 1]>,
+          E: Dimensions<Bounds = [isize; 1]>, F: Dimensions<Bounds = [isize; 1]> {
     type Index = (A::Index, B::Index, C::Index, D::Index, E::Index, F::Index);
+    type Bounds = [isize; 6];
+    type Extents = [usize; 6];
 
     #[inline(always)]
     fn offset(&self, index: Self::Index) -> usize {
@@ -152,17 +1051,92 @@ impl<A, B, C, D, E, F> Dimensions for (A, B, C, D, E, F)
         )
     }
 
+    #[inline(always)]
+    fn index(&self, offset: usize) -> Self::Index {
+        let rest = offset;
+        let f = self.5.index(rest % self.5.size());
+        let rest = rest / self.5.size();
+        let e = self.4.index(rest % self.4.size());
+        let rest = rest / self.4.size();
+        let d = self.3.index(rest % self.3.size());
+        let rest = rest / self.3.size();
+        let c = self.2.index(rest % self.2.size());
+        let rest = rest / self.2.size();
+        let b = self.1.index(rest % self.1.size());
+        let rest = rest / self.1.size();
+        let a = self.0.index(rest);
+        (a, b, c, d, e, f)
+    }
+
     #[inline(always)]
     fn size(&self) -> usize {
         self.0.size() * self.1.size() * self.2.size() *
         self.3.size() * self.4.size() * self.5.size()
     }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, 6);
+        match axis {
+            0 => self.0.lbound(0),
+            1 => self.1.lbound(0),
+            2 => self.2.lbound(0),
+            3 => self.3.lbound(0),
+            4 => self.4.lbound(0),
+            5 => self.5.lbound(0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, 6);
+        match axis {
+            0 => self.0.extent(0),
+            1 => self.1.extent(0),
+            2 => self.2.extent(0),
+            3 => self.3.extent(0),
+            4 => self.4.extent(0),
+            5 => self.5.extent(0),
+            _ => unreachable!(),
+        }
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        check_axis(axis, 6);
+        let mut dims = self.clone();
+        match axis {
+            0 => dims.0 = self.0.with_extent(0, extent),
+            1 => dims.1 = self.1.with_extent(0, extent),
+            2 => dims.2 = self.2.with_extent(0, extent),
+            3 => dims.3 = self.3.with_extent(0, extent),
+            4 => dims.4 = self.4.with_extent(0, extent),
+            5 => dims.5 = self.5.with_extent(0, extent),
+            _ => unreachable!(),
+        }
+        dims
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 6] {
+        [self.0.offset(index.0), self.1.offset(index.1), self.2.offset(index.2), self.3.offset(index.3), self.4.offset(index.4), self.5.offset(index.5)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 6, "expected 6 positions, got {}", positions.len());
+        (self.0.index(positions[0]), self.1.index(positions[1]), self.2.index(positions[2]), self.3.index(positions[3]), self.4.index(positions[4]), self.5.index(positions[5]))
+    }
 }
 
 impl<A, B, C, D, E, F, G> Dimensions for (A, B, C, D, E, F, G)
-    where A: Dimensions, B: Dimensions, C: Dimensions, D:Dimensions,
-          E: Dimensions, F: Dimensions, G:Dimensions {
+    where A: Dimensions<Bounds = [isize; 1]>, B: Dimensions<Bounds = [isize; 1]>,
+          C: Dimensions<Bounds = [isize; 1]>, D: Dimensions<Bounds = [isize; 1]>,
+          E: Dimensions<Bounds = [isize; 1]>, F: Dimensions<Bounds = [isize; 1]>,
+          G: Dimensions<Bounds = [isize; 1]> {
     type Index = (A::Index, B::Index, C::Index, D::Index, E::Index, F::Index, G::Index);
+    type Bounds = [isize; 7];
+    type Extents = [usize; 7];
 
     #[inline(always)]
     fn offset(&self, index: Self::Index) -> usize {
@@ -179,13 +1153,277 @@ impl<A, B, C, D, E, F, G> Dimensions for (A, B, C, D, E, F, G)
         )
     }
 
+    #[inline(always)]
+    fn index(&self, offset: usize) -> Self::Index {
+        let rest = offset;
+        let g = self.6.index(rest % self.6.size());
+        let rest = rest / self.6.size();
+        let f = self.5.index(rest % self.5.size());
+        let rest = rest / self.5.size();
+        let e = self.4.index(rest % self.4.size());
+        let rest = rest / self.4.size();
+        let d = self.3.index(rest % self.3.size());
+        let rest = rest / self.3.size();
+        let c = self.2.index(rest % self.2.size());
+        let rest = rest / self.2.size();
+        let b = self.1.index(rest % self.1.size());
+        let rest = rest / self.1.size();
+        let a = self.0.index(rest);
+        (a, b, c, d, e, f, g)
+    }
+
     #[inline(always)]
     fn size(&self) -> usize {
         self.0.size() * self.1.size() * self.2.size() *
         self.3.size() * self.4.size() * self.5.size() * self.6.size()
     }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, 7);
+        match axis {
+            0 => self.0.lbound(0),
+            1 => self.1.lbound(0),
+            2 => self.2.lbound(0),
+            3 => self.3.lbound(0),
+            4 => self.4.lbound(0),
+            5 => self.5.lbound(0),
+            6 => self.6.lbound(0),
+            _ => unreachable!(),
+        }
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, 7);
+        match axis {
+            0 => self.0.extent(0),
+            1 => self.1.extent(0),
+            2 => self.2.extent(0),
+            3 => self.3.extent(0),
+            4 => self.4.extent(0),
+            5 => self.5.extent(0),
+            6 => self.6.extent(0),
+            _ => unreachable!(),
+        }
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        check_axis(axis, 7);
+        let mut dims = self.clone();
+        match axis {
+            0 => dims.0 = self.0.with_extent(0, extent),
+            1 => dims.1 = self.1.with_extent(0, extent),
+            2 => dims.2 = self.2.with_extent(0, extent),
+            3 => dims.3 = self.3.with_extent(0, extent),
+            4 => dims.4 = self.4.with_extent(0, extent),
+            5 => dims.5 = self.5.with_extent(0, extent),
+            6 => dims.6 = self.6.with_extent(0, extent),
+            _ => unreachable!(),
+        }
+        dims
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> [usize; 7] {
+        [self.0.offset(index.0), self.1.offset(index.1), self.2.offset(index.2), self.3.offset(index.3), self.4.offset(index.4), self.5.offset(index.5), self.6.offset(index.6)]
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        assert!(positions.len() == 7, "expected 7 positions, got {}", positions.len());
+        (self.0.index(positions[0]), self.1.index(positions[1]), self.2.index(positions[2]), self.3.index(positions[3]), self.4.index(positions[4]), self.5.index(positions[5]), self.6.index(positions[6]))
+    }
+}
+
+/// Dimensions which can be extended with a new axis at position `AXIS`,
+/// raising the rank by one. The new axis is always a `usize` dimension. The
+/// axis is a const parameter since it changes the index type.
+///
+/// ```
+/// use mudi::{Dimensions, InsertAxis};
+/// let dims = InsertAxis::<1>::insert_axis(&(3, -2..2), 5);
+/// assert_eq!(dims, (3, 5, -2..2));
+/// assert_eq!(dims.size(), 60);
+/// ```
+pub trait InsertAxis<const AXIS: usize>: Dimensions {
+    /// The dimensions with the new axis
+    type Output: Dimensions;
+    /// Insert a new axis with `extent` elements at position `AXIS`.
+    fn insert_axis(&self, extent: usize) -> Self::Output;
+    /// Split an index of the extended dimensions into the index along the
+    /// new axis, and the index in these dimensions.
+    fn split_index(index: <Self::Output as Dimensions>::Index) -> (usize, Self::Index);
+}
+
+impl<D> InsertAxis<0> for D where D: Dimensions<Bounds = [isize; 1]> {
+    type Output = (usize, D);
+
+    fn insert_axis(&self, extent: usize) -> (usize, D) {
+        (extent, self.clone())
+    }
+
+    fn split_index(index: (usize, D::Index)) -> (usize, D::Index) {
+        index
+    }
+}
+
+impl<D> InsertAxis<1> for D where D: Dimensions<Bounds = [isize; 1]> {
+    type Output = (D, usize);
+
+    fn insert_axis(&self, extent: usize) -> (D, usize) {
+        (self.clone(), extent)
+    }
+
+    fn split_index(index: (D::Index, usize)) -> (usize, D::Index) {
+        (index.1, index.0)
+    }
+}
+
+macro_rules! insert_axis {
+    (<$axis: tt> ($($dims: ident),+) => $output: ty, |($($name: ident),+), $new: ident| $inserted: tt) => {
+        impl<$($dims),+> InsertAxis<$axis> for ($($dims),+) where $($dims: Dimensions<Bounds = [isize; 1]>),+ {
+            type Output = $output;
+
+            fn insert_axis(&self, extent: usize) -> $output {
+                let ($($name),+) = self.clone();
+                let $new = extent;
+                $inserted
+            }
+
+            fn split_index(index: <$output as Dimensions>::Index) -> (usize, Self::Index) {
+                let $inserted = index;
+                ($new, ($($name),+))
+            }
+        }
+    };
+}
+
+insert_axis!(<0> (A, B) => (usize, A, B), |(a, b), n| (n, a, b));
+insert_axis!(<1> (A, B) => (A, usize, B), |(a, b), n| (a, n, b));
+insert_axis!(<2> (A, B) => (A, B, usize), |(a, b), n| (a, b, n));
+
+insert_axis!(<0> (A, B, C) => (usize, A, B, C), |(a, b, c), n| (n, a, b, c));
+insert_axis!(<1> (A, B, C) => (A, usize, B, C), |(a, b, c), n| (a, n, b, c));
+insert_axis!(<2> (A, B, C) => (A, B, usize, C), |(a, b, c), n| (a, b, n, c));
+insert_axis!(<3> (A, B, C) => (A, B, C, usize), |(a, b, c), n| (a, b, c, n));
+
+insert_axis!(<0> (A, B, C, D) => (usize, A, B, C, D), |(a, b, c, d), n| (n, a, b, c, d));
+insert_axis!(<1> (A, B, C, D) => (A, usize, B, C, D), |(a, b, c, d), n| (a, n, b, c, d));
+insert_axis!(<2> (A, B, C, D) => (A, B, usize, C, D), |(a, b, c, d), n| (a, b, n, c, d));
+insert_axis!(<3> (A, B, C, D) => (A, B, C, usize, D), |(a, b, c, d), n| (a, b, c, n, d));
+insert_axis!(<4> (A, B, C, D) => (A, B, C, D, usize), |(a, b, c, d), n| (a, b, c, d, n));
+
+insert_axis!(<0> (A, B, C, D, E) => (usize, A, B, C, D, E), |(a, b, c, d, e), n| (n, a, b, c, d, e));
+insert_axis!(<1> (A, B, C, D, E) => (A, usize, B, C, D, E), |(a, b, c, d, e), n| (a, n, b, c, d, e));
+insert_axis!(<2> (A, B, C, D, E) => (A, B, usize, C, D, E), |(a, b, c, d, e), n| (a, b, n, c, d, e));
+insert_axis!(<3> (A, B, C, D, E) => (A, B, C, usize, D, E), |(a, b, c, d, e), n| (a, b, c, n, d, e));
+insert_axis!(<4> (A, B, C, D, E) => (A, B, C, D, usize, E), |(a, b, c, d, e), n| (a, b, c, d, n, e));
+insert_axis!(<5> (A, B, C, D, E) => (A, B, C, D, E, usize), |(a, b, c, d, e), n| (a, b, c, d, e, n));
+
+insert_axis!(<0> (A, B, C, D, E, F) => (usize, A, B, C, D, E, F), |(a, b, c, d, e, f), n| (n, a, b, c, d, e, f));
+insert_axis!(<1> (A, B, C, D, E, F) => (A, usize, B, C, D, E, F), |(a, b, c, d, e, f), n| (a, n, b, c, d, e, f));
+insert_axis!(<2> (A, B, C, D, E, F) => (A, B, usize, C, D, E, F), |(a, b, c, d, e, f), n| (a, b, n, c, d, e, f));
+insert_axis!(<3> (A, B, C, D, E, F) => (A, B, C, usize, D, E, F), |(a, b, c, d, e, f), n| (a, b, c, n, d, e, f));
+insert_axis!(<4> (A, B, C, D, E, F) => (A, B, C, D, usize, E, F), |(a, b, c, d, e, f), n| (a, b, c, d, n, e, f));
+insert_axis!(<5> (A, B, C, D, E, F) => (A, B, C, D, E, usize, F), |(a, b, c, d, e, f), n| (a, b, c, d, e, n, f));
+insert_axis!(<6> (A, B, C, D, E, F) => (A, B, C, D, E, F, usize), |(a, b, c, d, e, f), n| (a, b, c, d, e, f, n));
+
+/// Dimensions which can be created from the lower bound and the extent of
+/// each axis, for example to describe arrays coming from other libraries.
+///
+/// ```
+/// use mudi::FromBounds;
+/// assert_eq!(FromBounds::from_bounds(&[-1, 0], &[3, 4]), Some((-1..2, 4)));
+/// // `usize` dimensions always start at 0
+/// assert_eq!(<(usize, usize)>::from_bounds(&[-1, 0], &[3, 4]), None);
+/// ```
+pub trait FromBounds: Dimensions {
+    /// Create dimensions with the given lower bounds and extents, or `None`
+    /// if they can not be represented by this type.
+    fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<Self>;
+}
+
+impl FromBounds for usize {
+    fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<usize> {
+        match (lbounds, extents) {
+            (&[0], &[extent]) => Some(extent),
+            _ => None,
+        }
+    }
 }
 
+macro_rules! from_bounds_range {
+    ($($int: ty),*) => {$(
+        impl FromBounds for Range<$int> {
+            fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<Range<$int>> {
+                let (lower, extent) = match (lbounds, extents) {
+                    (&[lower], &[extent]) => (lower, extent),
+                    _ => return None,
+                };
+                let start = <$int>::try_from(lower).ok()?;
+                let end = <$int>::try_from(lower as i128 + extent as i128).ok()?;
+                Some(start..end)
+            }
+        }
+
+        impl FromBounds for RangeInclusive<$int> {
+            fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<RangeInclusive<$int>> {
+                let (lower, extent) = match (lbounds, extents) {
+                    (&[lower], &[extent]) => (lower, extent),
+                    _ => return None,
+                };
+                let start = <$int>::try_from(lower).ok()?;
+                let end = <$int>::try_from(lower as i128 + extent as i128 - 1).ok()?;
+                Some(start..=end)
+            }
+        }
+    )*};
+}
+
+from_bounds_range!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<D> FromBounds for Periodic<D> where D: FromBounds, Periodic<D>: Dimensions {
+    fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<Periodic<D>> {
+        D::from_bounds(lbounds, extents).map(Periodic)
+    }
+}
+
+impl<D> FromBounds for ColumnMajor<D> where D: FromBounds {
+    fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<ColumnMajor<D>> {
+        D::from_bounds(lbounds, extents).map(ColumnMajor)
+    }
+}
+
+impl<E: IndexEnum> FromBounds for Enumerated<E> {
+    fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<Enumerated<E>> {
+        match (lbounds, extents) {
+            (&[0], &[extent]) if extent == E::COUNT => Some(Enumerated::new()),
+            _ => None,
+        }
+    }
+}
+
+macro_rules! from_bounds_tuple {
+    ($rank: expr; $($dims: ident: $axis: expr),+) => {
+        impl<$($dims),+> FromBounds for ($($dims,)+) where $($dims: FromBounds<Bounds = [isize; 1]>),+ {
+            fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<Self> {
+                if lbounds.len() != $rank || extents.len() != $rank {
+                    return None;
+                }
+                Some(($($dims::from_bounds(&lbounds[$axis..$axis + 1], &extents[$axis..$axis + 1])?,)+))
+            }
+        }
+    };
+}
+
+from_bounds_tuple!(2; A: 0, B: 1);
+from_bounds_tuple!(3; A: 0, B: 1, C: 2);
+from_bounds_tuple!(4; A: 0, B: 1, C: 2, D: 3);
+from_bounds_tuple!(5; A: 0, B: 1, C: 2, D: 3, E: 4);
+from_bounds_tuple!(6; A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+from_bounds_tuple!(7; A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+
 #[cfg(test)]
 mod tests {
     pub use super::*;
@@ -197,6 +1435,7 @@ mod tests {
             let dim = 4..10;
             assert_eq!(dim.size(), 6);
             assert_eq!(dim.offset(5), 1);
+            assert_eq!(dim.index(1), 5);
         }
 
         #[test]
@@ -222,6 +1461,15 @@ mod tests {
             assert_eq!(dim.size(), 14);
             assert_eq!(dim.offset(5), 9);
             assert_eq!(dim.offset(-3), 1);
+            assert_eq!(dim.index(1), -3);
+            assert_eq!(dim.index(9), 5);
+        }
+
+        #[test]
+        #[should_panic]
+        fn offset_bigger_than_size() {
+            let dim = -4..10;
+            dim.index(14);
         }
 
         #[test]
@@ -239,6 +1487,386 @@ mod tests {
         }
     }
 
+    mod bounds {
+        use super::*;
+        #[test]
+        fn one_dimensional() {
+            assert_eq!(5.rank(), 1);
+            assert_eq!(5.lbound(0), 0);
+            assert_eq!(5.ubound(0), 4);
+            assert_eq!(5.extent(0), 5);
+
+            let dim = -4..10;
+            assert_eq!(dim.lbound(0), -4);
+            assert_eq!(dim.ubound(0), 9);
+            assert_eq!(dim.extent(0), 14);
+
+            let dim = -4_i32..=10;
+            assert_eq!(dim.lbounds(), [-4]);
+            assert_eq!(dim.ubounds(), [10]);
+            assert_eq!(dim.extents(), [15]);
+
+            let dim = Periodic(3_u8..7);
+            assert_eq!(dim.lbound(0), 3);
+            assert_eq!(dim.ubound(0), 6);
+
+            // empty dimensions
+            let dim = 4..4;
+            assert_eq!(dim.lbound(0), 4);
+            assert_eq!(dim.ubound(0), 3);
+            assert_eq!(dim.extent(0), 0);
+        }
+
+        #[test]
+        fn tuples() {
+            let dim = (3, -10..10);
+            assert_eq!(dim.rank(), 2);
+            assert_eq!(dim.lbounds(), [0, -10]);
+            assert_eq!(dim.ubounds(), [2, 9]);
+            assert_eq!(dim.extents(), [3, 20]);
+
+            let dim = (1, 2, 3, 4, 5, 6, -7..=7);
+            assert_eq!(dim.rank(), 7);
+            assert_eq!(dim.extents(), [1, 2, 3, 4, 5, 6, 15]);
+            assert_eq!(dim.lbound(6), -7);
+            assert_eq!(dim.ubound(6), 7);
+        }
+
+        #[test]
+        fn positions() {
+            let dim = -4_i32..10;
+            assert_eq!(dim.positions(-2), [2]);
+            assert_eq!(dim.index_from_positions(&[2]), -2);
+
+            let dim = (3, -10..10, Periodic(1..=4));
+            assert_eq!(dim.positions((2, -10, 3)), [2, 0, 2]);
+            assert_eq!(dim.positions((2, -10, 5)), [2, 0, 0]);
+            assert_eq!(dim.index_from_positions(&[1, 19, 3]), (1, 9, 4));
+        }
+
+        #[test]
+        fn with_extent() {
+            assert_eq!(4.with_extent(0, 7), 7);
+            assert_eq!((-4_i8..10).with_extent(0, 2), -4..-2);
+            assert_eq!((1_u8..=4).with_extent(0, 10), 1..=10);
+            let empty = (1_u8..=4).with_extent(0, 0);
+            assert_eq!((empty.size(), empty.lbound(0)), (0, 1));
+            let empty = (-3_i64..=4).with_extent(0, 0);
+            assert_eq!((empty.size(), empty.lbound(0)), (0, -3));
+            // No upper bound is available below the lower bound
+            let empty = (0_u8..=4).with_extent(0, 0);
+            assert_eq!((empty.size(), empty.lbound(0)), (0, 1));
+            let empty = (0_usize..=4).with_extent(0, 0);
+            assert_eq!((empty.size(), empty.lbound(0)), (0, 1));
+            let empty = (i8::MIN..=4).with_extent(0, 0);
+            assert_eq!((empty.size(), empty.lbound(0)), (0, -127));
+            assert_eq!(Periodic(-1..1).with_extent(0, 4), Periodic(-1..3));
+
+            let dim = (3, -10..10, Periodic(1..=4));
+            assert_eq!(dim.with_extent(1, 2), (3, -10..-8, Periodic(1..=4)));
+            assert_eq!(dim.with_extent(2, 1), (3, -10..10, Periodic(1..=1)));
+        }
+
+        #[test]
+        #[should_panic(expected = "upper bound overflows i8")]
+        fn with_extent_overflow() {
+            (100_i8..110).with_extent(0, 30);
+        }
+
+        #[test]
+        #[should_panic(expected = "expected 3 positions, got 2")]
+        fn invalid_positions() {
+            (3, 4, 5).index_from_positions(&[1, 2]);
+        }
+
+        #[test]
+        #[should_panic(expected = "axis 1 is out of bound for 1-dimensional dimensions")]
+        fn invalid_axis() {
+            (0..4).lbound(1);
+        }
+
+        #[test]
+        #[should_panic(expected = "lower bound overflows isize")]
+        fn overflow() {
+            (u64::MAX - 1..u64::MAX).lbound(0);
+        }
+    }
+
+    mod wide_ranges {
+        use super::*;
+        #[test]
+        fn offset() {
+            let dim = -5_000_000_000_i64..5_000_000_000;
+            assert_eq!(dim.size(), 10_000_000_000);
+            assert_eq!(dim.offset(-4_999_999_999), 1);
+            assert_eq!(dim.index(9_999_999_999), 4_999_999_999);
+
+            let dim = 250_u8..255;
+            assert_eq!(dim.size(), 5);
+            assert_eq!(dim.offset(254), 4);
+            assert_eq!(dim.index(2), 252);
+
+            let dim = -100_i8..100;
+            assert_eq!(dim.size(), 200);
+            assert_eq!(dim.offset(99), 199);
+            assert_eq!(dim.index(0), -100);
+
+            let dim = 3_000_000_000_u32..3_000_000_010;
+            assert_eq!(dim.offset(3_000_000_003), 3);
+
+            let dim = i64::MIN..i64::MAX;
+            assert_eq!(dim.size(), usize::MAX);
+            assert_eq!(dim.offset(0), 1 << 63);
+            assert_eq!(dim.index(usize::MAX - 1), i64::MAX - 1);
+        }
+
+        #[test]
+        fn empty() {
+            let (start, end) = (10_i64, -10);
+            let dim = start..end;
+            assert_eq!(dim.size(), 0);
+            let dim = 10_u16..10;
+            assert_eq!(dim.size(), 0);
+        }
+
+        #[test]
+        #[should_panic]
+        fn bigger_than_dim() {
+            let dim = -4_isize..10;
+            dim.offset(10);
+        }
+    }
+
+    mod inclusive_ranges {
+        use super::*;
+        #[test]
+        fn offset() {
+            let dim = -4..=10;
+            assert_eq!(dim.size(), 15);
+            assert_eq!(dim.offset(-4), 0);
+            assert_eq!(dim.offset(10), 14);
+            assert_eq!(dim.index(14), 10);
+
+            let dim = 1_usize..=3;
+            assert_eq!(dim.size(), 3);
+            assert_eq!(dim.offset(3), 2);
+
+            let dim = 0_u8..=255;
+            assert_eq!(dim.size(), 256);
+            assert_eq!(dim.index(255), 255);
+
+            let (start, end) = (5_i64, 4);
+            let dim = start..=end;
+            assert_eq!(dim.size(), 0);
+        }
+
+        #[test]
+        fn tuples() {
+            let dim = (1..=3, -1..=1);
+            assert_eq!(dim.size(), 9);
+            assert_eq!(dim.offset((1, -1)), 0);
+            assert_eq!(dim.offset((3, 1)), 8);
+        }
+
+        #[test]
+        #[should_panic]
+        fn bigger_than_dim() {
+            let dim = 1..=3;
+            dim.offset(4);
+        }
+
+        #[test]
+        #[should_panic]
+        fn smaller_than_dim() {
+            let dim = 1..=3;
+            dim.offset(0);
+        }
+
+        #[test]
+        #[should_panic(expected = "range size overflows usize")]
+        fn overflow() {
+            let dim = i64::MIN..=i64::MAX;
+            dim.size();
+        }
+    }
+
+    mod periodic {
+        use super::*;
+        #[test]
+        fn offset() {
+            let dim = Periodic(5);
+            assert_eq!(dim.size(), 5);
+            assert_eq!(dim.offset(3), 3);
+            assert_eq!(dim.offset(5), 0);
+            assert_eq!(dim.offset(13), 3);
+            assert_eq!(dim.index(3), 3);
+
+            let dim = Periodic(4..10);
+            assert_eq!(dim.offset(5), 1);
+            assert_eq!(dim.offset(10), 0);
+            assert_eq!(dim.offset(2), 4);
+            assert_eq!(dim.index(4), 8);
+
+            let dim = Periodic(-4..10);
+            assert_eq!(dim.size(), 14);
+            assert_eq!(dim.offset(-3), 1);
+            assert_eq!(dim.offset(-5), 13);
+            assert_eq!(dim.offset(24), 0);
+            assert_eq!(dim.offset(-32), 0);
+            assert_eq!(dim.index(13), 9);
+
+            let dim = Periodic(1..=3);
+            assert_eq!(dim.offset(4), 0);
+            assert_eq!(dim.offset(0), 2);
+
+            let dim = Periodic(-5_000_000_000_i64..5_000_000_000);
+            assert_eq!(dim.offset(5_000_000_000), 0);
+        }
+
+        #[test]
+        fn tuples() {
+            let dim = (Periodic(0..3), 0..2);
+            assert_eq!(dim.offset((3, 1)), 1);
+            assert_eq!(dim.offset((-1, 1)), 5);
+        }
+
+        #[test]
+        #[should_panic]
+        fn not_periodic_axis() {
+            let dim = (Periodic(0..3), 0..2);
+            dim.offset((1, 2));
+        }
+
+        #[test]
+        #[should_panic]
+        fn empty() {
+            let dim = Periodic(3..3);
+            dim.offset(3);
+        }
+    }
+
+    mod column_major {
+        use super::*;
+        #[test]
+        fn offset() {
+            let dim = ColumnMajor((2, -1..2, 1..=4));
+            assert_eq!(dim.size(), 24);
+            assert_eq!(dim.offset((0, -1, 1)), 0);
+            assert_eq!(dim.offset((1, -1, 1)), 1);
+            assert_eq!(dim.offset((0, 0, 1)), 2);
+            assert_eq!(dim.offset((0, -1, 2)), 6);
+            assert_eq!(dim.offset((1, 1, 4)), 23);
+            for offset in 0..24 {
+                assert_eq!(dim.offset(dim.index(offset)), offset);
+            }
+            assert_eq!(dim.lbounds(), [0, -1, 1]);
+            assert_eq!(dim.with_extent(1, 5), ColumnMajor((2, -1..4, 1..=4)));
+
+            let dim = ColumnMajor((Periodic(0..3), 2));
+            assert_eq!(dim.offset((4, 1)), 4);
+        }
+
+        #[test]
+        #[should_panic]
+        fn bigger_than_dim() {
+            let dim = ColumnMajor((2, 3));
+            dim.offset((2, 0));
+        }
+    }
+
+    mod from_bounds {
+        use super::*;
+        #[test]
+        fn ranges() {
+            assert_eq!(usize::from_bounds(&[0], &[4]), Some(4));
+            assert_eq!(usize::from_bounds(&[1], &[4]), None);
+            assert_eq!(Range::<i32>::from_bounds(&[-2], &[4]), Some(-2..2));
+            assert_eq!(RangeInclusive::<i32>::from_bounds(&[-2], &[4]), Some(-2..=1));
+            assert_eq!(Range::<u8>::from_bounds(&[-2], &[4]), None);
+            assert_eq!(Range::<u8>::from_bounds(&[250], &[10]), None);
+            assert_eq!(Range::<i32>::from_bounds(&[0, 0], &[1, 1]), None);
+        }
+
+        #[test]
+        fn nested() {
+            let dims = <ColumnMajor<(usize, Periodic<Range<i64>>, usize)>>::from_bounds(&[0, 3, 0], &[2, 3, 4]);
+            assert_eq!(dims, Some(ColumnMajor((2, Periodic(3..6), 4))));
+            assert_eq!(<(usize, usize)>::from_bounds(&[0, 0, 0], &[2, 3, 4]), None);
+            assert_eq!(dims.unwrap().strides(), Some([1, 2, 6]));
+            assert_eq!((3, 1, 4).strides(), Some([4, 0, 1]));
+        }
+    }
+
+    mod enumerated {
+        use super::*;
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        enum Species {
+            Hydrogen,
+            Oxygen,
+        }
+
+        impl IndexEnum for Species {
+            const COUNT: usize = 2;
+
+            fn ordinal(self) -> usize {
+                self as usize
+            }
+
+            fn from_ordinal(ordinal: usize) -> Species {
+                match ordinal {
+                    0 => Species::Hydrogen,
+                    1 => Species::Oxygen,
+                    _ => panic!("invalid ordinal {} for Species", ordinal),
+                }
+            }
+        }
+
+        #[test]
+        fn offset() {
+            let dim = Enumerated::<Species>::new();
+            assert_eq!(dim.size(), 2);
+            assert_eq!(dim.offset(Species::Hydrogen), 0);
+            assert_eq!(dim.offset(Species::Oxygen), 1);
+            assert_eq!(dim.index(1), Species::Oxygen);
+        }
+
+        #[test]
+        fn tuples() {
+            let dim = (3, Enumerated::<Species>::new());
+            assert_eq!(dim.size(), 6);
+            assert_eq!(dim.offset((1, Species::Oxygen)), 3);
+            assert_eq!(dim.index(4), (2, Species::Hydrogen));
+        }
+
+        #[test]
+        #[should_panic]
+        fn bigger_than_dim() {
+            let dim = Enumerated::<Species>::new();
+            dim.index(2);
+        }
+
+        #[derive(Clone, Copy, PartialEq, Debug)]
+        struct Invalid;
+
+        impl IndexEnum for Invalid {
+            const COUNT: usize = 1;
+            fn ordinal(self) -> usize {
+                3
+            }
+            fn from_ordinal(_: usize) -> Invalid {
+                Invalid
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "ordinal out of bound: count is 1 but ordinal is 3")]
+        fn invalid_ordinal() {
+            let dim = Enumerated::<Invalid>::new();
+            dim.offset(Invalid);
+        }
+    }
+
     mod dim1 {
         use super::*;
         #[test]
@@ -267,6 +1895,10 @@ mod tests {
             assert_eq!(dim.offset((1, 0)), 3);
 
             assert_eq!(dim.offset((1, 2)), 5);
+
+            assert_eq!(dim.index(1), (0, 1));
+            assert_eq!(dim.index(3), (1, 0));
+            assert_eq!(dim.index(5), (1, 2));
         }
 
         #[test]
@@ -298,6 +1930,14 @@ mod tests {
             assert_eq!(dim.offset((1, 1, 4)), 19);
         }
 
+        #[test]
+        fn index() {
+            let dim = (2, -2..2, 4..7);
+            assert_eq!(dim.index(0), (0, -2, 4));
+            assert_eq!(dim.index(5), (0, -1, 6));
+            assert_eq!(dim.index(23), (1, 1, 6));
+        }
+
         #[test]
         #[should_panic]
         fn bigger_than_dim_1() {
@@ -494,6 +2134,15 @@ mod tests {
             assert_eq!(dim.offset((1, 1, 4, 2, 3, 4, 5)), 7199);
         }
 
+        #[test]
+        fn index() {
+            let dim = (2, 2, 5, 3, 4, 5, 6);
+            for offset in 0..dim.size() {
+                assert_eq!(dim.offset(dim.index(offset)), offset);
+            }
+            assert_eq!(dim.index(7199), (1, 1, 4, 2, 3, 4, 5));
+        }
+
         #[test]
         #[should_panic]
         fn bigger_than_dim_1() {
@@ -0,0 +1,324 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+
+use dimensions::check_axis;
+use {Dimensions, FromBounds};
+
+/// Maximal number of axes in `DynDims`, which is also the maximal rank of
+/// Fortran arrays before Fortran 2008.
+pub const MAX_DYN_RANK: usize = 7;
+
+/// A list of values, with one value for each axis of `DynDims`. This is used
+/// for the indexes, bounds and extents of `DynDims`, and dereferences to a
+/// slice.
+///
+/// ```
+/// use mudi::DynIndex;
+/// let index = DynIndex::from([2, -1, 4]);
+/// assert_eq!(index.len(), 3);
+/// assert_eq!(index[1], -1);
+/// ```
+#[derive(Clone, Copy)]
+pub struct DynList<T> {
+    len: usize,
+    values: [T; MAX_DYN_RANK],
+}
+
+/// Index type for `DynDims`
+pub type DynIndex = DynList<isize>;
+
+impl<T: Copy + Default> DynList<T> {
+    /// Create a new list containing `values`.
+    ///
+    /// # Panics
+    /// If there are more than `MAX_DYN_RANK` values.
+    pub fn new(values: &[T]) -> DynList<T> {
+        assert!(values.len() <= MAX_DYN_RANK, "dynamic dimensions can have at most {} axes, got {}",
+                MAX_DYN_RANK, values.len());
+        let mut list = DynList {
+            len: values.len(),
+            ..DynList::default()
+        };
+        list.values[..values.len()].copy_from_slice(values);
+        list
+    }
+}
+
+impl<T: Copy + Default> Default for DynList<T> {
+    fn default() -> DynList<T> {
+        DynList {
+            len: 0,
+            values: [T::default(); MAX_DYN_RANK],
+        }
+    }
+}
+
+impl<'a, T: Copy + Default> From<&'a [T]> for DynList<T> {
+    fn from(values: &'a [T]) -> DynList<T> {
+        DynList::new(values)
+    }
+}
+
+impl<T: Copy + Default, const N: usize> From<[T; N]> for DynList<T> {
+    fn from(values: [T; N]) -> DynList<T> {
+        DynList::new(&values)
+    }
+}
+
+impl<T> Deref for DynList<T> {
+    type Target = [T];
+    fn deref(&self) -> &[T] {
+        &self.values[..self.len]
+    }
+}
+
+impl<T> DerefMut for DynList<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.values[..self.len]
+    }
+}
+
+impl<T> AsRef<[T]> for DynList<T> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T> AsMut<[T]> for DynList<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T: PartialEq> PartialEq for DynList<T> {
+    fn eq(&self, other: &DynList<T>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: Eq> Eq for DynList<T> {}
+
+impl<T: Hash> Hash for DynList<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for DynList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// Dimensions with a rank only known at runtime, up to `MAX_DYN_RANK` axes.
+/// Each axis has a lower bound and an extent, and the elements are stored in
+/// row-major order like for tuple dimensions.
+///
+/// ```
+/// use mudi::{Array, DynDims, DynIndex};
+/// let dims = DynDims::new(&[1, -2], &[2, 3]);
+/// let array = Array::from_fn(dims, |index: DynIndex| 10 * index[0] + index[1]);
+/// assert_eq!(array.rank(), 2);
+/// assert_eq!(array.ubounds(), [2, 0].into());
+/// assert_eq!(array[DynIndex::from([2, -1])], 19);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct DynDims {
+    lbounds: DynList<isize>,
+    extents: DynList<usize>,
+}
+
+impl DynDims {
+    /// Create dimensions with the given lower bounds and extents along each
+    /// axis.
+    ///
+    /// # Panics
+    /// If `lbounds` and `extents` do not have the same length, or if there
+    /// are more than `MAX_DYN_RANK` axes.
+    pub fn new(lbounds: &[isize], extents: &[usize]) -> DynDims {
+        assert!(lbounds.len() == extents.len(), "got {} lower bounds for {} extents", lbounds.len(), extents.len());
+        DynDims {
+            lbounds: DynList::new(lbounds),
+            extents: DynList::new(extents),
+        }
+    }
+
+    /// Create dimensions with the given extents, and a lower bound of 0
+    /// along each axis.
+    ///
+    /// # Panics
+    /// If there are more than `MAX_DYN_RANK` axes.
+    pub fn from_extents(extents: &[usize]) -> DynDims {
+        let extents = DynList::new(extents);
+        let mut lbounds = DynList::new(&[0; MAX_DYN_RANK]);
+        lbounds.len = extents.len;
+        DynDims {
+            lbounds,
+            extents,
+        }
+    }
+}
+
+impl Dimensions for DynDims {
+    type Index = DynIndex;
+    type Bounds = DynList<isize>;
+    type Extents = DynList<usize>;
+
+    #[inline(always)]
+    fn offset(&self, index: DynIndex) -> usize {
+        let positions = self.positions(index);
+        positions.iter().zip(self.extents.iter()).fold(0, |offset, (position, extent)| offset * extent + position)
+    }
+
+    #[inline(always)]
+    fn index(&self, mut offset: usize) -> DynIndex {
+        assert!(offset < self.size(), "offset out of bound: size is {} but offset is {}", self.size(), offset);
+        let mut index = self.lbounds;
+        for (value, &extent) in index.iter_mut().zip(self.extents.iter()).rev() {
+            *value += (offset % extent) as isize;
+            offset /= extent;
+        }
+        index
+    }
+
+    #[inline(always)]
+    fn size(&self) -> usize {
+        self.extents.iter().product()
+    }
+
+    #[inline(always)]
+    fn rank(&self) -> usize {
+        self.extents.len()
+    }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        check_axis(axis, self.rank());
+        self.lbounds[axis]
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        check_axis(axis, self.rank());
+        self.extents[axis]
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> DynDims {
+        check_axis(axis, self.rank());
+        let mut dims = *self;
+        dims.extents[axis] = extent;
+        dims
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: DynIndex) -> DynList<usize> {
+        assert!(index.len() == self.rank(), "expected an index with {} values, got {}", self.rank(), index.len());
+        let mut positions = self.extents;
+        for (axis, position) in positions.iter_mut().enumerate() {
+            let lower = self.lbounds[axis];
+            let upper = lower + self.extents[axis] as isize;
+            assert!(lower <= index[axis] && index[axis] < upper,
+                    "index out of bound along axis {}: range is ({}..{}) but index is {}",
+                    axis, lower, upper, index[axis]);
+            *position = (index[axis] - lower) as usize;
+        }
+        positions
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> DynIndex {
+        assert!(positions.len() == self.rank(), "expected {} positions, got {}", self.rank(), positions.len());
+        let mut index = self.lbounds;
+        for (axis, value) in index.iter_mut().enumerate() {
+            assert!(positions[axis] < self.extents[axis], "offset out of bound: size is {} but offset is {}",
+                    self.extents[axis], positions[axis]);
+            *value += positions[axis] as isize;
+        }
+        index
+    }
+
+    fn lbounds(&self) -> DynList<isize> {
+        self.lbounds
+    }
+
+    fn ubounds(&self) -> DynList<isize> {
+        let mut bounds = self.lbounds;
+        for (bound, &extent) in bounds.iter_mut().zip(self.extents.iter()) {
+            *bound += extent as isize - 1;
+        }
+        bounds
+    }
+
+    fn extents(&self) -> DynList<usize> {
+        self.extents
+    }
+}
+
+impl FromBounds for DynDims {
+    fn from_bounds(lbounds: &[isize], extents: &[usize]) -> Option<DynDims> {
+        if lbounds.len() != extents.len() || extents.len() > MAX_DYN_RANK {
+            return None;
+        }
+        Some(DynDims::new(lbounds, extents))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Array, ColumnMajor};
+
+    #[test]
+    fn offset() {
+        let dims = DynDims::new(&[-1, 0, 2], &[2, 3, 4]);
+        assert_eq!(dims.rank(), 3);
+        assert_eq!(dims.size(), 24);
+        assert_eq!(dims.offset(DynIndex::from([-1, 0, 2])), 0);
+        assert_eq!(dims.offset(DynIndex::from([-1, 1, 3])), 5);
+        assert_eq!(dims.offset(DynIndex::from([0, 2, 5])), 23);
+        for offset in 0..24 {
+            assert_eq!(dims.offset(dims.index(offset)), offset);
+        }
+        assert_eq!(dims.lbounds(), [-1, 0, 2].into());
+        assert_eq!(dims.ubounds(), [0, 2, 5].into());
+        assert_eq!(dims.with_extent(1, 5).extents(), [2, 5, 4].into());
+
+        let dims = DynDims::from_extents(&[3, 2]);
+        assert_eq!(dims.lbounds(), [0, 0].into());
+        assert_eq!(dims, DynDims::new(&[0, 0], &[3, 2]));
+        assert_eq!(DynDims::from_bounds(&[0, 0], &[3, 2]), Some(dims));
+        assert_eq!(DynDims::from_bounds(&[0; 8], &[1; 8]), None);
+    }
+
+    #[test]
+    fn generic_code() {
+        let array = Array::from_fn(DynDims::from_extents(&[3, 3]), |index: DynIndex| index[0] * 3 + index[1]);
+        let sums = array.windows([2, 2].into()).map(|window| window.iter().sum()).collect::<Vec<isize>>();
+        assert_eq!(sums, vec![8, 12, 20, 24]);
+
+        let column = Array::from_fn(ColumnMajor(DynDims::from_extents(&[3, 3])), |index: DynIndex| index[0] * 3 + index[1]);
+        assert_eq!(column.flat_iter().take(3).cloned().collect::<Vec<_>>(), vec![0, 3, 6]);
+        assert_eq!(column.cshift(1, 0)[DynIndex::from([0, 1])], 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bound along axis 1: range is (0..2) but index is 2")]
+    fn out_of_bounds() {
+        let dims = DynDims::from_extents(&[3, 2]);
+        dims.offset(DynIndex::from([0, 2]));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected an index with 2 values, got 3")]
+    fn wrong_rank() {
+        let dims = DynDims::from_extents(&[3, 2]);
+        dims.offset(DynIndex::from([0, 0, 0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "dynamic dimensions can have at most 7 axes, got 8")]
+    fn too_many_axes() {
+        let _ = DynDims::from_extents(&[1; 8]);
+    }
+}
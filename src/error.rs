@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when the number of elements does not match the size of
+/// the array dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeError {
+    /// There are not enough elements to fill the array
+    TooShort {
+        /// Size of the dimensions
+        expected: usize,
+        /// Number of available elements
+        found: usize,
+    },
+    /// There are more elements than the size of the dimensions
+    TooLong {
+        /// Size of the dimensions
+        expected: usize,
+    },
+}
+
+impl fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ShapeError::TooShort { expected, found } => {
+                write!(f, "expected {} elements for the dimensions, got {}", expected, found)
+            }
+            ShapeError::TooLong { expected } => {
+                write!(f, "expected {} elements for the dimensions, got more", expected)
+            }
+        }
+    }
+}
+
+impl Error for ShapeError {}
+
+/// Error returned when data coming from another library can not be used with
+/// the requested dimensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The shape and the lower bounds of the data can not be represented by
+    /// the dimensions
+    Bounds,
+    /// The data strides do not match the storage order of the dimensions
+    Strides {
+        /// Axis with the wrong stride
+        axis: usize,
+        /// Stride in elements for the dimensions
+        expected: isize,
+        /// Stride in elements in the data
+        found: isize,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LayoutError::Bounds => write!(f, "the data bounds do not fit in the dimensions"),
+            LayoutError::Strides { axis, expected, found } => {
+                write!(f, "expected a stride of {} elements along axis {}, got {} elements", expected, axis, found)
+            }
+        }
+    }
+}
+
+impl Error for LayoutError {}
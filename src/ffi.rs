@@ -0,0 +1,482 @@
+//! Interoperability with Fortran 2018 C descriptors.
+//!
+//! Fortran procedures with assumed-shape dummy arguments and the `bind(C)`
+//! attribute receive their arrays as a pointer to a `CFI_cdesc_t` C
+//! descriptor, defined in `ISO_Fortran_binding.h`. [`CfiDescriptor`] has the
+//! same layout as the gfortran definition of this structure, with room for
+//! `CFI_MAX_RANK` axes.
+//!
+//! Descriptors are created from arrays and views with `cfi_descriptor` and
+//! `cfi_descriptor_mut`, and incoming descriptors are turned back into views
+//! with `ArrayView::from_cfi` and `ArrayViewMut::from_cfi`. The lower bound
+//! of each axis maps directly to `lower_bound`, so that the indexes are the
+//! same on both sides.
+//!
+//! Fortran arrays are stored in column-major order: use
+//! [`ColumnMajor`](../struct.ColumnMajor.html) dimensions to share contiguous
+//! data with Fortran. Row-major arrays are described with the corresponding
+//! strides, which Fortran handles as a non-contiguous array.
+//!
+//! ```
+//! use mudi::{Array, ArrayView, ColumnMajor};
+//! use mudi::ffi::CfiDim;
+//! use std::ops::RangeInclusive;
+//!
+//! let array = Array::from_fn(ColumnMajor((1..=3, 0..=1)), |(i, j)| 10.0 * i as f64 + j as f64);
+//! let descriptor = array.cfi_descriptor();
+//! assert_eq!(descriptor.dims(), &[
+//!     CfiDim { lower_bound: 1, extent: 3, sm: 8 },
+//!     CfiDim { lower_bound: 0, extent: 2, sm: 24 },
+//! ]);
+//!
+//! // `&descriptor` can be given to a `bind(C)` Fortran procedure, or back to Rust
+//! type Dims = ColumnMajor<(RangeInclusive<i32>, RangeInclusive<i32>)>;
+//! let view = unsafe { ArrayView::<f64, Dims>::from_cfi(&descriptor) }.unwrap();
+//! assert_eq!(view[(3, 1)], 31.0);
+//! ```
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::mem;
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+use std::slice;
+
+use {ArrayBase, ArrayView, ArrayViewMut, Dimensions, FromBounds, Storage, MAX_DYN_RANK};
+
+/// Version of the descriptors created by this module, `CFI_VERSION`
+pub const CFI_VERSION: c_int = 1;
+/// Maximal number of axes in a descriptor, `CFI_MAX_RANK`
+pub const CFI_MAX_RANK: usize = 15;
+
+/// Attribute of descriptors for Fortran pointers, `CFI_attribute_pointer`
+pub const CFI_ATTRIBUTE_POINTER: i8 = 0;
+/// Attribute of descriptors for allocatable arrays, `CFI_attribute_allocatable`
+pub const CFI_ATTRIBUTE_ALLOCATABLE: i8 = 1;
+/// Attribute of all other descriptors, `CFI_attribute_other`
+pub const CFI_ATTRIBUTE_OTHER: i8 = 2;
+
+/// Description of a single axis in a descriptor, `CFI_dim_t`
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CfiDim {
+    /// Lower bound of the axis
+    pub lower_bound: isize,
+    /// Number of elements along the axis
+    pub extent: isize,
+    /// Distance in bytes between two consecutive elements along the axis
+    pub sm: isize,
+}
+
+/// A C descriptor, with the same layout as `CFI_cdesc_t`. Only the first
+/// `rank` entries of `dim` are used.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CfiDescriptor {
+    /// Address of the first element of the array
+    pub base_addr: *mut c_void,
+    /// Size in bytes of a single element
+    pub elem_len: usize,
+    /// Version of the descriptor, `CFI_VERSION`
+    pub version: c_int,
+    /// Number of axes
+    pub rank: i8,
+    /// One of the `CFI_ATTRIBUTE_*` constants
+    pub attribute: i8,
+    /// Type code of the elements, see [`CfiType`](trait.CfiType.html)
+    pub type_: i16,
+    /// Description of each axis
+    pub dim: [CfiDim; CFI_MAX_RANK],
+}
+
+impl CfiDescriptor {
+    /// Get the description of the `rank` axes of this descriptor.
+    pub fn dims(&self) -> &[CfiDim] {
+        let rank = usize::try_from(self.rank).unwrap_or(0);
+        &self.dim[..rank]
+    }
+}
+
+/// Element types with a C descriptor type code. The codes are the ones
+/// used by gfortran, *e.g.* `CFI_type_double` for `f64`.
+pub trait CfiType: Sized {
+    /// The type code for this type
+    const CFI_TYPE: i16;
+}
+
+const CFI_TYPE_INTEGER: i16 = 1;
+const CFI_TYPE_LOGICAL: i16 = 2;
+const CFI_TYPE_REAL: i16 = 3;
+
+macro_rules! cfi_type {
+    ($($ty: ty => $kind: expr),*) => {$(
+        impl CfiType for $ty {
+            const CFI_TYPE: i16 = $kind + ((mem::size_of::<$ty>() as i16) << 8);
+        }
+    )*};
+}
+
+cfi_type!(
+    i8 => CFI_TYPE_INTEGER, i16 => CFI_TYPE_INTEGER, i32 => CFI_TYPE_INTEGER,
+    i64 => CFI_TYPE_INTEGER, isize => CFI_TYPE_INTEGER,
+    f32 => CFI_TYPE_REAL, f64 => CFI_TYPE_REAL, bool => CFI_TYPE_LOGICAL
+);
+
+/// Error returned when a C descriptor does not match the requested view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CfiError {
+    /// The descriptor version is not `CFI_VERSION`
+    Version(c_int),
+    /// The descriptor rank is not the rank of the dimensions
+    Rank {
+        /// Rank of the dimensions
+        expected: usize,
+        /// Rank of the descriptor
+        found: i8,
+    },
+    /// The descriptor type code does not match the element type
+    Type {
+        /// Type code of the element type
+        expected: i16,
+        /// Type code in the descriptor
+        found: i16,
+    },
+    /// The descriptor element length does not match the element type
+    ElementLength {
+        /// Size of the element type
+        expected: usize,
+        /// Element length in the descriptor
+        found: usize,
+    },
+    /// The descriptor bounds can not be represented by the dimensions
+    Bounds,
+    /// The descriptor strides do not match the storage order of the
+    /// dimensions
+    Strides {
+        /// Axis with the wrong stride
+        axis: usize,
+        /// Stride in bytes for the dimensions
+        expected: isize,
+        /// Stride in bytes in the descriptor
+        found: isize,
+    },
+    /// The descriptor base address is null, but the array is not empty
+    Null,
+}
+
+impl fmt::Display for CfiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CfiError::Version(version) => write!(f, "unsupported descriptor version {}", version),
+            CfiError::Rank { expected, found } => {
+                write!(f, "expected a descriptor of rank {}, got rank {}", expected, found)
+            }
+            CfiError::Type { expected, found } => {
+                write!(f, "expected a descriptor with type {}, got type {}", expected, found)
+            }
+            CfiError::ElementLength { expected, found } => {
+                write!(f, "expected a descriptor with elements of {} bytes, got {} bytes", expected, found)
+            }
+            CfiError::Bounds => write!(f, "the descriptor bounds do not fit in the dimensions"),
+            CfiError::Strides { axis, expected, found } => {
+                write!(f, "expected a stride of {} bytes along axis {}, got {} bytes", expected, axis, found)
+            }
+            CfiError::Null => write!(f, "the descriptor base address is null"),
+        }
+    }
+}
+
+impl Error for CfiError {}
+
+/// Get the distance in bytes between two consecutive elements along each
+/// axis of `layout`. Axes with less than two elements, where the distance
+/// does not matter, use `elem_len`.
+fn byte_strides<D: Dimensions>(layout: &D, elem_len: usize) -> D::Bounds {
+    let elements = layout.strides().expect("C descriptors require dimensions with strided storage");
+    let mut strides = layout.lbounds();
+    for ((stride, &elements), axis) in strides.as_mut().iter_mut().zip(elements.as_ref()).zip(0..) {
+        *stride = if layout.extent(axis) > 1 {
+            (elements * elem_len) as isize
+        } else {
+            elem_len as isize
+        };
+    }
+    strides
+}
+
+/// Create a descriptor for the `dims` region of `data`, stored with the
+/// given `layout`.
+fn descriptor<T: CfiType, D: Dimensions>(data: *const T, layout: &D, dims: &D) -> CfiDescriptor {
+    let base = if dims.size() != 0 {
+        data.wrapping_add(layout.offset(dims.index(0)))
+    } else {
+        data
+    };
+
+    let elem_len = mem::size_of::<T>();
+    let mut descriptor = CfiDescriptor {
+        base_addr: base as *mut c_void,
+        elem_len,
+        version: CFI_VERSION,
+        rank: dims.rank() as i8,
+        attribute: CFI_ATTRIBUTE_OTHER,
+        type_: T::CFI_TYPE,
+        dim: [CfiDim::default(); CFI_MAX_RANK],
+    };
+
+    let strides = byte_strides(layout, elem_len);
+    for axis in 0..dims.rank() {
+        descriptor.dim[axis] = CfiDim {
+            lower_bound: dims.lbound(axis),
+            extent: dims.extent(axis) as isize,
+            sm: strides.as_ref()[axis],
+        };
+    }
+    descriptor
+}
+
+/// Check the header and the strides of `descriptor` against `T` and `D`, and
+/// get the base address and the dimensions of the described array.
+unsafe fn import<T: CfiType, D: FromBounds>(descriptor: *const CfiDescriptor) -> Result<(*mut T, D), CfiError> {
+    // The descriptor may have been allocated with less than `CFI_MAX_RANK`
+    // axes, so we only read the fields we need without creating a reference.
+    let version = ptr::addr_of!((*descriptor).version).read();
+    if version != CFI_VERSION {
+        return Err(CfiError::Version(version));
+    }
+
+    let found_rank = ptr::addr_of!((*descriptor).rank).read();
+    // Dynamic dimensions have empty default bounds, and accept any rank up
+    // to `MAX_DYN_RANK`.
+    let expected = match D::Bounds::default().as_ref().len() {
+        0 => usize::try_from(found_rank).map_or(MAX_DYN_RANK, |rank| rank.min(MAX_DYN_RANK)),
+        rank => rank,
+    };
+    let rank = match usize::try_from(found_rank) {
+        Ok(rank) if rank <= CFI_MAX_RANK => rank,
+        _ => return Err(CfiError::Rank { expected, found: found_rank }),
+    };
+
+    let found = ptr::addr_of!((*descriptor).type_).read();
+    if found != T::CFI_TYPE {
+        return Err(CfiError::Type { expected: T::CFI_TYPE, found });
+    }
+
+    let elem_len = mem::size_of::<T>();
+    let found = ptr::addr_of!((*descriptor).elem_len).read();
+    if found != elem_len {
+        return Err(CfiError::ElementLength { expected: elem_len, found });
+    }
+
+    let first = ptr::addr_of!((*descriptor).dim) as *const CfiDim;
+    let axes = (0..rank).map(|axis| first.add(axis).read()).collect::<Vec<_>>();
+    let lbounds = axes.iter().map(|dim| dim.lower_bound).collect::<Vec<_>>();
+    let extents = axes.iter().map(|dim| usize::try_from(dim.extent)).collect::<Result<Vec<_>, _>>()
+                      .map_err(|_| CfiError::Bounds)?;
+    let dims = match D::from_bounds(&lbounds, &extents) {
+        Some(dims) => dims,
+        None if rank != expected => return Err(CfiError::Rank { expected, found: found_rank }),
+        None => return Err(CfiError::Bounds),
+    };
+
+    let base = ptr::addr_of!((*descriptor).base_addr).read() as *mut T;
+    if dims.size() != 0 {
+        if base.is_null() {
+            return Err(CfiError::Null);
+        }
+        let strides = byte_strides(&dims, elem_len);
+        for (axis, (&expected, dim)) in strides.as_ref().iter().zip(&axes).enumerate() {
+            if dims.extent(axis) > 1 && dim.sm != expected {
+                return Err(CfiError::Strides { axis, expected, found: dim.sm });
+            }
+        }
+    }
+
+    Ok((base, dims))
+}
+
+impl<'a, T: CfiType, D: Dimensions> ArrayView<'a, T, D> {
+    /// Get a C descriptor for this view. The descriptor points to the data
+    /// of the view: it must not be used after the view lifetime, and the
+    /// data must not be modified through it.
+    ///
+    /// # Panics
+    /// If the dimensions do not have strided storage.
+    pub fn cfi_descriptor(&self) -> CfiDescriptor {
+        let (data, layout, dims) = self.raw_parts();
+        descriptor(data.as_ptr(), layout, dims)
+    }
+}
+
+impl<'a, T: CfiType, D: FromBounds> ArrayView<'a, T, D> {
+    /// Create a view of the array described by `descriptor`. The descriptor
+    /// must have the rank of `D`, describe elements of type `T` and have the
+    /// strides matching the storage order of `D`.
+    ///
+    /// # Safety
+    /// `descriptor` must point to a valid descriptor, and the data it
+    /// describes must be valid and not modified for the lifetime `'a`.
+    pub unsafe fn from_cfi(descriptor: *const CfiDescriptor) -> Result<ArrayView<'a, T, D>, CfiError> {
+        let (base, dims) = import::<T, D>(descriptor)?;
+        let data = if dims.size() != 0 {
+            slice::from_raw_parts(base as *const T, dims.size())
+        } else {
+            &[]
+        };
+        Ok(ArrayView::new(data, dims.clone(), dims))
+    }
+}
+
+impl<'a, T: CfiType, D: Dimensions> ArrayViewMut<'a, T, D> {
+    /// Get a C descriptor for this view, which can be used to modify the
+    /// data. The descriptor must not be used after the view lifetime.
+    ///
+    /// # Panics
+    /// If the dimensions do not have strided storage.
+    pub fn cfi_descriptor_mut(&mut self) -> CfiDescriptor {
+        let (data, layout, dims) = self.raw_parts_mut();
+        descriptor(data.as_mut_ptr(), layout, dims)
+    }
+}
+
+impl<'a, T: CfiType, D: FromBounds> ArrayViewMut<'a, T, D> {
+    /// Create a mutable view of the array described by `descriptor`. See
+    /// [`ArrayView::from_cfi`](struct.ArrayView.html#method.from_cfi).
+    ///
+    /// # Safety
+    /// `descriptor` must point to a valid descriptor, and the data it
+    /// describes must be valid and not accessed by anything else for the
+    /// lifetime `'a`.
+    pub unsafe fn from_cfi(descriptor: *const CfiDescriptor) -> Result<ArrayViewMut<'a, T, D>, CfiError> {
+        let (base, dims) = import::<T, D>(descriptor)?;
+        let data = if dims.size() != 0 {
+            slice::from_raw_parts_mut(base, dims.size())
+        } else {
+            &mut []
+        };
+        Ok(ArrayViewMut::new(data, dims.clone(), dims))
+    }
+}
+
+impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage, S::Item: CfiType {
+    /// Get a C descriptor for this array. See
+    /// [`ArrayView::cfi_descriptor`](struct.ArrayView.html#method.cfi_descriptor).
+    pub fn cfi_descriptor(&self) -> CfiDescriptor {
+        self.view().cfi_descriptor()
+    }
+
+    /// Get a C descriptor for this array, which can be used to modify the
+    /// data. See
+    /// [`ArrayViewMut::cfi_descriptor_mut`](struct.ArrayViewMut.html#method.cfi_descriptor_mut).
+    pub fn cfi_descriptor_mut(&mut self) -> CfiDescriptor {
+        self.view_mut().cfi_descriptor_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::{Range, RangeInclusive};
+    use {Array, ColumnMajor, DynDims};
+
+    #[test]
+    fn layout() {
+        assert_eq!(mem::size_of::<CfiDim>(), 3 * mem::size_of::<isize>());
+        let header = 2 * mem::size_of::<usize>() + 8;
+        assert_eq!(mem::size_of::<CfiDescriptor>(), header + CFI_MAX_RANK * mem::size_of::<CfiDim>());
+        assert_eq!(<f64 as CfiType>::CFI_TYPE, 2051);
+        assert_eq!(<f32 as CfiType>::CFI_TYPE, 1027);
+        assert_eq!(<i32 as CfiType>::CFI_TYPE, 1025);
+        assert_eq!(<bool as CfiType>::CFI_TYPE, 258);
+    }
+
+    #[test]
+    fn row_major() {
+        let array = Array::from_fn((-1..2, 4), |(i, j)| 10 * i + j as i32);
+        let descriptor = array.cfi_descriptor();
+        assert_eq!(descriptor.base_addr as *const i32, array.flat_iter().as_slice().as_ptr());
+        assert_eq!(descriptor.elem_len, 4);
+        assert_eq!(descriptor.rank, 2);
+        assert_eq!(descriptor.attribute, CFI_ATTRIBUTE_OTHER);
+        assert_eq!(descriptor.dims(), &[
+            CfiDim { lower_bound: -1, extent: 3, sm: 16 },
+            CfiDim { lower_bound: 0, extent: 4, sm: 4 },
+        ]);
+
+        let view = unsafe { ArrayView::<i32, (Range<i32>, usize)>::from_cfi(&descriptor) }.unwrap();
+        assert_eq!(view.shape(), (-1..2, 4));
+        assert_eq!(view.to_owned(), array);
+    }
+
+    #[test]
+    fn subview() {
+        let array = Array::from_fn(ColumnMajor((0..4, 1..=3)), |(i, j)| 10 * i + j);
+        let view = array.subview(ColumnMajor((1..3, 2..=3)));
+        let descriptor = view.cfi_descriptor();
+        assert_eq!(descriptor.base_addr as *const i32, &view[(1, 2)] as *const i32);
+        assert_eq!(descriptor.dims(), &[
+            CfiDim { lower_bound: 1, extent: 2, sm: 4 },
+            CfiDim { lower_bound: 2, extent: 2, sm: 16 },
+        ]);
+
+        // Strided descriptors can not be imported
+        let error = unsafe { ArrayView::<i32, ColumnMajor<(Range<i32>, RangeInclusive<i32>)>>::from_cfi(&descriptor) };
+        assert_eq!(error.unwrap_err(), CfiError::Strides { axis: 1, expected: 8, found: 16 });
+    }
+
+    #[test]
+    fn mutable() {
+        let mut array = Array::from_element(0.0_f32, ColumnMajor((2, 2)));
+        let descriptor = array.cfi_descriptor_mut();
+        {
+            let mut view = unsafe { ArrayViewMut::<f32, ColumnMajor<(usize, usize)>>::from_cfi(&descriptor) }.unwrap();
+            view[(1, 0)] = 3.0;
+        }
+        assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![0.0, 3.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn errors() {
+        let array = Array::from_element(0.0, (1..3, 2));
+        let descriptor = array.cfi_descriptor();
+
+        let error = unsafe { ArrayView::<f64, usize>::from_cfi(&descriptor) }.unwrap_err();
+        assert_eq!(error, CfiError::Rank { expected: 1, found: 2 });
+        assert_eq!(error.to_string(), "expected a descriptor of rank 1, got rank 2");
+
+        let error = unsafe { ArrayView::<i64, (Range<i32>, usize)>::from_cfi(&descriptor) }.unwrap_err();
+        assert_eq!(error, CfiError::Type { expected: 2049, found: 2051 });
+
+        // Dynamic dimensions have at most MAX_DYN_RANK axes
+        let mut large = descriptor;
+        large.rank = 8;
+        for dim in &mut large.dim[2..8] {
+            *dim = CfiDim { lower_bound: 0, extent: 1, sm: 8 };
+        }
+        let error = unsafe { ArrayView::<f64, DynDims>::from_cfi(&large) }.unwrap_err();
+        assert_eq!(error, CfiError::Rank { expected: MAX_DYN_RANK, found: 8 });
+
+        // usize dimensions start at 0
+        let error = unsafe { ArrayView::<f64, (usize, usize)>::from_cfi(&descriptor) }.unwrap_err();
+        assert_eq!(error, CfiError::Bounds);
+
+        // Row-major data is not column-major
+        let error = unsafe { ArrayView::<f64, ColumnMajor<(Range<i32>, usize)>>::from_cfi(&descriptor) }.unwrap_err();
+        assert_eq!(error, CfiError::Strides { axis: 0, expected: 8, found: 16 });
+
+        let mut descriptor = descriptor;
+        descriptor.version = 3;
+        let error = unsafe { ArrayView::<f64, (Range<i32>, usize)>::from_cfi(&descriptor) }.unwrap_err();
+        assert_eq!(error, CfiError::Version(3));
+    }
+
+    #[test]
+    fn empty() {
+        let array = Array::<f64, _>::from_vector(vec![], (0, 1..4));
+        let descriptor = array.cfi_descriptor();
+        assert_eq!(descriptor.dims()[0].extent, 0);
+        let view = unsafe { ArrayView::<f64, (usize, Range<u8>)>::from_cfi(&descriptor) }.unwrap();
+        assert_eq!(view.shape(), (0, 1..4));
+    }
+}
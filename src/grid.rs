@@ -0,0 +1,431 @@
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::ops::{Add, Sub, Index, IndexMut, Range};
+
+use {Array, ArrayView, ArrayViewMut, Dimensions, FromBounds};
+
+/// Dimensions which can be used for the interior of a `Grid`. This is
+/// implemented for ranges of all integer types, and for tuples of two or
+/// three ranges.
+pub trait GridDimensions: FromBounds {
+    /// Width of the halo, with one value for each axis
+    type Halo: Copy + Debug + PartialEq;
+    /// Get the halo width of all the axes
+    fn widths(halo: Self::Halo) -> Self::Extents;
+}
+
+impl<T> GridDimensions for Range<T> where Range<T>: FromBounds + Dimensions<Extents = [usize; 1]> {
+    type Halo = usize;
+
+    fn widths(halo: usize) -> [usize; 1] {
+        [halo]
+    }
+}
+
+impl<A, B> GridDimensions for (A, B)
+    where A: GridDimensions<Bounds = [isize; 1], Halo = usize>,
+          B: GridDimensions<Bounds = [isize; 1], Halo = usize> {
+    type Halo = (usize, usize);
+
+    fn widths(halo: (usize, usize)) -> [usize; 2] {
+        [halo.0, halo.1]
+    }
+}
+
+impl<A, B, C> GridDimensions for (A, B, C)
+    where A: GridDimensions<Bounds = [isize; 1], Halo = usize>,
+          B: GridDimensions<Bounds = [isize; 1], Halo = usize>,
+          C: GridDimensions<Bounds = [isize; 1], Halo = usize> {
+    type Halo = (usize, usize, usize);
+
+    fn widths(halo: (usize, usize, usize)) -> [usize; 3] {
+        [halo.0, halo.1, halo.2]
+    }
+}
+
+/// Boundary conditions used to fill the halo of a `Grid`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Boundary {
+    /// Copy the values from the other side of the interior
+    Periodic,
+    /// Set the halo to zero, i.e. `T::default()`
+    Zero,
+    /// Mirror the interior values across the face: the first halo cell gets
+    /// the value of the first interior cell, the second halo cell the value
+    /// of the second interior cell, *etc.*
+    Reflect,
+    /// Linear extrapolation from the two last cells before each halo cell
+    Extrapolate,
+}
+
+/// One of the two sides of a `Grid` axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The side of the lower bound of the axis
+    Lower,
+    /// The side of the upper bound of the axis
+    Upper,
+}
+
+/// Get positions with the same number of axes as `like`, all set to 0
+fn zeros<E: AsMut<[usize]> + Clone>(like: &E) -> E {
+    let mut zeros = like.clone();
+    for value in zeros.as_mut() {
+        *value = 0;
+    }
+    zeros
+}
+
+/// Move `positions` to the next positions in storage order inside of
+/// `extents`, keeping the position along `axis` fixed. Returns `false` when
+/// all the positions were visited.
+fn advance(positions: &mut [usize], extents: &[usize], axis: usize) -> bool {
+    for current in (0..positions.len()).rev() {
+        if current == axis {
+            continue;
+        }
+        positions[current] += 1;
+        if positions[current] < extents[current] {
+            return true;
+        }
+        positions[current] = 0;
+    }
+    false
+}
+
+/// A grid of values for stencil computations, with a halo of ghost cells
+/// around the interior.
+///
+/// The grid is stored in an `Array` with range dimensions covering both the
+/// interior and the halo: a `(0..n, 0..m)` interior with a `(h, k)` halo is
+/// stored in a `(-h..n + h, -k..m + k)` array. The halo cells are thus indexed
+/// with negative indexes, or indexes past the end of the interior.
+///
+/// ```
+/// use mudi::{Grid, Boundary};
+/// let mut grid = Grid::new(0.0, (0..4, 0..3), (1, 1));
+/// for (i, value) in grid.interior_mut().iter_mut().enumerate() {
+///     *value = i as f64;
+/// }
+///
+/// grid.fill_halo(Boundary::Periodic);
+/// assert_eq!(grid[(-1, 0)], grid[(3, 0)]);
+/// assert_eq!(grid[(4, 2)], grid[(0, 2)]);
+/// assert_eq!(grid[(-1, -1)], grid[(3, 2)]);
+/// ```
+#[derive(Debug)]
+pub struct Grid<T, D> where D: GridDimensions {
+    data: Array<T, D>,
+    interior: D,
+    halo: D::Halo,
+}
+
+impl<T, D> Grid<T, D> where D: GridDimensions {
+    /// Create a new grid with the given `interior` dimensions and `halo`
+    /// width, filled with `value`.
+    pub fn new(value: T, interior: D, halo: D::Halo) -> Grid<T, D> where T: Clone {
+        let mut lbounds = interior.lbounds();
+        let mut extents = interior.extents();
+        let widths = D::widths(halo);
+        for (axis, &width) in widths.as_ref().iter().enumerate() {
+            let lbound = &mut lbounds.as_mut()[axis];
+            *lbound = isize::try_from(width).ok().and_then(|width| lbound.checked_sub(width))
+                                            .expect("halo lower bound overflows isize");
+            let extent = &mut extents.as_mut()[axis];
+            *extent = width.checked_mul(2).and_then(|width| extent.checked_add(width))
+                           .expect("halo extent overflows usize");
+        }
+        let dims = D::from_bounds(lbounds.as_ref(), extents.as_ref())
+                    .expect("the grid with its halo can not be represented by the dimensions type");
+        Grid {
+            data: Array::from_element(value, dims),
+            interior,
+            halo,
+        }
+    }
+
+    /// Get the dimensions of the grid interior.
+    pub fn interior_shape(&self) -> D {
+        self.interior.clone()
+    }
+
+    /// Get the dimensions of the whole grid, including the halo.
+    pub fn shape(&self) -> D {
+        self.data.shape()
+    }
+
+    /// Get the width of the halo.
+    pub fn halo(&self) -> D::Halo {
+        self.halo
+    }
+
+    /// Get the array containing the whole grid, including the halo.
+    pub fn array(&self) -> &Array<T, D> {
+        &self.data
+    }
+
+    /// Get the mutable array containing the whole grid, including the halo.
+    pub fn array_mut(&mut self) -> &mut Array<T, D> {
+        &mut self.data
+    }
+
+    /// Get a view of the grid interior.
+    pub fn interior(&self) -> ArrayView<'_, T, D> {
+        self.data.subview(self.interior.clone())
+    }
+
+    /// Get a mutable view of the grid interior.
+    pub fn interior_mut(&mut self) -> ArrayViewMut<'_, T, D> {
+        self.data.subview_mut(self.interior.clone())
+    }
+
+    /// Get the dimensions of the halo face on the given `side` of `axis`.
+    /// The face covers the interior along the other axes, and does not
+    /// contain the halo corners.
+    fn face_dims(&self, axis: usize, side: Side) -> D {
+        let rank = self.interior.rank();
+        assert!(axis < rank, "axis {} is out of bound for a {}-dimensional grid", axis, rank);
+        let mut lbounds = self.interior.lbounds();
+        let mut extents = self.interior.extents();
+        let width = D::widths(self.halo).as_ref()[axis];
+        lbounds.as_mut()[axis] = match side {
+            Side::Lower => self.data.shape().lbound(axis),
+            Side::Upper => self.interior.ubound(axis) + 1,
+        };
+        extents.as_mut()[axis] = width;
+        D::from_bounds(lbounds.as_ref(), extents.as_ref()).expect("the halo faces are inside of the grid")
+    }
+
+    /// Get a view of the halo face on the given `side` of `axis`. The face
+    /// covers the interior along the other axes, and does not contain the
+    /// halo corners.
+    ///
+    /// ```
+    /// use mudi::{Grid, Side};
+    /// let grid = Grid::new(0, (0..4, 0..3), (2, 1));
+    /// let face = grid.face(0, Side::Upper);
+    /// assert_eq!(face.shape(), (4..6, 0..3));
+    /// ```
+    pub fn face(&self, axis: usize, side: Side) -> ArrayView<'_, T, D> {
+        let dims = self.face_dims(axis, side);
+        self.data.subview(dims)
+    }
+
+    /// Get a mutable view of the halo face on the given `side` of `axis`.
+    /// See [`Grid::face`](#method.face).
+    pub fn face_mut(&mut self, axis: usize, side: Side) -> ArrayViewMut<'_, T, D> {
+        let dims = self.face_dims(axis, side);
+        self.data.subview_mut(dims)
+    }
+}
+
+impl<T, D> Grid<T, D> where D: GridDimensions, T: Clone + Default + Add<Output = T> + Sub<Output = T> {
+    /// Fill the whole halo using the `boundary` conditions. The axes are
+    /// filled in order, each one covering the halo of the previous axes, so
+    /// that the corners of the halo are filled too.
+    pub fn fill_halo(&mut self, boundary: Boundary) {
+        for axis in 0..self.interior.rank() {
+            self.fill_halo_axis(axis, boundary);
+        }
+    }
+
+    /// Fill the halo on both sides of `axis` using the `boundary` conditions.
+    /// The halo is filled over the whole grid extent along the other axes,
+    /// including their halo. Calling this function for each axis with
+    /// different conditions allow to mix boundary conditions.
+    ///
+    /// # Panics
+    /// If the interior is smaller than the halo for `Boundary::Periodic` and
+    /// `Boundary::Reflect`, or smaller than 2 for `Boundary::Extrapolate`.
+    pub fn fill_halo_axis(&mut self, axis: usize, boundary: Boundary) {
+        let rank = self.interior.rank();
+        assert!(axis < rank, "axis {} is out of bound for a {}-dimensional grid", axis, rank);
+        let width = D::widths(self.halo).as_ref()[axis];
+        let size = self.interior.extent(axis);
+        if width == 0 {
+            return;
+        }
+        match boundary {
+            Boundary::Periodic | Boundary::Reflect => assert!(
+                width <= size, "the halo width ({}) is larger than the grid interior ({})", width, size
+            ),
+            Boundary::Extrapolate => assert!(
+                size >= 2, "at least two interior points are needed to extrapolate, got {}", size
+            ),
+            Boundary::Zero => {}
+        }
+
+        // Positions are counted from the lower bound of the whole grid, where
+        // the interior starts at `width` along `axis`
+        let shape = self.data.shape();
+        let extents = shape.extents();
+        if extents.as_ref().contains(&0) {
+            return;
+        }
+        let data = &mut self.data;
+        for side in &[Side::Lower, Side::Upper] {
+            for distance in 1..(width + 1) {
+                // position of the halo cell, and of the cells `steps` away
+                // from it toward the interior of the grid
+                let ghost = match *side {
+                    Side::Lower => width - distance,
+                    Side::Upper => width + size - 1 + distance,
+                };
+                let inward = |steps: usize| match *side {
+                    Side::Lower => ghost + steps,
+                    Side::Upper => ghost - steps,
+                };
+                let mut positions = zeros(&extents);
+                loop {
+                    let mut at = |position: usize| {
+                        positions.as_mut()[axis] = position;
+                        shape.index_from_positions(positions.as_ref())
+                    };
+                    let value = match boundary {
+                        Boundary::Periodic => data[at(inward(size))].clone(),
+                        Boundary::Zero => T::default(),
+                        Boundary::Reflect => data[at(inward(2 * distance - 1))].clone(),
+                        Boundary::Extrapolate => {
+                            let last = data[at(inward(1))].clone();
+                            let before = data[at(inward(2))].clone();
+                            last.clone() + (last - before)
+                        }
+                    };
+                    data[at(ghost)] = value;
+                    if !advance(positions.as_mut(), extents.as_ref(), axis) {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T, D, I: Copy> Index<I> for Grid<T, D> where D: GridDimensions<Index = I> {
+    type Output = T;
+    fn index(&self, index: I) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T, D, I: Copy> IndexMut<I> for Grid<T, D> where D: GridDimensions<Index = I> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        &mut self.data[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_1d(boundary: Boundary) -> Grid<i32, Range<i32>> {
+        let mut grid = Grid::new(-100, 0..5, 2);
+        for i in 0..5 {
+            grid[i] = 10 + i * i;
+        }
+        grid.fill_halo(boundary);
+        grid
+    }
+
+    fn values(grid: &Grid<i32, Range<i32>>) -> Vec<i32> {
+        grid.array().flat_iter().cloned().collect()
+    }
+
+    #[test]
+    fn shape() {
+        let grid = Grid::new(0, (0..4, 2..5, -1..1), (1, 0, 3));
+        assert_eq!(grid.interior_shape(), (0..4, 2..5, -1..1));
+        assert_eq!(grid.shape(), (-1..5, 2..5, -4..4));
+        assert_eq!(grid.halo(), (1, 0, 3));
+        assert_eq!(grid.interior().shape(), (0..4, 2..5, -1..1));
+    }
+
+    #[test]
+    fn faces() {
+        let mut grid = Grid::new(0, (0..4, 0..3), (2, 1));
+        assert_eq!(grid.face(0, Side::Lower).shape(), (-2..0, 0..3));
+        assert_eq!(grid.face(0, Side::Upper).shape(), (4..6, 0..3));
+        assert_eq!(grid.face(1, Side::Lower).shape(), (0..4, -1..0));
+        assert_eq!(grid.face(1, Side::Upper).shape(), (0..4, 3..4));
+
+        grid.face_mut(1, Side::Upper).fill(3);
+        assert_eq!(grid[(2, 3)], 3);
+        assert_eq!(grid[(-1, 3)], 0);
+        assert_eq!(grid.array().flat_iter().sum::<i32>(), 12);
+    }
+
+    #[test]
+    fn periodic() {
+        let grid = grid_1d(Boundary::Periodic);
+        assert_eq!(values(&grid), vec![19, 26, 10, 11, 14, 19, 26, 10, 11]);
+    }
+
+    #[test]
+    fn zero() {
+        let grid = grid_1d(Boundary::Zero);
+        assert_eq!(values(&grid), vec![0, 0, 10, 11, 14, 19, 26, 0, 0]);
+    }
+
+    #[test]
+    fn reflect() {
+        let grid = grid_1d(Boundary::Reflect);
+        assert_eq!(values(&grid), vec![11, 10, 10, 11, 14, 19, 26, 26, 19]);
+    }
+
+    #[test]
+    fn extrapolate() {
+        let grid = grid_1d(Boundary::Extrapolate);
+        assert_eq!(values(&grid), vec![8, 9, 10, 11, 14, 19, 26, 33, 40]);
+    }
+
+    #[test]
+    fn corners() {
+        let mut grid = Grid::new(0, (0..3, 0..2), (1, 1));
+        for i in 0..3 {
+            for j in 0..2 {
+                grid[(i, j)] = 10 * i + j;
+            }
+        }
+        grid.fill_halo(Boundary::Periodic);
+        assert_eq!(grid[(-1, -1)], 21);
+        assert_eq!(grid[(3, 2)], 0);
+        assert_eq!(grid[(-1, 2)], 20);
+        assert_eq!(grid[(3, -1)], 1);
+
+        // Mixed boundary conditions
+        grid.fill_halo_axis(0, Boundary::Zero);
+        grid.fill_halo_axis(1, Boundary::Reflect);
+        assert_eq!(grid[(-1, 0)], 0);
+        assert_eq!(grid[(1, -1)], 10);
+        assert_eq!(grid[(1, 2)], 11);
+    }
+
+    #[test]
+    fn integer_ranges() {
+        let mut grid = Grid::new(0, (1_u8..4, -2_i64..0), (1, 2));
+        assert_eq!(grid.shape(), (0..5, -4..2));
+        assert_eq!(grid.face(1, Side::Upper).shape(), (1..4, 0..2));
+        for i in 1..4 {
+            for j in -2..0 {
+                grid[(i, j)] = 10 * i as i64 + j;
+            }
+        }
+        grid.fill_halo(Boundary::Periodic);
+        assert_eq!(grid[(0, -2)], 28);
+        assert_eq!(grid[(4, 1)], 9);
+        assert_eq!(grid[(0, -4)], 28);
+    }
+
+    #[test]
+    #[should_panic(expected = "the grid with its halo can not be represented by the dimensions type")]
+    fn unsigned_halo() {
+        let _ = Grid::new(0, 0_u32..4, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "the halo width (3) is larger than the grid interior (2)")]
+    fn halo_too_large() {
+        let mut grid = Grid::new(0, 0..2, 3);
+        grid.fill_halo(Boundary::Periodic);
+    }
+}
@@ -0,0 +1,113 @@
+use std::iter::FromIterator;
+
+use {Array, ArrayBase, Dimensions, ShapeError, Storage};
+
+impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
+    /// Try to create a new array using the values from an iterator, in
+    /// storage order. This returns an error instead of panicking if the
+    /// iterator does not contain exactly the right number of elements.
+    ///
+    /// ```
+    /// use mudi::{Array, ShapeError};
+    /// let array = Array::from_iter_with_dims((2, -1..2), 0..6).unwrap();
+    /// assert_eq!(array[(1, -1)], 3);
+    ///
+    /// let error = Array::from_iter_with_dims((2, -1..2), 0..4).unwrap_err();
+    /// assert_eq!(error, ShapeError::TooShort { expected: 6, found: 4 });
+    /// ```
+    pub fn from_iter_with_dims<I>(dims: D, iter: I) -> Result<ArrayBase<S, D>, ShapeError>
+        where I: IntoIterator<Item = S::Item> {
+        let size = dims.size();
+        let mut iter = iter.into_iter();
+        let data = match S::try_from_iter(size, iter.by_ref()) {
+            Ok(data) => data,
+            Err(found) => return Err(ShapeError::TooShort {
+                expected: size,
+                found,
+            }),
+        };
+        if iter.next().is_some() {
+            return Err(ShapeError::TooLong {
+                expected: size,
+            });
+        }
+        Ok(ArrayBase::from_storage(data, dims))
+    }
+}
+
+/// Collect iterators directly into arrays with given dimensions. This trait
+/// is implemented for all iterators.
+///
+/// ```
+/// use mudi::{Array, CollectArray};
+/// let array = (0..6).map(|i| i * i).collect_array((2, 3));
+/// assert_eq!(array, Array::from_vector(vec![0, 1, 4, 9, 16, 25], (2, 3)));
+/// ```
+pub trait CollectArray: Iterator + Sized {
+    /// Collect the values of this iterator in a new array, in storage order.
+    ///
+    /// # Panics
+    /// If the length of the iterator does not match the size of the
+    /// dimensions. See `ArrayBase::from_iter_with_dims` for a non-panicking
+    /// version.
+    fn collect_array<D: Dimensions>(self, dims: D) -> Array<Self::Item, D> {
+        Array::from_shape_iter(dims, self)
+    }
+}
+
+impl<I: Iterator> CollectArray for I {}
+
+/// One-dimensional arrays can be collected from any iterator, with the
+/// size of the array given by the number of elements.
+///
+/// ```
+/// use mudi::Array;
+/// let array: Array<_, usize> = (0..5).filter(|i| i % 2 == 0).collect();
+/// assert_eq!(array.shape(), 3);
+/// assert_eq!(array[2], 4);
+/// ```
+impl<T> FromIterator<T> for Array<T, usize> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Array<T, usize> {
+        let values = iter.into_iter().collect::<Vec<_>>();
+        let size = values.len();
+        Array::from_vector(values, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {AlignedArray, Array, CollectArray, ShapeError};
+
+    #[test]
+    fn from_iter_with_dims() {
+        let array = Array::from_iter_with_dims((2, 1..=2), vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(array[(1, 1)], 3);
+
+        let array = AlignedArray::<_, _, 64>::from_iter_with_dims(4, 0..4).unwrap();
+        assert_eq!(array[3], 3);
+        let error = AlignedArray::<String, _, 64>::from_iter_with_dims(4, vec![String::new(); 3]).unwrap_err();
+        assert_eq!(error, ShapeError::TooShort { expected: 4, found: 3 });
+
+        let error = Array::<i32, _>::from_iter_with_dims((2, 2), 0..5).unwrap_err();
+        assert_eq!(error, ShapeError::TooLong { expected: 4 });
+        assert_eq!(error.to_string(), "expected 4 elements for the dimensions, got more");
+
+        let error = Array::<i32, _>::from_iter_with_dims((2, 2), 0..3).unwrap_err();
+        assert_eq!(error.to_string(), "expected 4 elements for the dimensions, got 3");
+    }
+
+    #[test]
+    fn collect() {
+        let array = "abcdef".chars().collect_array((-1..2, 2));
+        assert_eq!(array[(1, 0)], 'e');
+
+        let array = (0..4).rev().collect::<Array<_, usize>>();
+        assert_eq!(array, Array::from_vector(vec![3, 2, 1, 0], 4));
+    }
+
+    #[test]
+    #[should_panic(expected = "Iterator length does not match the dimensions")]
+    fn collect_mismatch() {
+        let _ = (0..7).collect_array((2, 3));
+    }
+}
@@ -0,0 +1,200 @@
+use {ArrayBase, ArrayView, Dimensions, InsertAxis, Storage};
+
+/// Check that all the `views` have the same extents as `dims`, except
+/// along `skip` if it is given.
+fn check_extents<T, D: Dimensions>(dims: &D, views: &[ArrayView<'_, T, D>], skip: Option<usize>) {
+    for view in views {
+        let shape = view.shape();
+        for axis in 0..dims.rank() {
+            if Some(axis) != skip {
+                assert!(shape.extent(axis) == dims.extent(axis),
+                        "extent mismatch along axis {}: expected {}, got {}",
+                        axis, dims.extent(axis), shape.extent(axis));
+            }
+        }
+    }
+}
+
+impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage, S::Item: Clone {
+    /// Create a new array by replicating this array `ncopies` times along a
+    /// new axis at position `AXIS`, like the Fortran
+    /// `SPREAD(source, dim, ncopies)` intrinsic with axes numbered from 0. The
+    /// new axis is a `0..ncopies` dimension, and the other axes keep their
+    /// bounds.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_vector(vec![1, 2, 3], -1..2);
+    ///
+    /// let spread = array.spread::<0>(2);
+    /// assert_eq!(spread, Array::from_vector(vec![1, 2, 3, 1, 2, 3], (2, -1..2)));
+    ///
+    /// let spread = array.spread::<1>(2);
+    /// assert_eq!(spread, Array::from_vector(vec![1, 1, 2, 2, 3, 3], (-1..2, 2)));
+    /// ```
+    pub fn spread<const AXIS: usize>(&self, ncopies: usize) -> ArrayBase<S, D::Output> where D: InsertAxis<AXIS> {
+        ArrayBase::from_fn(self.shape().insert_axis(ncopies), |index| {
+            let (_, index) = D::split_index(index);
+            self[index].clone()
+        })
+    }
+
+    /// Create a new array by joining `views` along an existing `axis`. The
+    /// views must have the same extents along all the other axes.
+    ///
+    /// The result uses the bounds of the first view: the lower bound of
+    /// `axis` is the lower bound of the first view, and its upper bound is
+    /// extended to fit all the views. The other views are placed in order
+    /// after the first one, regardless of their own bounds.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let a = Array::from_vector(vec![1, 2, 3, 4], (1..3, 2));
+    /// let b = Array::from_vector(vec![5, 6], (7..8, 2));
+    ///
+    /// let joined = Array::concatenate(0, &[a.view(), b.view()]);
+    /// assert_eq!(joined.shape(), (1..4, 2));
+    /// assert_eq!(joined[(3, 0)], 5);
+    ///
+    /// let joined = Array::concatenate(1, &[a.view(), a.view()]);
+    /// assert_eq!(joined, Array::from_vector(vec![1, 2, 1, 2, 3, 4, 3, 4], (1..3, 4)));
+    /// ```
+    ///
+    /// # Panics
+    /// If `views` is empty, if `axis` is out of bound or if the extents of
+    /// the views do not match along the other axes.
+    pub fn concatenate(axis: usize, views: &[ArrayView<'_, S::Item, D>]) -> ArrayBase<S, D> {
+        assert!(!views.is_empty(), "can not concatenate an empty list of arrays");
+        let first = views[0].shape();
+        check_extents(&first, views, Some(axis));
+        let extent = views.iter().map(|view| view.shape().extent(axis)).sum();
+        let dims = first.with_extent(axis, extent);
+        ArrayBase::from_fn(dims.clone(), |index| {
+            let mut positions = dims.positions(index);
+            let mut position = positions.as_ref()[axis];
+            for view in views {
+                let shape = view.shape();
+                if position < shape.extent(axis) {
+                    positions.as_mut()[axis] = position;
+                    return view[shape.index_from_positions(positions.as_ref())].clone();
+                }
+                position -= shape.extent(axis);
+            }
+            unreachable!("position is inside one of the views");
+        })
+    }
+
+    /// Create a new array by joining `views` along a new axis at position
+    /// `AXIS`. The new axis is a `0..views.len()` dimension, and the other
+    /// axes use the bounds of the first view. All the views must have the
+    /// same extents.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let a = Array::from_vector(vec![1, 2, 3], -1..2);
+    /// let b = Array::from_vector(vec![4, 5, 6], 0..3);
+    ///
+    /// let stacked = Array::stack::<0>(&[a.view(), b.view()]);
+    /// assert_eq!(stacked, Array::from_vector(vec![1, 2, 3, 4, 5, 6], (2, -1..2)));
+    ///
+    /// let stacked = Array::stack::<1>(&[a.view(), b.view()]);
+    /// assert_eq!(stacked[(1, 1)], 6);
+    /// ```
+    ///
+    /// # Panics
+    /// If `views` is empty or if the extents of the views do not match.
+    pub fn stack<const AXIS: usize>(views: &[ArrayView<'_, S::Item, D>]) -> ArrayBase<S, D::Output> where D: InsertAxis<AXIS> {
+        assert!(!views.is_empty(), "can not stack an empty list of arrays");
+        let first = views[0].shape();
+        check_extents(&first, views, None);
+        ArrayBase::from_fn(first.insert_axis(views.len()), |index| {
+            let (view, index) = D::split_index(index);
+            let shape = views[view].shape();
+            views[view][shape.index_from_positions(first.positions(index).as_ref())].clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Array, Periodic};
+
+    #[test]
+    fn spread() {
+        let array = Array::from_fn((2, -1..2), |(i, j)| 10 * i as i32 + j);
+
+        let spread = array.spread::<0>(3);
+        assert_eq!(spread.shape(), (3, 2, -1..2));
+        let spread = array.spread::<1>(3);
+        assert_eq!(spread.shape(), (2, 3, -1..2));
+        let spread = array.spread::<2>(3);
+        assert_eq!(spread.shape(), (2, -1..2, 3));
+        for i in 0..2 {
+            for j in -1..2 {
+                for k in 0..3 {
+                    assert_eq!(spread[(i, j, k)], array[(i, j)]);
+                }
+            }
+        }
+
+        assert_eq!(array.spread::<1>(0).shape(), (2, 0, -1..2));
+    }
+
+    #[test]
+    fn concatenate() {
+        let a = Array::from_fn((2, -1..2), |(i, j)| 10 * i as i32 + j);
+        let b = Array::from_fn((1, 0..3), |(i, j)| 100 + 10 * i as i32 + j);
+        let joined = Array::concatenate(0, &[a.view(), b.view()]);
+        assert_eq!(joined.shape(), (3, -1..2));
+        assert_eq!(joined.flat_iter().cloned().collect::<Vec<_>>(), vec![-1, 0, 1, 9, 10, 11, 100, 101, 102]);
+
+        let a = Array::from_fn((0..2, -1..2), |(i, j)| 10 * i + j);
+        let c = Array::from_element(0, (0..2, 5..6));
+        let joined = Array::concatenate(1, &[a.view(), c.view(), a.subview((0..2, 1..2))]);
+        assert_eq!(joined.shape(), (0..2, -1..4));
+        assert_eq!(joined.flat_iter().cloned().collect::<Vec<_>>(), vec![-1, 0, 1, 0, 1, 9, 10, 11, 0, 11]);
+
+        let periodic = Array::from_vector(vec![1, 2], Periodic(-1..1));
+        let joined = Array::concatenate(0, &[periodic.view(), periodic.view()]);
+        assert_eq!(joined.shape(), Periodic(-1..3));
+        assert_eq!(joined[-2], 2);
+    }
+
+    #[test]
+    fn concatenate_inclusive() {
+        let a = Array::from_element(1, 1..=2);
+        let b = Array::from_element(2, 1..=3);
+        let joined = Array::concatenate(0, &[a.view(), b.view()]);
+        assert_eq!(joined, Array::from_vector(vec![1, 1, 2, 2, 2], 1..=5));
+    }
+
+    #[test]
+    #[should_panic(expected = "extent mismatch along axis 1: expected 3, got 2")]
+    fn concatenate_mismatch() {
+        let a = Array::from_element(0, (2, 3));
+        let b = Array::from_element(0, (2, 2));
+        let _ = Array::concatenate(0, &[a.view(), b.view()]);
+    }
+
+    #[test]
+    fn stack() {
+        let a = Array::from_fn((2, -1..2), |(i, j)| 10 * i as i32 + j);
+        let b = Array::from_fn((2, 0..3), |(i, j)| 100 + 10 * i as i32 + j);
+
+        let stacked = Array::stack::<1>(&[a.view(), b.view()]);
+        assert_eq!(stacked.shape(), (2, 2, -1..2));
+        assert_eq!(stacked[(1, 0, 1)], 11);
+        assert_eq!(stacked[(1, 1, 1)], 112);
+
+        let stacked = Array::stack::<2>(&[a.view(), b.view(), a.view()]);
+        assert_eq!(stacked.shape(), (2, -1..2, 3));
+        assert_eq!(stacked[(0, -1, 1)], 100);
+        assert_eq!(stacked[(0, -1, 2)], -1);
+    }
+
+    #[test]
+    #[should_panic(expected = "can not stack an empty list of arrays")]
+    fn stack_empty() {
+        let _ = Array::<i32, (usize, usize, usize)>::stack::<0>(&[]);
+    }
+}
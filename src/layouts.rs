@@ -0,0 +1,368 @@
+use std::cmp::min;
+
+use Dimensions;
+
+/// Tiled storage for the `D` dimensions, where the elements are stored by
+/// blocks. The tiles are stored in row-major order, and the elements in each
+/// tile are also stored in row-major order. The tiles on the upper edge of an
+/// axis are cut to the extent of the axis, so no storage is wasted.
+///
+/// Indexing is the same as with `D`; only the order of the elements in
+/// storage changes. Tiles are meant for rank 2 and 3 dimensions, where they
+/// keep neighbouring elements along all the axes close in memory.
+///
+/// ```
+/// use mudi::{Array, Tiled};
+/// let array = Array::from_fn(Tiled::new((4, -1..3), [2, 2]), |(i, j)| 10 * i as i32 + j);
+/// // The first tile contains (0..2, -1..1)
+/// assert_eq!(array.flat_iter().take(4).cloned().collect::<Vec<_>>(), vec![-1, 0, 9, 10]);
+/// assert_eq!(array[(3, 2)], 32);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tiled<D> where D: Dimensions {
+    dims: D,
+    tile: D::Extents,
+}
+
+impl<D> Eq for Tiled<D> where D: Dimensions + Eq {}
+
+impl<D: Dimensions> Tiled<D> {
+    /// Create tiled dimensions, using tiles with `tile` elements along each
+    /// axis.
+    ///
+    /// # Panics
+    /// If the tile extent along any axis is 0.
+    pub fn new(dims: D, tile: D::Extents) -> Tiled<D> {
+        assert!(tile.as_ref().iter().all(|&extent| extent > 0), "tiles must contain at least one element along each axis");
+        Tiled {
+            dims,
+            tile,
+        }
+    }
+
+    /// Get the dimensions stored in tiles
+    pub fn dims(&self) -> &D {
+        &self.dims
+    }
+
+    /// Get the extents of a tile
+    pub fn tile(&self) -> D::Extents {
+        self.tile.clone()
+    }
+}
+
+impl<D: Dimensions> Dimensions for Tiled<D> {
+    type Index = D::Index;
+    type Bounds = D::Bounds;
+    type Extents = D::Extents;
+
+    #[inline(always)]
+    fn offset(&self, index: Self::Index) -> usize {
+        let positions = self.dims.positions(index);
+        let positions = positions.as_ref();
+        let tile = self.tile.as_ref();
+        // Skip the tiles before `index`, one axis at a time. The size of the
+        // tiles skipped along each axis depends on the (cut) extents of the
+        // tile containing `index` along the previous axes.
+        let mut offset = 0;
+        let mut inner = 1;
+        let mut outer = self.dims.size();
+        for (axis, &position) in positions.iter().enumerate() {
+            let extent = self.dims.extent(axis);
+            let start = position - position % tile[axis];
+            outer /= extent;
+            offset += start * inner * outer;
+            inner *= min(tile[axis], extent - start);
+        }
+        // Row-major offset in the tile containing `index`
+        let mut in_tile = 0;
+        for (axis, &position) in positions.iter().enumerate() {
+            let extent = self.dims.extent(axis);
+            let start = position - position % tile[axis];
+            in_tile = in_tile * min(tile[axis], extent - start) + position - start;
+        }
+        offset + in_tile
+    }
+
+    #[inline(always)]
+    fn index(&self, offset: usize) -> Self::Index {
+        assert!(offset < self.size(), "offset out of bound: size is {} but offset is {}",
+                self.size(), offset);
+        let tile = self.tile.as_ref();
+        let mut starts = self.dims.extents();
+        let mut cut = self.dims.extents();
+        let mut rest = offset;
+        let mut inner = 1;
+        let mut outer = self.dims.size();
+        for (axis, &size) in tile.iter().enumerate() {
+            let extent = self.dims.extent(axis);
+            outer /= extent;
+            let skipped = size * inner * outer;
+            let start = rest / skipped * size;
+            rest %= skipped;
+            inner *= min(size, extent - start);
+            starts.as_mut()[axis] = start;
+            cut.as_mut()[axis] = min(size, extent - start);
+        }
+        let mut positions = starts;
+        for (axis, position) in positions.as_mut().iter_mut().enumerate().rev() {
+            let extent = cut.as_ref()[axis];
+            *position += rest % extent;
+            rest /= extent;
+        }
+        self.dims.index_from_positions(positions.as_ref())
+    }
+
+    #[inline(always)]
+    fn size(&self) -> usize {
+        self.dims.size()
+    }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        self.dims.lbound(axis)
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        self.dims.extent(axis)
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        Tiled::new(self.dims.with_extent(axis, extent), self.tile.clone())
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> Self::Extents {
+        self.dims.positions(index)
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        self.dims.index_from_positions(positions)
+    }
+
+    fn strides(&self) -> Option<Self::Extents> {
+        None
+    }
+}
+
+/// Morton (Z-order) storage for the `D` dimensions, where the offset of an
+/// element interleaves the bits of its positions along each axis. The last
+/// axis uses the lowest bit, like in row-major storage. Axes with fewer bits
+/// than the others stop contributing to the offset once their bits are used.
+///
+/// Indexing is the same as with `D`; only the order of the elements in
+/// storage changes. The extents of all the axes must be powers of two, use
+/// [`Tiled`](struct.Tiled.html) storage for other extents.
+///
+/// ```
+/// use mudi::{Array, Morton};
+/// let array = Array::from_fn(Morton::new((4, 4)), |(i, j)| 10 * i + j);
+/// assert_eq!(array.flat_iter().take(8).cloned().collect::<Vec<_>>(), vec![0, 1, 10, 11, 2, 3, 12, 13]);
+/// assert_eq!(array[(3, 3)], 33);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Morton<D>(D);
+
+impl<D: Dimensions> Morton<D> {
+    /// Create Morton dimensions.
+    ///
+    /// # Panics
+    /// If the extent of any axis is not a power of two.
+    pub fn new(dims: D) -> Morton<D> {
+        for axis in 0..dims.rank() {
+            let extent = dims.extent(axis);
+            assert!(extent.is_power_of_two(), "Morton storage requires extents which are powers of two, got {} along axis {}",
+                    extent, axis);
+        }
+        Morton(dims)
+    }
+
+    /// Get the dimensions stored in Morton order
+    pub fn dims(&self) -> &D {
+        &self.0
+    }
+
+    /// Get the number of bits of the positions along `axis`
+    #[inline(always)]
+    fn bits(&self, axis: usize) -> u32 {
+        self.0.extent(axis).trailing_zeros()
+    }
+
+    /// Get the number of bits of the positions along the largest axis
+    #[inline(always)]
+    fn max_bits(&self) -> u32 {
+        (0..self.rank()).map(|axis| self.bits(axis)).max().unwrap_or(0)
+    }
+}
+
+impl<D: Dimensions> Dimensions for Morton<D> {
+    type Index = D::Index;
+    type Bounds = D::Bounds;
+    type Extents = D::Extents;
+
+    #[inline(always)]
+    fn offset(&self, index: Self::Index) -> usize {
+        let positions = self.0.positions(index);
+        let positions = positions.as_ref();
+        let mut offset = 0;
+        let mut shift = 0;
+        for bit in 0..self.max_bits() {
+            for axis in (0..positions.len()).rev() {
+                if bit < self.bits(axis) {
+                    offset |= ((positions[axis] >> bit) & 1) << shift;
+                    shift += 1;
+                }
+            }
+        }
+        offset
+    }
+
+    #[inline(always)]
+    fn index(&self, offset: usize) -> Self::Index {
+        assert!(offset < self.size(), "offset out of bound: size is {} but offset is {}",
+                self.size(), offset);
+        let mut positions = self.0.extents();
+        for position in positions.as_mut() {
+            *position = 0;
+        }
+        let mut shift = 0;
+        for bit in 0..self.max_bits() {
+            for (axis, position) in positions.as_mut().iter_mut().enumerate().rev() {
+                if bit < self.bits(axis) {
+                    *position |= ((offset >> shift) & 1) << bit;
+                    shift += 1;
+                }
+            }
+        }
+        self.0.index_from_positions(positions.as_ref())
+    }
+
+    #[inline(always)]
+    fn size(&self) -> usize {
+        self.0.size()
+    }
+
+    #[inline(always)]
+    fn lbound(&self, axis: usize) -> isize {
+        self.0.lbound(axis)
+    }
+
+    #[inline(always)]
+    fn extent(&self, axis: usize) -> usize {
+        self.0.extent(axis)
+    }
+
+    fn with_extent(&self, axis: usize, extent: usize) -> Self {
+        Morton::new(self.0.with_extent(axis, extent))
+    }
+
+    #[inline(always)]
+    fn positions(&self, index: Self::Index) -> Self::Extents {
+        self.0.positions(index)
+    }
+
+    #[inline(always)]
+    fn index_from_positions(&self, positions: &[usize]) -> Self::Index {
+        self.0.index_from_positions(positions)
+    }
+
+    fn strides(&self) -> Option<Self::Extents> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {Array, ColumnMajor};
+
+    /// Check that `offset` and `index` are inverse of each other, and that
+    /// all offsets are used
+    fn check_bijection<D: Dimensions>(dims: &D) {
+        for offset in 0..dims.size() {
+            assert_eq!(dims.offset(dims.index(offset)), offset);
+        }
+    }
+
+    #[test]
+    fn tiled() {
+        let dims = Tiled::new((3, 5), [2, 2]);
+        let array = Array::from_fn(dims.clone(), |(i, j)| 10 * i + j);
+        assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![
+            0, 1, 10, 11,  2, 3, 12, 13,  4, 14,
+            20, 21,  22, 23,  24,
+        ]);
+        check_bijection(&dims);
+        check_bijection(&Tiled::new((-3..4, 5), [3, 8]));
+        check_bijection(&Tiled::new((7, 1..6, 4), [2, 3, 4]));
+        check_bijection(&Tiled::new((5, 6, 7), [4, 4, 4]));
+        assert_eq!(dims.strides(), None);
+
+        // Tiles larger than the array give row-major storage
+        let large = Array::from_fn(Tiled::new((3, 4), [8, 8]), |(i, j)| 10 * i + j);
+        assert_eq!(large.flat_iter().collect::<Vec<_>>(), Array::from_fn((3, 4), |(i, j)| 10 * i + j).flat_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn morton() {
+        let dims = Morton::new((2, 8));
+        let array = Array::from_fn(dims.clone(), |(i, j)| 10 * i + j);
+        assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![
+            0, 1, 10, 11,  2, 3, 12, 13,  4, 5, 14, 15,  6, 7, 16, 17,
+        ]);
+        check_bijection(&dims);
+        check_bijection(&Morton::new((-4..4, 2, 0..16)));
+        check_bijection(&Morton::new((1, 4)));
+        assert_eq!(Morton::new((8, 8, 8)).offset((1, 1, 1)), 7);
+        assert_eq!(Morton::new((8, 8, 8)).offset((4, 0, 0)), 256);
+        assert_eq!(dims.strides(), None);
+    }
+
+    #[test]
+    fn views() {
+        let array = Array::from_fn(Tiled::new((-2..6, 0..8), [4, 4]), |(i, j)| 10 * i + j);
+        let view = array.subview(Tiled::new((0..3, 1..3), [4, 4]));
+        assert_eq!(view[(2, 1)], 21);
+        assert_eq!(view.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 11, 12, 21, 22]);
+
+        let mut array = Array::from_element(0, Morton::new((0..4, -4..4)));
+        array.subview_mut(Morton::new((0..2, -2..0))).fill(1);
+        assert_eq!(array.flat_iter().filter(|&&value| value == 1).count(), 4);
+        assert_eq!(array[(1, -1)], 1);
+    }
+
+    #[test]
+    fn to_layout() {
+        let array = Array::from_fn((-1..3, 5), |(i, j)| 10 * i + j as i32);
+        let tiled = array.to_layout(Tiled::new((-1..3, 5), [2, 4]));
+        assert_eq!(tiled[(2, 4)], 24);
+        assert_eq!(tiled.to_layout((-1..3, 5)), array);
+
+        let column = tiled.to_layout(ColumnMajor((-1..3, 5)));
+        assert_eq!(column.flat_iter().take(4).cloned().collect::<Vec<_>>(), vec![-10, 0, 10, 20]);
+
+        let morton = Array::from_fn(Morton::new((4, 2)), |(i, j)| 10 * i + j);
+        assert_eq!(morton.to_layout((4, 2)), Array::from_fn((4, 2), |(i, j)| 10 * i + j));
+    }
+
+    #[test]
+    #[should_panic(expected = "Morton storage requires extents which are powers of two, got 6 along axis 1")]
+    fn morton_extents() {
+        let _ = Morton::new((4, 6));
+    }
+
+    #[test]
+    #[should_panic(expected = "tiles must contain at least one element along each axis")]
+    fn empty_tile() {
+        let _ = Tiled::new((4, 6), [2, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "windows and stencils require dimensions with strided storage")]
+    fn windows() {
+        let array = Array::from_element(0, Tiled::new((4, 4), [2, 2]));
+        let _ = array.windows([2, 2]);
+    }
+}
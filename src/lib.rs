@@ -48,7 +48,9 @@
 //! Dimensions are represented by tuples of either single `usize` value, or
 //! ranges. `3` and `10..30` are dimensions for 1-dimensional arrays, and
 //! `(3, 4, 5)` or `(-20..20, 5, 6..8)` are dimensions for 3-dimensional arrays.
-//! Indexing is implemented for tuple dimensions up to 7-dimensional arrays.
+//! Ranges can use any primitive integer type, and inclusive ranges (`1..=10`)
+//! follow the Fortran `lbound:ubound` convention. Indexing is implemented for
+//! tuple dimensions up to 7-dimensional arrays.
 //!
 //! ```
 //! # #[macro_use]
@@ -63,15 +65,92 @@
 //! assert_eq!(a[(1, 2, -6)], 42.0);
 //! # }
 //! ```
+//!
+//! # Optional features
+//!
+//! - `approx`: implement the `AbsDiffEq`, `RelativeEq` and `UlpsEq` traits
+//!   from the [`approx`](https://docs.rs/approx) crate for arrays.
+//! - `capi`: expose a C API for arrays with dynamic dimensions in the
+//!   [`capi`](capi/index.html) module, declared in the `include/mudi.h`
+//!   header.
+//! - `ndarray`: convert arrays and views to and from the
+//!   [`ndarray`](https://docs.rs/ndarray) crate types. Views are converted
+//!   without copying the data; the lower bounds are dropped when going to
+//!   `ndarray`, and given back with the `from_ndarray` constructors.
+//! - `nalgebra`: convert rank-2 arrays and views to and from `DMatrix` and
+//!   `DMatrixView` in the [`nalgebra`](https://docs.rs/nalgebra) crate, to
+//!   use its decompositions on blocks of arrays.
+
+#[cfg(feature = "approx")]
+#[cfg_attr(test, macro_use)]
+extern crate approx;
+#[cfg(feature = "ndarray")]
+extern crate ndarray;
+#[cfg(feature = "nalgebra")]
+extern crate nalgebra;
 
 mod dimensions;
-pub use dimensions::Dimensions;
+pub use dimensions::{Dimensions, FromBounds, InsertAxis, Periodic, ColumnMajor, IndexEnum, Enumerated};
+
+mod dynamic;
+pub use dynamic::{DynDims, DynList, DynIndex, MAX_DYN_RANK};
+
+mod packed;
+pub use packed::{UpperTriangular, LowerTriangular, Symmetric, Banded};
+
+mod sparse;
+pub use sparse::{CooMatrix, CsrMatrix};
+
+mod layouts;
+pub use layouts::{Tiled, Morton};
 
 mod storage;
-pub use storage::Storage;
+pub use storage::{Storage, AlignedStorage};
 
 mod arrays;
-pub use arrays::{Array, ArrayBase};
+pub use arrays::{Array, AlignedArray, ArrayBase};
+
+mod error;
+pub use error::{ShapeError, LayoutError};
+
+mod iter;
+pub use iter::CollectArray;
+
+mod close;
+pub use close::{CloseError, Float};
+
+mod shift;
+mod join;
+
+mod masked;
+pub use masked::{Elsewhere, Mask};
+
+mod bits;
+pub use bits::{BitStorage, BitArray, BitMut};
+
+mod views;
+pub use views::{ArrayView, ArrayViewMut, ViewIter, ViewIterMut};
+
+mod zip;
+pub use zip::{Zip, IndexedZip, ZipProducer};
+
+mod stencil;
+pub use stencil::{Windows, Window, WindowIter, Stencil, StencilBoundary, Neighborhood};
+
+mod grid;
+pub use grid::{Grid, GridDimensions, Boundary, Side};
+
+#[cfg(feature = "ndarray")]
+mod ndarray_impl;
+#[cfg(feature = "ndarray")]
+pub use ndarray_impl::{NdarrayRank, NdarrayDim};
+#[cfg(feature = "nalgebra")]
+mod nalgebra_impl;
+
+pub mod ffi;
+
+#[cfg(feature = "capi")]
+pub mod capi;
 
 #[macro_use]
 mod macros;
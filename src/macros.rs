@@ -37,6 +37,223 @@ macro_rules! array {
     );
 }
 
+#[macro_export]
+/// Defines an enum implementing `IndexEnum`, to be used as index for an
+/// `Enumerated` dimension.
+///
+/// The enum variants must not have data or explicit discriminants. The enum
+/// automatically derives `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq` and
+/// `Hash`.
+///
+/// ```
+/// # #[macro_use] extern crate mudi;
+/// use mudi::{Array, Enumerated, IndexEnum};
+///
+/// index_enum! {
+///     /// Components of a 3D vector
+///     pub enum Component { X, Y, Z }
+/// }
+///
+/// # fn main() {
+/// assert_eq!(Component::COUNT, 3);
+/// assert_eq!(Component::Y.ordinal(), 1);
+/// assert_eq!(Component::from_ordinal(2), Component::Z);
+///
+/// let array = Array::from_fn((Enumerated::<Component>::new(), 4), |(c, i)| {
+///     c.ordinal() * i
+/// });
+/// assert_eq!(array[(Component::Z, 3)], 6);
+/// # }
+/// ```
+macro_rules! index_enum {
+    (
+        $(#[$attr: meta])*
+        $visibility: vis enum $name: ident {
+            $($(#[$variant_attr: meta])* $variant: ident),+ $(,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        $visibility enum $name {
+            $($(#[$variant_attr])* $variant),+
+        }
+
+        impl $crate::IndexEnum for $name {
+            const COUNT: usize = [$($name::$variant),+].len();
+
+            fn ordinal(self) -> usize {
+                self as usize
+            }
+
+            fn from_ordinal(ordinal: usize) -> $name {
+                const VALUES: &[$name] = &[$($name::$variant),+];
+                assert!(ordinal < VALUES.len(), "invalid ordinal {} for {}", ordinal, stringify!($name));
+                VALUES[ordinal]
+            }
+        }
+    };
+}
+
+#[macro_export]
+/// Defines a record struct, and a structure-of-arrays container storing one
+/// `Array` for each field of the record, with the same dimensions.
+///
+/// The container is generic over the dimensions, and its fields are arrays
+/// with the same names and visibility as the record fields, which gives
+/// direct access to per-field views and to contiguous data for vectorized
+/// loops. All the arrays must keep the dimensions of the container.
+///
+/// `Index` can not return a proxy, so whole records are accessed with `get`,
+/// `set`, `at` and `at_mut`. `at` and `at_mut` return proxy structs with a
+/// reference to each field, named in the macro invocation. The container also
+/// provides `from_fn`, `from_element`, `shape`, `from_records` and
+/// `to_records`. The field types must implement `Clone`.
+///
+/// This is a declarative macro, and not a `#[derive]`: a derive would need a
+/// separate procedural macro crate. The record struct is thus declared inside
+/// of the macro invocation, together with the container.
+///
+/// ```
+/// use mudi::Array;
+///
+/// mudi::soa! {
+///     /// Fluid state in a cell
+///     #[derive(Debug, Clone, PartialEq)]
+///     pub struct Cell { pub rho: f64, pub u: f64, pub p: f64 }
+///     /// Fluid state in all the cells
+///     pub struct Cells<D> { ref CellRef, mut CellMut }
+/// }
+///
+/// # fn main() {
+/// let mut cells = Cells::from_fn((-1..3, 0..4), |(i, j)| Cell { rho: 1.0, u: i as f64, p: j as f64 });
+/// *cells.at_mut((0, 2)).rho = 2.0;
+/// assert_eq!(*cells.at((0, 2)).rho, 2.0);
+/// assert_eq!(cells.get((-1, 3)), Cell { rho: 1.0, u: -1.0, p: 3.0 });
+///
+/// // Each field is an array with the same dimensions
+/// let total = cells.rho.flat_iter().sum::<f64>();
+/// assert_eq!(total, 17.0);
+/// let velocity = cells.u.subview((0..2, 1..3));
+/// assert_eq!(velocity[(1, 1)], 1.0);
+/// # }
+/// ```
+macro_rules! soa {
+    (
+        $(#[$attr: meta])*
+        $visibility: vis struct $name: ident {
+            $($field_visibility: vis $field: ident : $type: ty),+ $(,)*
+        }
+        $(#[$container_attr: meta])*
+        $container_visibility: vis struct $container: ident < $dims: ident > {
+            ref $refs: ident, mut $muts: ident $(,)*
+        }
+    ) => {
+        $(#[$attr])*
+        $visibility struct $name {
+            $($field_visibility $field: $type),+
+        }
+
+        $(#[$container_attr])*
+        $container_visibility struct $container<$dims> where $dims: $crate::Dimensions {
+            $(
+                #[doc = concat!("Values of the `", stringify!($field), "` field")]
+                $field_visibility $field: $crate::Array<$type, $dims>
+            ),+
+        }
+
+        #[doc = concat!("References to the fields of a [`", stringify!($name), "`] stored in a [`",
+                        stringify!($container), "`]")]
+        $container_visibility struct $refs<'a> {
+            $(
+                #[doc = concat!("Reference to the `", stringify!($field), "` field")]
+                $field_visibility $field: &'a $type
+            ),+
+        }
+
+        #[doc = concat!("Mutable references to the fields of a [`", stringify!($name), "`] stored in a [`",
+                        stringify!($container), "`]")]
+        $container_visibility struct $muts<'a> {
+            $(
+                #[doc = concat!("Mutable reference to the `", stringify!($field), "` field")]
+                $field_visibility $field: &'a mut $type
+            ),+
+        }
+
+        #[allow(dead_code)]
+        impl<$dims> $container<$dims> where $dims: $crate::Dimensions {
+            /// Create a new container by calling `function` with the index of
+            /// each record, in storage order.
+            pub fn from_fn<F>(dims: $dims, mut function: F) -> $container<$dims>
+                where F: FnMut(<$dims as $crate::Dimensions>::Index) -> $name {
+                let size = $crate::Dimensions::size(&dims);
+                $(let mut $field = Vec::with_capacity(size);)+
+                for offset in 0..size {
+                    let record = function($crate::Dimensions::index(&dims, offset));
+                    $($field.push(record.$field);)+
+                }
+                $container {
+                    $($field: $crate::Array::from_vector($field, dims.clone())),+
+                }
+            }
+
+            /// Create a new container with all the records equal to `record`.
+            pub fn from_element(record: $name, dims: $dims) -> $container<$dims> {
+                $container {
+                    $($field: $crate::Array::from_element(record.$field.clone(), dims.clone())),+
+                }
+            }
+
+            /// Create a new container from an array of records.
+            pub fn from_records<S>(records: &$crate::ArrayBase<S, $dims>) -> $container<$dims>
+                where S: $crate::Storage<Item = $name> {
+                let dims = records.shape();
+                $container {
+                    $($field: $crate::Array::from_shape_iter(dims.clone(), records.flat_iter().map(|record| record.$field.clone()))),+
+                }
+            }
+
+            /// Copy the records in an array of records.
+            pub fn to_records(&self) -> $crate::Array<$name, $dims> {
+                $crate::Array::from_fn(self.shape(), |index| self.get(index))
+            }
+
+            /// Get the shape of the container.
+            pub fn shape(&self) -> $dims {
+                $crate::soa!(@first self, $($field)+).shape()
+            }
+
+            /// Get a copy of the record at `index`.
+            pub fn get(&self, index: <$dims as $crate::Dimensions>::Index) -> $name {
+                $name {
+                    $($field: self.$field[index].clone()),+
+                }
+            }
+
+            /// Set the record at `index`.
+            pub fn set(&mut self, index: <$dims as $crate::Dimensions>::Index, record: $name) {
+                $(self.$field[index] = record.$field;)+
+            }
+
+            /// Get references to the fields of the record at `index`.
+            pub fn at(&self, index: <$dims as $crate::Dimensions>::Index) -> $refs<'_> {
+                $refs {
+                    $($field: &self.$field[index]),+
+                }
+            }
+
+            /// Get mutable references to the fields of the record at `index`.
+            pub fn at_mut(&mut self, index: <$dims as $crate::Dimensions>::Index) -> $muts<'_> {
+                $muts {
+                    $($field: &mut self.$field[index]),+
+                }
+            }
+        }
+    };
+    (@first $self: ident, $first: ident $($rest: ident)*) => {
+        $self.$first
+    };
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -50,9 +267,74 @@ mod tests {
     }
 
     #[test]
+    #[allow(semicolon_in_expressions_from_macros)]
     fn from_vector() {
         let array = array![0.0, 1.0, 2.0, 3.0; (2, 2)];
 
         assert_eq!(array, ::Array::from_vector(vec![0.0, 1.0, 2.0, 3.0], (2, 2)));
     }
+
+    index_enum! {
+        enum Axis {
+            /// First axis
+            X,
+            Y,
+        }
+    }
+
+    #[test]
+    fn index_enum() {
+        use {IndexEnum, Enumerated};
+        assert_eq!(Axis::COUNT, 2);
+        assert_eq!(Axis::X.ordinal(), 0);
+        assert_eq!(Axis::from_ordinal(1), Axis::Y);
+
+        let mut array = array![0; (2, Enumerated::<Axis>::new())];
+        array[(1, Axis::Y)] = 5;
+        assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![0, 0, 0, 5]);
+    }
+
+    soa! {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Particle {
+            mass: f32,
+            tag: String,
+        }
+        struct Particles<D> { ref ParticleRef, mut ParticleMut }
+    }
+
+    #[test]
+    fn soa() {
+        let mut particles = Particles::from_fn(-2..2, |i: i32| Particle { mass: i as f32, tag: i.to_string() });
+        assert_eq!(particles.shape(), -2..2);
+        assert_eq!(particles.mass.flat_iter().cloned().collect::<Vec<_>>(), vec![-2.0, -1.0, 0.0, 1.0]);
+        assert_eq!(particles.get(-1), Particle { mass: -1.0, tag: String::from("-1") });
+
+        particles.set(0, Particle { mass: 4.0, tag: String::from("four") });
+        {
+            let particle = particles.at_mut(1);
+            *particle.mass *= 2.0;
+            particle.tag.push('!');
+        }
+        let particle = particles.at(1);
+        assert_eq!((*particle.mass, particle.tag.as_str()), (2.0, "1!"));
+        assert_eq!(particles.tag[0], "four");
+
+        let records = particles.to_records();
+        assert_eq!(records[0].mass, 4.0);
+        let copy = Particles::from_records(&records);
+        assert_eq!(copy.mass, particles.mass);
+        assert_eq!(copy.tag, particles.tag);
+
+        let empty = Particles::from_element(Particle { mass: 0.0, tag: String::new() }, (3, 0..2));
+        assert_eq!(empty.mass.shape(), (3, 0..2));
+        assert_eq!(empty.tag.view().iter().count(), 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid ordinal 2 for Axis")]
+    fn invalid_ordinal() {
+        use IndexEnum;
+        Axis::from_ordinal(2);
+    }
 }
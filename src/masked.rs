@@ -0,0 +1,294 @@
+use {Array, ArrayBase, Dimensions, Storage};
+
+/// Check that the mask (or the source array, depending on `name`) and the
+/// array have the same dimensions
+fn check_mask<D: Dimensions>(array: &D, mask: &D, name: &str) {
+    assert!(array == mask, "{} dimensions do not match the array dimensions", name);
+}
+
+/// Boolean masks for the masked operations, like
+/// [`ArrayBase::where_assign`](struct.ArrayBase.html#method.where_assign).
+/// This is implemented for arrays of `bool` and for
+/// [`BitArray`](struct.BitArray.html).
+pub trait Mask<D: Dimensions> {
+    /// Get the dimensions of the mask
+    fn dims(&self) -> D;
+    /// Get the value of the mask at `offset`, in storage order
+    fn is_set(&self, offset: usize) -> bool;
+}
+
+impl<S, D> Mask<D> for ArrayBase<S, D> where D: Dimensions, S: Storage<Item = bool> {
+    fn dims(&self) -> D {
+        self.shape()
+    }
+
+    #[inline(always)]
+    fn is_set(&self, offset: usize) -> bool {
+        self.flat_iter().as_slice()[offset]
+    }
+}
+
+/// The `ELSEWHERE` branch of a masked assignment, created by
+/// [`ArrayBase::where_assign`](struct.ArrayBase.html#method.where_assign) and
+/// [`ArrayBase::where_assign_from`](struct.ArrayBase.html#method.where_assign_from).
+/// Dropping this value without calling any method leaves the elements where
+/// the mask is `false` unchanged.
+#[must_use = "the elsewhere branch does nothing unless `elsewhere` or `elsewhere_from` is called"]
+pub struct Elsewhere<'a, T: 'a, D: 'a> where D: Dimensions {
+    data: &'a mut [T],
+    dims: D,
+    mask: &'a dyn Mask<D>,
+}
+
+impl<'a, T: Clone, D> Elsewhere<'a, T, D> where D: Dimensions {
+    /// Set all the elements where the mask is `false` to `value`.
+    pub fn elsewhere(self, value: T) {
+        for (offset, element) in self.data.iter_mut().enumerate() {
+            if !self.mask.is_set(offset) {
+                *element = value.clone();
+            }
+        }
+    }
+
+    /// Set all the elements where the mask is `false` to the corresponding
+    /// element in `source`.
+    ///
+    /// # Panics
+    /// If `source` does not have the same dimensions as the array.
+    pub fn elsewhere_from<S>(self, source: &ArrayBase<S, D>) where S: Storage<Item = T> {
+        check_mask(&self.dims, &source.shape(), "source");
+        for (offset, (element, value)) in self.data.iter_mut().zip(source.flat_iter()).enumerate() {
+            if !self.mask.is_set(offset) {
+                *element = value.clone();
+            }
+        }
+    }
+}
+
+impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage, S::Item: Clone {
+    /// Set all the elements where `mask` is `true` to `value`, like the
+    /// Fortran `WHERE (mask) array = value` construct. The returned value can
+    /// be used to assign the elements where `mask` is `false`, like the
+    /// Fortran `ELSEWHERE` construct.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let mut array = Array::from_vector(vec![1, -2, 3, -4], (-1..1, 2));
+    /// let mask = Array::from_vector(vec![true, false, true, false], (-1..1, 2));
+    ///
+    /// array.where_assign(&mask, 0).elsewhere(1);
+    /// assert_eq!(array, Array::from_vector(vec![0, 1, 0, 1], (-1..1, 2)));
+    /// ```
+    ///
+    /// # Panics
+    /// If `mask` does not have the same dimensions as the array.
+    pub fn where_assign<'a, M>(&'a mut self, mask: &'a M, value: S::Item) -> Elsewhere<'a, S::Item, D>
+        where M: Mask<D> {
+        let dims = self.shape();
+        check_mask(&dims, &mask.dims(), "mask");
+        let data = self.flat_iter_mut().into_slice();
+        for (offset, element) in data.iter_mut().enumerate() {
+            if mask.is_set(offset) {
+                *element = value.clone();
+            }
+        }
+        Elsewhere {
+            data,
+            dims,
+            mask,
+        }
+    }
+
+    /// Set all the elements where `mask` is `true` to the corresponding
+    /// element in `source`, like the Fortran `WHERE (mask) array = source`
+    /// construct. See [`ArrayBase::where_assign`](#method.where_assign).
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let mut array = Array::from_vector(vec![1, -2, 3, -4], 4);
+    /// let mask = Array::from_vector(vec![true, false, true, false], 4);
+    /// let source = Array::from_vector(vec![10, 20, 30, 40], 4);
+    ///
+    /// array.where_assign_from(&mask, &source).elsewhere(0);
+    /// assert_eq!(array, Array::from_vector(vec![10, 0, 30, 0], 4));
+    /// ```
+    ///
+    /// # Panics
+    /// If `mask` or `source` do not have the same dimensions as the array.
+    pub fn where_assign_from<'a, M, R>(&'a mut self, mask: &'a M, source: &ArrayBase<R, D>) -> Elsewhere<'a, S::Item, D>
+        where M: Mask<D>, R: Storage<Item = S::Item> {
+        let dims = self.shape();
+        check_mask(&dims, &mask.dims(), "mask");
+        check_mask(&dims, &source.shape(), "source");
+        let data = self.flat_iter_mut().into_slice();
+        for (offset, (element, value)) in data.iter_mut().zip(source.flat_iter()).enumerate() {
+            if mask.is_set(offset) {
+                *element = value.clone();
+            }
+        }
+        Elsewhere {
+            data,
+            dims,
+            mask,
+        }
+    }
+
+    /// Create a new array taking elements from `tsource` where `mask` is
+    /// `true`, and from `fsource` where `mask` is `false`, like the Fortran
+    /// `MERGE(tsource, fsource, mask)` intrinsic.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let tsource = Array::from_vector(vec![1, 2, 3], 1..=3);
+    /// let fsource = Array::from_vector(vec![-1, -2, -3], 1..=3);
+    /// let mask = Array::from_vector(vec![true, false, true], 1..=3);
+    ///
+    /// let merged = Array::merge(&tsource, &fsource, &mask);
+    /// assert_eq!(merged, Array::from_vector(vec![1, -2, 3], 1..=3));
+    /// ```
+    ///
+    /// # Panics
+    /// If `tsource`, `fsource` and `mask` do not have the same dimensions.
+    pub fn merge<T, F, M>(tsource: &ArrayBase<T, D>, fsource: &ArrayBase<F, D>, mask: &M) -> ArrayBase<S, D>
+        where T: Storage<Item = S::Item>, F: Storage<Item = S::Item>, M: Mask<D> {
+        let dims = tsource.shape();
+        check_mask(&dims, &mask.dims(), "mask");
+        assert!(dims == fsource.shape(), "tsource and fsource dimensions do not match");
+        let values = tsource.flat_iter().zip(fsource.flat_iter()).enumerate().map(|(offset, (t, f))| {
+            if mask.is_set(offset) { t.clone() } else { f.clone() }
+        });
+        ArrayBase::from_shape_iter(dims, values)
+    }
+
+    /// Gather the elements where `mask` is `true` in a one-dimensional array,
+    /// in storage order, like the Fortran `PACK(array, mask)` intrinsic.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_vector(vec![1, 2, 3, 4], (2, -1..1));
+    /// let mask = Array::from_vector(vec![false, true, true, false], (2, -1..1));
+    /// assert_eq!(array.pack(&mask), Array::from_vector(vec![2, 3], 2));
+    /// ```
+    ///
+    /// # Panics
+    /// If `mask` does not have the same dimensions as the array.
+    pub fn pack<M>(&self, mask: &M) -> Array<S::Item, usize> where M: Mask<D> {
+        check_mask(&self.shape(), &mask.dims(), "mask");
+        let values = self.flat_iter().enumerate()
+                                     .filter(|&(offset, _)| mask.is_set(offset))
+                                     .map(|(_, value)| value.clone())
+                                     .collect::<Vec<_>>();
+        let size = values.len();
+        Array::from_vector(values, size)
+    }
+
+    /// Scatter the elements of `vector` in a new array at the positions where
+    /// `mask` is `true`, in storage order, like the Fortran
+    /// `UNPACK(vector, mask, field)` intrinsic. The other elements are taken
+    /// from `field`.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let vector = Array::from_vector(vec![7, 8], 2);
+    /// let mask = Array::from_vector(vec![false, true, true, false], (2, -1..1));
+    /// let field = Array::from_element(0, (2, -1..1));
+    ///
+    /// let array = Array::unpack(&vector, &mask, &field);
+    /// assert_eq!(array, Array::from_vector(vec![0, 7, 8, 0], (2, -1..1)));
+    /// ```
+    ///
+    /// # Panics
+    /// If `mask` and `field` do not have the same dimensions, or if `vector`
+    /// has less elements than there are `true` values in `mask`.
+    pub fn unpack<V, M, F>(vector: &ArrayBase<V, usize>, mask: &M, field: &ArrayBase<F, D>) -> ArrayBase<S, D>
+        where V: Storage<Item = S::Item>, M: Mask<D>, F: Storage<Item = S::Item> {
+        let dims = field.shape();
+        check_mask(&dims, &mask.dims(), "mask");
+        let mut vector = vector.flat_iter();
+        let values = field.flat_iter().enumerate().map(|(offset, value)| {
+            if mask.is_set(offset) {
+                vector.next().expect("vector has less elements than true values in the mask").clone()
+            } else {
+                value.clone()
+            }
+        });
+        ArrayBase::from_shape_iter(dims, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use Array;
+
+    fn mask() -> Array<bool, (usize, ::std::ops::Range<i32>)> {
+        Array::from_fn((2, -1..2), |(i, j)| (i as i32 + j) % 2 == 0)
+    }
+
+    #[test]
+    fn where_assign() {
+        let mut array = Array::from_element(5, (2, -1..2));
+        array.where_assign(&mask(), 1).elsewhere(-1);
+        assert_eq!(array, Array::from_vector(vec![-1, 1, -1, 1, -1, 1], (2, -1..2)));
+
+        // Without elsewhere branch
+        let mut array = Array::from_element(5, (2, -1..2));
+        let _ = array.where_assign(&mask(), 1);
+        assert_eq!(array, Array::from_vector(vec![5, 1, 5, 1, 5, 1], (2, -1..2)));
+    }
+
+    #[test]
+    fn where_assign_from() {
+        let source = Array::from_fn((2, -1..2), |(i, j)| 10 * i as i32 + j);
+        let other = Array::from_element(100, (2, -1..2));
+
+        let mut array = Array::from_element(5, (2, -1..2));
+        array.where_assign_from(&mask(), &source).elsewhere_from(&other);
+        assert_eq!(array, Array::from_vector(vec![100, 0, 100, 9, 100, 11], (2, -1..2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "mask dimensions do not match the array dimensions")]
+    fn mask_dimensions() {
+        let mut array = Array::from_element(5, (2, 0..3));
+        let mask = Array::from_element(true, (2, 1..4));
+        let _ = array.where_assign(&mask, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "source dimensions do not match the array dimensions")]
+    fn elsewhere_dimensions() {
+        let mut array = Array::from_element(5, (2, -1..2));
+        let source = Array::from_element(0, (2, 0..3));
+        array.where_assign(&mask(), 1).elsewhere_from(&source);
+    }
+
+    #[test]
+    fn merge() {
+        let tsource = Array::from_element("t", (2, -1..2));
+        let fsource = Array::from_element("f", (2, -1..2));
+        let merged = Array::merge(&tsource, &fsource, &mask());
+        assert_eq!(merged, Array::from_vector(vec!["f", "t", "f", "t", "f", "t"], (2, -1..2)));
+    }
+
+    #[test]
+    fn pack_unpack() {
+        let array = Array::from_fn((2, -1..2), |(i, j)| 10 * i as i32 + j);
+        let packed = array.pack(&mask());
+        assert_eq!(packed, Array::from_vector(vec![0, 9, 11], 3));
+
+        let field = Array::from_element(-5, (2, -1..2));
+        let unpacked = Array::unpack(&packed, &mask(), &field);
+        assert_eq!(unpacked, Array::from_vector(vec![-5, 0, -5, 9, -5, 11], (2, -1..2)));
+
+        let none = Array::from_element(false, (2, -1..2));
+        assert_eq!(array.pack(&none).shape(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "vector has less elements than true values in the mask")]
+    fn unpack_short_vector() {
+        let vector = Array::from_vector(vec![1, 2], 2);
+        let field = Array::from_element(0, (2, -1..2));
+        let _ = Array::unpack(&vector, &mask(), &field);
+    }
+}
@@ -0,0 +1,370 @@
+use std::convert::TryFrom;
+use std::slice;
+
+use nalgebra::{DMatrix, DMatrixView, DMatrixViewMut, Dim, Dyn, MatrixView, MatrixViewMut, Scalar};
+
+use {Array, ArrayView, ArrayViewMut, Dimensions, FromBounds, LayoutError};
+use dimensions::check_strides;
+use views::region_origin;
+
+/// Get the number of rows and columns of the `dims` region of data stored
+/// with the given `layout`, the strides of the layout along the rows and
+/// columns, and the offset of the first element of the region in the data.
+/// The region must be stored as a single strided block, without wrapping
+/// around periodic axes.
+fn matrix_shape<D>(layout: &D, dims: &D) -> ((usize, usize), (usize, usize), usize) where D: Dimensions<Bounds = [isize; 2]> {
+    let shape = (dims.extent(0), dims.extent(1));
+    if dims.size() == 0 {
+        // nalgebra only accepts empty data with column-major strides
+        return (shape, (1, shape.0), 0);
+    }
+    let strides = layout.strides().expect("nalgebra matrices require dimensions with strided storage");
+    let strides = strides.as_ref();
+    let origin = region_origin(layout, dims);
+    let origin = origin.as_ref();
+    (shape, (strides[0], strides[1]), origin[0] * strides[0] + origin[1] * strides[1])
+}
+
+/// Check if the elements of `dims` are stored in column-major order, like
+/// in a `DMatrix`.
+fn is_column_major<D>(dims: &D) -> bool where D: Dimensions<Bounds = [isize; 2]> {
+    dims.strides().is_some() && check_strides(dims, &[1, dims.extent(0) as isize]).is_ok()
+}
+
+/// Create dimensions for a matrix with the given `shape` and `strides`,
+/// checking that the matrix is stored contiguously in the storage order of
+/// `D`.
+fn import<D>(shape: (usize, usize), strides: (usize, usize), lbounds: [isize; 2]) -> Result<D, LayoutError>
+    where D: FromBounds<Bounds = [isize; 2]>
+{
+    let dims = D::from_bounds(&lbounds, &[shape.0, shape.1]).ok_or(LayoutError::Bounds)?;
+    check_strides(&dims, &[strides.0 as isize, strides.1 as isize])?;
+    Ok(dims)
+}
+
+/// Views are converted without copying the data, using the strides of the
+/// array storage: row-major arrays give a matrix view with a row stride
+/// larger than the column stride. The lower bounds of the dimensions are
+/// dropped, and the conversion panics if the dimensions do not have strided
+/// storage, or if the view wraps around a periodic axis of the array.
+///
+/// ```
+/// extern crate nalgebra;
+/// # extern crate mudi;
+/// use nalgebra::{DMatrixView, Dyn};
+/// use mudi::Array;
+///
+/// # fn main() {
+/// let array = Array::from_fn((-2..2, 0..4), |(i, j)| if i == j { 2.0 } else { 0.1 * (i + j) as f64 });
+/// let block = DMatrixView::<f64, Dyn, Dyn>::from(array.subview((0..2, 0..2)));
+/// assert_eq!(block[(1, 0)], 0.1);
+///
+/// let lu = block.lu();
+/// assert!((lu.determinant() - 3.99).abs() < 1e-12);
+/// # }
+/// ```
+impl<'a, T, D> From<ArrayView<'a, T, D>> for DMatrixView<'a, T, Dyn, Dyn> where T: Scalar, D: Dimensions<Bounds = [isize; 2]> {
+    fn from(view: ArrayView<'a, T, D>) -> DMatrixView<'a, T, Dyn, Dyn> {
+        let (data, layout, dims) = view.raw_parts();
+        let ((nrows, ncols), (rstride, cstride), first) = matrix_shape(layout, dims);
+        MatrixView::from_slice_with_strides_generic(&data[first..], Dyn(nrows), Dyn(ncols), Dyn(rstride), Dyn(cstride))
+    }
+}
+
+impl<'a, T, D> From<ArrayViewMut<'a, T, D>> for DMatrixViewMut<'a, T, Dyn, Dyn> where T: Scalar, D: Dimensions<Bounds = [isize; 2]> {
+    fn from(view: ArrayViewMut<'a, T, D>) -> DMatrixViewMut<'a, T, Dyn, Dyn> {
+        let (data, layout, dims) = view.into_raw_parts();
+        let ((nrows, ncols), (rstride, cstride), first) = matrix_shape(&layout, &dims);
+        MatrixViewMut::from_slice_with_strides_generic(&mut data[first..], Dyn(nrows), Dyn(ncols), Dyn(rstride), Dyn(cstride))
+    }
+}
+
+/// Arrays stored in column-major order, such as arrays with
+/// [`ColumnMajor`](struct.ColumnMajor.html) dimensions, are converted
+/// without copying the data. The elements of other arrays are copied in a
+/// new matrix.
+impl<T, D> From<Array<T, D>> for DMatrix<T> where T: Scalar, D: Dimensions<Bounds = [isize; 2]> {
+    fn from(array: Array<T, D>) -> DMatrix<T> {
+        let shape = array.shape();
+        if is_column_major(&shape) {
+            let (data, _) = array.into_raw_parts();
+            DMatrix::from_vec(shape.extent(0), shape.extent(1), data)
+        } else {
+            DMatrixView::from(array.view()).into_owned()
+        }
+    }
+}
+
+impl<'a, T, D> ArrayView<'a, T, D> where D: FromBounds<Bounds = [isize; 2]> {
+    /// Create a view of the data of a `nalgebra` matrix view without copying
+    /// it, using `lbounds` as the lower bounds of the rows and columns. The
+    /// matrix view must be stored contiguously, in the storage order of `D`.
+    ///
+    /// ```
+    /// extern crate nalgebra;
+    /// # extern crate mudi;
+    /// use std::ops::RangeInclusive;
+    /// use mudi::{ArrayView, ColumnMajor};
+    ///
+    /// # fn main() {
+    /// let matrix = nalgebra::DMatrix::from_fn(3, 2, |i, j| 10 * i + j);
+    /// type Dims = ColumnMajor<(RangeInclusive<i32>, RangeInclusive<i32>)>;
+    /// let view = ArrayView::<_, Dims>::from_nalgebra(matrix.view((0, 0), (3, 2)), [1, 1]).unwrap();
+    /// assert_eq!(view[(3, 2)], 21);
+    /// # }
+    /// ```
+    pub fn from_nalgebra<R, C>(view: MatrixView<'a, T, Dyn, Dyn, R, C>, lbounds: [isize; 2]) -> Result<ArrayView<'a, T, D>, LayoutError>
+        where R: Dim, C: Dim
+    {
+        let dims = import::<D>(view.shape(), view.strides(), lbounds)?;
+        let data = if dims.size() != 0 {
+            // The strides match the ones of `dims`, so the matrix view
+            // covers exactly `dims.size()` elements starting at its first one
+            unsafe { slice::from_raw_parts(view.as_ptr(), dims.size()) }
+        } else {
+            &[]
+        };
+        Ok(ArrayView::new(data, dims.clone(), dims))
+    }
+}
+
+impl<'a, T, D> ArrayViewMut<'a, T, D> where D: FromBounds<Bounds = [isize; 2]> {
+    /// Create a mutable view of the data of a mutable `nalgebra` matrix
+    /// view. See
+    /// [`ArrayView::from_nalgebra`](struct.ArrayView.html#method.from_nalgebra).
+    pub fn from_nalgebra<R, C>(mut view: MatrixViewMut<'a, T, Dyn, Dyn, R, C>, lbounds: [isize; 2]) -> Result<ArrayViewMut<'a, T, D>, LayoutError>
+        where R: Dim, C: Dim
+    {
+        let dims = import::<D>(view.shape(), view.strides(), lbounds)?;
+        let data = if dims.size() != 0 {
+            // Same as for `ArrayView::from_nalgebra`, and the matrix view
+            // gives exclusive access to the data for `'a`
+            unsafe { slice::from_raw_parts_mut(view.as_mut_ptr(), dims.size()) }
+        } else {
+            &mut []
+        };
+        Ok(ArrayViewMut::new(data, dims.clone(), dims))
+    }
+}
+
+impl<T, D> Array<T, D> where T: Scalar, D: FromBounds<Bounds = [isize; 2]> {
+    /// Create an array from a `nalgebra` matrix, using `lbounds` as the lower
+    /// bounds of the rows and columns. The data is moved without copy if `D`
+    /// uses column-major storage, and the elements are cloned in the storage
+    /// order of `D` otherwise.
+    ///
+    /// ```
+    /// extern crate nalgebra;
+    /// # extern crate mudi;
+    /// use std::ops::Range;
+    /// use mudi::{Array, ColumnMajor};
+    ///
+    /// # fn main() {
+    /// let matrix = nalgebra::DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 3.0, 4.0]);
+    /// let array = Array::<f64, ColumnMajor<(Range<i32>, usize)>>::from_nalgebra(matrix.clone(), [-1, 0]).unwrap();
+    /// assert_eq!(array[(0, 0)], 3.0);
+    /// assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![1.0, 3.0, 2.0, 4.0]);
+    ///
+    /// let array = Array::<f64, (usize, usize)>::from_nalgebra(matrix, [0, 0]).unwrap();
+    /// assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![1.0, 2.0, 3.0, 4.0]);
+    /// # }
+    /// ```
+    pub fn from_nalgebra(matrix: DMatrix<T>, lbounds: [isize; 2]) -> Result<Array<T, D>, LayoutError> {
+        let (nrows, ncols) = matrix.shape();
+        let dims = D::from_bounds(&lbounds, &[nrows, ncols]).ok_or(LayoutError::Bounds)?;
+        if is_column_major(&dims) {
+            return Ok(Array::from_vector(Vec::from(matrix.data), dims));
+        }
+
+        Ok(Array::from_fn(dims.clone(), |index| {
+            let positions = dims.positions(index);
+            let positions = positions.as_ref();
+            matrix[(positions[0], positions[1])].clone()
+        }))
+    }
+}
+
+impl<'a, T, D, R, C> TryFrom<MatrixView<'a, T, Dyn, Dyn, R, C>> for ArrayView<'a, T, D>
+    where D: FromBounds<Bounds = [isize; 2]>, R: Dim, C: Dim
+{
+    type Error = LayoutError;
+
+    /// Convert a matrix view, using 0 as the lower bound of the rows and
+    /// columns.
+    fn try_from(view: MatrixView<'a, T, Dyn, Dyn, R, C>) -> Result<ArrayView<'a, T, D>, LayoutError> {
+        ArrayView::from_nalgebra(view, [0, 0])
+    }
+}
+
+impl<'a, T, D, R, C> TryFrom<MatrixViewMut<'a, T, Dyn, Dyn, R, C>> for ArrayViewMut<'a, T, D>
+    where D: FromBounds<Bounds = [isize; 2]>, R: Dim, C: Dim
+{
+    type Error = LayoutError;
+
+    /// Convert a mutable matrix view, using 0 as the lower bound of the rows
+    /// and columns.
+    fn try_from(view: MatrixViewMut<'a, T, Dyn, Dyn, R, C>) -> Result<ArrayViewMut<'a, T, D>, LayoutError> {
+        ArrayViewMut::from_nalgebra(view, [0, 0])
+    }
+}
+
+impl<T, D> TryFrom<DMatrix<T>> for Array<T, D> where T: Scalar, D: FromBounds<Bounds = [isize; 2]> {
+    type Error = LayoutError;
+
+    /// Convert a matrix, using 0 as the lower bound of the rows and columns.
+    fn try_from(matrix: DMatrix<T>) -> Result<Array<T, D>, LayoutError> {
+        Array::from_nalgebra(matrix, [0, 0])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::Range;
+    use {ColumnMajor, Grid, Periodic};
+
+    type Block<'a> = DMatrixView<'a, f64, Dyn, Dyn>;
+
+    #[test]
+    fn view_to_nalgebra() {
+        let array = Array::from_fn((-1..2, 1..=4), |(i, j)| 10 * i + j);
+        let matrix = DMatrixView::<i32, Dyn, Dyn>::from(array.view());
+        assert_eq!(matrix.shape(), (3, 4));
+        assert_eq!(matrix.strides(), (4, 1));
+        assert_eq!(matrix[(0, 3)], -6);
+        assert_eq!(matrix.as_ptr(), array.flat_iter().as_slice().as_ptr());
+
+        let matrix = DMatrixView::<i32, Dyn, Dyn>::from(array.subview((0..2, 2..=3)));
+        assert_eq!(matrix, DMatrix::from_row_slice(2, 2, &[2, 3, 12, 13]));
+
+        let array = Array::from_fn(ColumnMajor((3, 1)), |(i, _)| i);
+        let matrix = DMatrixView::<usize, Dyn, Dyn>::from(array.view());
+        assert_eq!(matrix.strides(), (1, 0));
+        assert_eq!(matrix, DMatrix::from_vec(3, 1, vec![0, 1, 2]));
+
+        let array = Array::from_fn((0..2, Periodic(0..4)), |(i, j)| 10 * i + j);
+        let matrix = DMatrixView::<i32, Dyn, Dyn>::from(array.subview((0..2, Periodic(5..7))));
+        assert_eq!(matrix, DMatrix::from_row_slice(2, 2, &[1, 2, 11, 12]));
+    }
+
+    #[test]
+    fn decompositions() {
+        let mut grid = Grid::new(0.0, (0..3, 0..3), (1, 1));
+        grid.interior_mut().fill(1.0);
+        grid.array_mut()[(0, 0)] = 4.0;
+        grid.array_mut()[(1, 1)] = 3.0;
+        grid.array_mut()[(2, 2)] = 2.0;
+
+        let block = Block::from(grid.interior());
+        assert_eq!(block.strides(), (5, 1));
+        let lu = block.lu();
+        assert!((lu.determinant() - 17.0).abs() < 1e-12);
+
+        let qr = Block::from(grid.interior()).qr();
+        assert!((qr.q() * qr.r() - block).abs().max() < 1e-12);
+
+        let svd = Block::from(grid.array().subview((0..2, 0..2))).svd(false, false);
+        let mut values = svd.singular_values.iter().cloned().collect::<Vec<_>>();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!((values[0] * values[1] - 11.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn view_mut_to_nalgebra() {
+        let mut array = Array::from_element(0, (-1..2, -1..2));
+        {
+            let mut matrix = DMatrixViewMut::<i32, Dyn, Dyn>::from(array.subview_mut((0..2, 0..2)));
+            matrix.fill(1);
+            matrix[(1, 0)] = 2;
+        }
+        assert_eq!(array[(1, 0)], 2);
+        assert_eq!(array.flat_iter().sum::<i32>(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "the region wraps around axis 1 of the array")]
+    fn wrapping_view_to_nalgebra() {
+        let array = Array::from_fn((0..2, Periodic(0..4)), |(i, j)| (10 * i + j) as f64);
+        let _ = Block::from(array.subview((0..2, Periodic(3..5))));
+    }
+
+    #[test]
+    #[should_panic(expected = "the region wraps around axis 1 of the array")]
+    fn wrapping_view_mut_to_nalgebra() {
+        let mut array = Array::from_element(0.0, (0..2, Periodic(0..4)));
+        let _ = DMatrixViewMut::<f64, Dyn, Dyn>::from(array.subview_mut((0..2, Periodic(3..5))));
+    }
+
+    #[test]
+    fn array_to_nalgebra() {
+        let array = Array::from_fn(ColumnMajor((1..3, 3)), |(i, j)| 10 * i + j as i32);
+        let pointer = array.flat_iter().as_slice().as_ptr();
+        let matrix = DMatrix::from(array);
+        assert_eq!(matrix.as_ptr(), pointer);
+        assert_eq!(matrix, DMatrix::from_row_slice(2, 3, &[10, 11, 12, 20, 21, 22]));
+
+        let matrix = DMatrix::from(Array::from_fn((1..3, 3), |(i, j)| 10 * i + j as i32));
+        assert_eq!(matrix, DMatrix::from_row_slice(2, 3, &[10, 11, 12, 20, 21, 22]));
+    }
+
+    #[test]
+    fn view_from_nalgebra() {
+        let matrix = DMatrix::from_row_slice(2, 3, &[1, 2, 3, 4, 5, 6]);
+        let view = ArrayView::<i32, ColumnMajor<(Range<i32>, usize)>>::from_nalgebra(matrix.view((0, 0), (2, 3)), [-1, 0]).unwrap();
+        assert_eq!(view.shape(), ColumnMajor((-1..1, 3)));
+        assert_eq!(view[(0, 2)], 6);
+        assert_eq!(&view[(-1, 0)] as *const i32, matrix.as_ptr());
+
+        // Row-major view of the transposed matrix
+        let transposed = MatrixView::from_slice_with_strides_generic(matrix.as_slice(), Dyn(3), Dyn(2), Dyn(2), Dyn(1));
+        let view = ArrayView::<i32, (usize, usize)>::try_from(transposed).unwrap();
+        assert_eq!(view.to_owned(), Array::from_vector(vec![1, 4, 2, 5, 3, 6], (3, 2)));
+
+        let mut matrix = matrix;
+        {
+            let mut view = ArrayViewMut::<i32, ColumnMajor<(usize, usize)>>::try_from(matrix.view_mut((0, 0), (2, 3))).unwrap();
+            view[(1, 1)] = 0;
+        }
+        assert_eq!(matrix[(1, 1)], 0);
+    }
+
+    #[test]
+    fn array_from_nalgebra() {
+        let matrix = DMatrix::from_row_slice(2, 3, &[1, 2, 3, 4, 5, 6]);
+        let copy = matrix.clone();
+        let pointer = copy.as_ptr();
+        let array = Array::<i32, ColumnMajor<(usize, usize)>>::try_from(copy).unwrap();
+        assert_eq!(array.flat_iter().as_slice().as_ptr(), pointer);
+        assert_eq!(array[(1, 2)], 6);
+
+        let array = Array::<i32, (usize, Range<i8>)>::from_nalgebra(matrix, [0, 10]).unwrap();
+        assert_eq!(array, Array::from_vector(vec![1, 2, 3, 4, 5, 6], (2, 10..13)));
+    }
+
+    #[test]
+    fn errors() {
+        let matrix = DMatrix::from_row_slice(2, 3, &[1, 2, 3, 4, 5, 6]);
+        let error = ArrayView::<i32, (usize, usize)>::try_from(matrix.view((0, 0), (2, 3))).unwrap_err();
+        assert_eq!(error, LayoutError::Strides { axis: 0, expected: 3, found: 1 });
+
+        let error = ArrayView::<i32, ColumnMajor<(usize, usize)>>::from_nalgebra(matrix.view((0, 0), (2, 3)), [1, 0]).unwrap_err();
+        assert_eq!(error, LayoutError::Bounds);
+
+        // Blocks of a bigger matrix are not contiguous
+        let error = ArrayView::<i32, ColumnMajor<(usize, usize)>>::try_from(matrix.view((0, 0), (1, 3))).unwrap_err();
+        assert_eq!(error, LayoutError::Strides { axis: 1, expected: 1, found: 2 });
+
+        let error = Array::<i32, (Range<u8>, usize)>::from_nalgebra(matrix, [255, 0]).unwrap_err();
+        assert_eq!(error, LayoutError::Bounds);
+    }
+
+    #[test]
+    fn empty() {
+        let array = Array::<f64, _>::from_vector(vec![], (0, 1..4));
+        let matrix = Block::from(array.view());
+        assert_eq!(matrix.shape(), (0, 3));
+        let view = ArrayView::<f64, (usize, Range<i32>)>::from_nalgebra(matrix, [0, 1]).unwrap();
+        assert_eq!(view.shape(), (0, 1..4));
+        assert_eq!(DMatrix::from(array).shape(), (0, 3));
+    }
+}
@@ -0,0 +1,390 @@
+use std::convert::TryFrom;
+use std::slice;
+
+use ndarray::{self, Dimension, Ix1, Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn, ShapeBuilder, StrideShape};
+
+use {Array, ArrayView, ArrayViewMut, Dimensions, DynList, FromBounds, LayoutError};
+use dimensions::check_strides;
+use views::region_origin;
+
+/// Bounds of `mudi` dimensions, associated with the `ndarray` dimension type
+/// of the same rank. Dimensions with up to 6 axes use the fixed-size
+/// `ndarray` dimensions, and the others use `IxDyn`.
+pub trait NdarrayRank {
+    /// The `ndarray` dimension type
+    type Dim: Dimension;
+}
+
+macro_rules! ndarray_rank {
+    ($($bounds: ty => $dim: ty),*) => {$(
+        impl NdarrayRank for $bounds {
+            type Dim = $dim;
+        }
+    )*};
+}
+
+ndarray_rank!(
+    [isize; 1] => Ix1, [isize; 2] => Ix2, [isize; 3] => Ix3, [isize; 4] => Ix4,
+    [isize; 5] => Ix5, [isize; 6] => Ix6, [isize; 7] => IxDyn, DynList<isize> => IxDyn
+);
+
+/// The `ndarray` dimension type used for arrays with `D` dimensions
+pub type NdarrayDim<D> = <<D as Dimensions>::Bounds as NdarrayRank>::Dim;
+
+/// Create an `ndarray` dimension containing `values`
+fn ndarray_dim<I: Dimension>(values: &[usize]) -> I {
+    let mut dim = I::zeros(values.len());
+    dim.slice_mut().copy_from_slice(values);
+    dim
+}
+
+/// Get the `ndarray` shape and strides of the `dims` region of data stored
+/// with the given `layout`, and the offset of the first element of the
+/// region in this data. The region must be stored as a single strided block,
+/// without wrapping around periodic axes.
+fn ndarray_shape<D>(layout: &D, dims: &D) -> (StrideShape<NdarrayDim<D>>, usize)
+    where D: Dimensions, D::Bounds: NdarrayRank
+{
+    let shape = ndarray_dim::<NdarrayDim<D>>(dims.extents().as_ref());
+    let strides = layout.strides().expect("ndarray arrays require dimensions with strided storage");
+    let origin = region_origin(layout, dims);
+    let first = origin.as_ref().iter().zip(strides.as_ref()).map(|(position, stride)| position * stride).sum();
+    let strides = ndarray_dim::<NdarrayDim<D>>(strides.as_ref());
+    (shape.strides(strides), first)
+}
+
+/// Create dimensions for an `ndarray` array with the given `shape` and
+/// `strides`, checking that the array is stored contiguously in the storage
+/// order of `D`.
+fn import<D: FromBounds>(shape: &[usize], strides: &[isize], lbounds: &[isize]) -> Result<D, LayoutError> {
+    let dims = D::from_bounds(lbounds, shape).ok_or(LayoutError::Bounds)?;
+    check_strides(&dims, strides)?;
+    Ok(dims)
+}
+
+/// Get lower bounds of 0 for each axis of `shape`
+fn zero_lbounds(shape: &[usize]) -> Vec<isize> {
+    vec![0; shape.len()]
+}
+
+/// Views are converted without copying the data, and the lower bounds of the
+/// dimensions are dropped: `ndarray` indexes always start at 0. The
+/// conversion panics if the dimensions do not have strided storage, or if
+/// the view wraps around a periodic axis of the array.
+///
+/// ```
+/// extern crate ndarray;
+/// # extern crate mudi;
+/// use mudi::Array;
+///
+/// # fn main() {
+/// let array = Array::from_fn((-1..2, 1..=4), |(i, j)| 10 * i + j);
+/// let view = ndarray::ArrayView2::from(array.subview((0..2, 2..=3)));
+/// assert_eq!(view.shape(), &[2, 2]);
+/// assert_eq!(view[[1, 0]], 12);
+/// # }
+/// ```
+impl<'a, T, D> From<ArrayView<'a, T, D>> for ndarray::ArrayView<'a, T, NdarrayDim<D>>
+    where D: Dimensions, D::Bounds: NdarrayRank
+{
+    fn from(view: ArrayView<'a, T, D>) -> ndarray::ArrayView<'a, T, NdarrayDim<D>> {
+        let (data, layout, dims) = view.raw_parts();
+        let (shape, first) = ndarray_shape(layout, dims);
+        ndarray::ArrayView::from_shape(shape, &data[first..]).expect("view region should be inside of the data")
+    }
+}
+
+impl<'a, T, D> From<ArrayViewMut<'a, T, D>> for ndarray::ArrayViewMut<'a, T, NdarrayDim<D>>
+    where D: Dimensions, D::Bounds: NdarrayRank
+{
+    fn from(view: ArrayViewMut<'a, T, D>) -> ndarray::ArrayViewMut<'a, T, NdarrayDim<D>> {
+        let (data, layout, dims) = view.into_raw_parts();
+        let (shape, first) = ndarray_shape(&layout, &dims);
+        ndarray::ArrayViewMut::from_shape(shape, &mut data[first..]).expect("view region should be inside of the data")
+    }
+}
+
+/// Arrays are converted without copying the data, which is stored in the
+/// same order in the `ndarray` array.
+impl<T, D> From<Array<T, D>> for ndarray::Array<T, NdarrayDim<D>> where D: Dimensions, D::Bounds: NdarrayRank {
+    fn from(array: Array<T, D>) -> ndarray::Array<T, NdarrayDim<D>> {
+        let (data, dims) = array.into_raw_parts();
+        let (shape, _) = ndarray_shape(&dims, &dims);
+        ndarray::Array::from_shape_vec(shape, data).expect("array layout should be valid for ndarray")
+    }
+}
+
+impl<'a, T, D> ArrayView<'a, T, D> where D: FromBounds, D::Bounds: NdarrayRank {
+    /// Create a view of the data of an `ndarray` view without copying it,
+    /// using `lbounds` as the lower bound of each axis. The `ndarray` view
+    /// must be stored contiguously, in the storage order of `D`.
+    ///
+    /// ```
+    /// extern crate ndarray;
+    /// # extern crate mudi;
+    /// use std::ops::Range;
+    /// use ndarray::ShapeBuilder;
+    /// use mudi::{ArrayView, ColumnMajor};
+    ///
+    /// # fn main() {
+    /// let array = ndarray::Array::from_shape_fn((2, 3).f(), |(i, j)| 10 * i + j);
+    /// let view = ArrayView::<_, ColumnMajor<(Range<i32>, usize)>>::from_ndarray(array.view(), &[1, 0]).unwrap();
+    /// assert_eq!(view[(2, 1)], 11);
+    /// # }
+    /// ```
+    pub fn from_ndarray(view: ndarray::ArrayView<'a, T, NdarrayDim<D>>, lbounds: &[isize]) -> Result<ArrayView<'a, T, D>, LayoutError> {
+        let dims = import::<D>(view.shape(), view.strides(), lbounds)?;
+        let data = if dims.size() != 0 {
+            // The strides match the ones of `dims`, so the `ndarray` view
+            // covers exactly `dims.size()` elements starting at its first one
+            unsafe { slice::from_raw_parts(view.as_ptr(), dims.size()) }
+        } else {
+            &[]
+        };
+        Ok(ArrayView::new(data, dims.clone(), dims))
+    }
+}
+
+impl<'a, T, D> ArrayViewMut<'a, T, D> where D: FromBounds, D::Bounds: NdarrayRank {
+    /// Create a mutable view of the data of an `ndarray` view. See
+    /// [`ArrayView::from_ndarray`](struct.ArrayView.html#method.from_ndarray).
+    pub fn from_ndarray(mut view: ndarray::ArrayViewMut<'a, T, NdarrayDim<D>>, lbounds: &[isize]) -> Result<ArrayViewMut<'a, T, D>, LayoutError> {
+        let dims = import::<D>(view.shape(), view.strides(), lbounds)?;
+        let data = if dims.size() != 0 {
+            // Same as for `ArrayView::from_ndarray`, and the `ndarray` view
+            // gives exclusive access to the data for `'a`
+            unsafe { slice::from_raw_parts_mut(view.as_mut_ptr(), dims.size()) }
+        } else {
+            &mut []
+        };
+        Ok(ArrayViewMut::new(data, dims.clone(), dims))
+    }
+}
+
+impl<T, D> Array<T, D> where D: FromBounds, D::Bounds: NdarrayRank {
+    /// Create an array from an `ndarray` array, using `lbounds` as the lower
+    /// bound of each axis. The data is moved without copy if the `ndarray`
+    /// array is stored contiguously in the storage order of `D`, and the
+    /// elements are reordered otherwise.
+    ///
+    /// ```
+    /// extern crate ndarray;
+    /// # extern crate mudi;
+    /// use std::ops::Range;
+    /// use mudi::{Array, ColumnMajor};
+    ///
+    /// # fn main() {
+    /// let array = ndarray::Array::from_shape_fn((2, 3), |(i, j)| 10 * i + j);
+    /// let array = Array::<_, ColumnMajor<(usize, Range<i32>)>>::from_ndarray(array, &[0, -1]).unwrap();
+    /// assert_eq!(array[(1, 0)], 11);
+    /// assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![0, 10, 1, 11, 2, 12]);
+    /// # }
+    /// ```
+    pub fn from_ndarray(array: ndarray::Array<T, NdarrayDim<D>>, lbounds: &[isize]) -> Result<Array<T, D>, LayoutError> {
+        let dims = match import::<D>(array.shape(), array.strides(), lbounds) {
+            Ok(dims) => {
+                let size = dims.size();
+                let (mut data, offset) = array.into_raw_vec_and_offset();
+                let offset = offset.unwrap_or(0);
+                data.truncate(offset + size);
+                data.drain(..offset);
+                return Ok(Array::from_vector(data, dims));
+            }
+            Err(LayoutError::Strides { .. }) => D::from_bounds(lbounds, array.shape()).ok_or(LayoutError::Bounds)?,
+            Err(error) => return Err(error),
+        };
+
+        // `ndarray` iterates over the elements in row-major order of the
+        // positions, which we move to their place in the storage of `dims`
+        let shape = array.shape().to_vec();
+        let mut values = array.into_iter().map(Some).collect::<Vec<_>>();
+        Ok(Array::from_fn(dims.clone(), |index| {
+            let positions = dims.positions(index);
+            let i = positions.as_ref().iter().zip(&shape).fold(0, |i, (&position, &extent)| i * extent + position);
+            values[i].take().expect("each position should be visited once")
+        }))
+    }
+}
+
+impl<'a, T, D> TryFrom<ndarray::ArrayView<'a, T, NdarrayDim<D>>> for ArrayView<'a, T, D>
+    where D: FromBounds, D::Bounds: NdarrayRank
+{
+    type Error = LayoutError;
+
+    /// Convert an `ndarray` view, using 0 as the lower bound of each axis.
+    fn try_from(view: ndarray::ArrayView<'a, T, NdarrayDim<D>>) -> Result<ArrayView<'a, T, D>, LayoutError> {
+        let lbounds = zero_lbounds(view.shape());
+        ArrayView::from_ndarray(view, &lbounds)
+    }
+}
+
+impl<'a, T, D> TryFrom<ndarray::ArrayViewMut<'a, T, NdarrayDim<D>>> for ArrayViewMut<'a, T, D>
+    where D: FromBounds, D::Bounds: NdarrayRank
+{
+    type Error = LayoutError;
+
+    /// Convert a mutable `ndarray` view, using 0 as the lower bound of each
+    /// axis.
+    fn try_from(view: ndarray::ArrayViewMut<'a, T, NdarrayDim<D>>) -> Result<ArrayViewMut<'a, T, D>, LayoutError> {
+        let lbounds = zero_lbounds(view.shape());
+        ArrayViewMut::from_ndarray(view, &lbounds)
+    }
+}
+
+impl<T, D> TryFrom<ndarray::Array<T, NdarrayDim<D>>> for Array<T, D> where D: FromBounds, D::Bounds: NdarrayRank {
+    type Error = LayoutError;
+
+    /// Convert an `ndarray` array, using 0 as the lower bound of each axis.
+    fn try_from(array: ndarray::Array<T, NdarrayDim<D>>) -> Result<Array<T, D>, LayoutError> {
+        let lbounds = zero_lbounds(array.shape());
+        Array::from_ndarray(array, &lbounds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::{Range, RangeInclusive};
+    use ndarray::{s, Axis};
+    use {ColumnMajor, DynDims, DynIndex, Periodic};
+
+    #[test]
+    fn view_to_ndarray() {
+        let array = Array::from_fn((-1..2, 0..4), |(i, j)| 10 * i + j);
+        let view = ndarray::ArrayView2::from(array.view());
+        assert_eq!(view.shape(), &[3, 4]);
+        assert_eq!(view.strides(), &[4, 1]);
+        assert_eq!(view[[0, 3]], -7);
+        assert_eq!(view.as_ptr(), array.flat_iter().as_slice().as_ptr());
+
+        let view = ndarray::ArrayView2::from(array.subview((0..2, 1..3)));
+        assert_eq!(view, ndarray::arr2(&[[1, 2], [11, 12]]));
+
+        let array = Array::from_fn(ColumnMajor((2, Periodic(1..4), 1)), |(i, j, _)| 10 * i as i32 + j);
+        let view = ndarray::ArrayView3::from(array.view());
+        assert_eq!(view.strides(), &[1, 2, 0]);
+        assert_eq!(view.index_axis(Axis(2), 0), ndarray::arr2(&[[1, 2, 3], [11, 12, 13]]));
+
+        let array = Array::from_fn(DynDims::new(&[1, -1], &[2, 2]), |index: DynIndex| index[0] * index[1]);
+        let view = ndarray::ArrayViewD::from(array.view());
+        assert_eq!(view.into_dimensionality().unwrap(), ndarray::arr2(&[[-1, 0], [-2, 0]]));
+    }
+
+    #[test]
+    fn view_mut_to_ndarray() {
+        let mut array = Array::from_element(0, ColumnMajor((1..=3, 3)));
+        {
+            let mut view = ndarray::ArrayViewMut2::from(array.subview_mut(ColumnMajor((2..=3, 3))));
+            view[[1, 2]] = 4;
+            view.slice_mut(s![0, ..]).fill(1);
+        }
+        assert_eq!(array[(3, 2)], 4);
+        assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![0, 1, 0, 0, 1, 0, 0, 1, 4]);
+    }
+
+    #[test]
+    fn periodic_view_to_ndarray() {
+        let array = Array::from_fn((0..2, Periodic(0..4)), |(i, j)| 10 * i + j);
+        let view = ndarray::ArrayView2::from(array.subview((0..2, Periodic(5..7))));
+        assert_eq!(view, ndarray::arr2(&[[1, 2], [11, 12]]));
+    }
+
+    #[test]
+    #[should_panic(expected = "the region wraps around axis 1 of the array")]
+    fn wrapping_view_to_ndarray() {
+        let array = Array::from_fn((0..2, Periodic(0..4)), |(i, j)| 10 * i + j);
+        let _ = ndarray::ArrayView2::from(array.subview((0..1, Periodic(3..5))));
+    }
+
+    #[test]
+    fn array_to_ndarray() {
+        let array = Array::from_fn((-1..2, 1..=2), |(i, j)| 10 * i + j);
+        let pointer = array.flat_iter().as_slice().as_ptr();
+        let array = ndarray::Array2::from(array);
+        assert_eq!(array.as_ptr(), pointer);
+        assert_eq!(array, ndarray::arr2(&[[-9, -8], [1, 2], [11, 12]]));
+
+        let array = ndarray::Array3::from(Array::from_vector(vec![1, 2, 3, 4], ColumnMajor((1, 2, 2))));
+        assert_eq!(array.index_axis(Axis(0), 0), ndarray::arr2(&[[1, 3], [2, 4]]));
+    }
+
+    #[test]
+    fn view_from_ndarray() {
+        let array = ndarray::arr2(&[[1, 2, 3], [4, 5, 6]]);
+        let view = ArrayView::<i32, (Range<i8>, RangeInclusive<i64>)>::from_ndarray(array.view(), &[-1, 1]).unwrap();
+        assert_eq!(view.shape(), (-1..1, 1..=3));
+        assert_eq!(view[(0, 1)], 4);
+        assert_eq!(&view[(-1, 1)] as *const i32, array.as_ptr());
+
+        let view = ArrayView::<i32, (usize, usize)>::try_from(array.slice(s![1.., ..])).unwrap();
+        assert_eq!(view.to_owned(), Array::from_vector(vec![4, 5, 6], (1, 3)));
+
+        let view = ArrayView::<i32, DynDims>::try_from(array.view().into_dyn()).unwrap();
+        assert_eq!(view.shape(), DynDims::from_extents(&[2, 3]));
+
+        let view = ArrayView::<i32, ColumnMajor<(usize, usize)>>::try_from(array.t()).unwrap();
+        assert_eq!(view[(2, 0)], 3);
+        assert_eq!(view.iter().cloned().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn view_mut_from_ndarray() {
+        let mut array = ndarray::Array::zeros((2, 2));
+        {
+            let mut view = ArrayViewMut::<f64, (Range<i32>, usize)>::from_ndarray(array.view_mut(), &[3, 0]).unwrap();
+            view[(4, 0)] = 2.0;
+        }
+        assert_eq!(array, ndarray::arr2(&[[0.0, 0.0], [2.0, 0.0]]));
+    }
+
+    #[test]
+    fn array_from_ndarray() {
+        let array = ndarray::arr2(&[[1, 2, 3], [4, 5, 6]]);
+        let pointer = array.as_ptr();
+        let array = Array::<i32, (usize, Range<i32>)>::from_ndarray(array, &[0, 2]).unwrap();
+        assert_eq!(array.flat_iter().as_slice().as_ptr(), pointer);
+        assert_eq!(array[(1, 4)], 6);
+
+        // Moved without copy from the middle of the allocation
+        let mut array = ndarray::arr2(&[[1, 2, 3], [4, 5, 6]]);
+        array.slice_collapse(s![1.., ..]);
+        let array = Array::<i32, (usize, usize)>::try_from(array).unwrap();
+        assert_eq!(array, Array::from_vector(vec![4, 5, 6], (1, 3)));
+
+        // Reordered elements
+        let array = ndarray::arr2(&[[1, 2, 3], [4, 5, 6]]).reversed_axes();
+        let array = Array::<i32, (usize, usize)>::try_from(array).unwrap();
+        assert_eq!(array, Array::from_vector(vec![1, 4, 2, 5, 3, 6], (3, 2)));
+
+        let array = Array::<String, ColumnMajor<DynDims>>::try_from(ndarray::arr1(&[String::from("a")]).into_dyn()).unwrap();
+        assert_eq!(array[DynIndex::from([0])], "a");
+    }
+
+    #[test]
+    fn errors() {
+        let array = ndarray::arr2(&[[1, 2, 3], [4, 5, 6]]);
+        let error = ArrayView::<i32, (usize, usize)>::from_ndarray(array.view(), &[0, 1]).unwrap_err();
+        assert_eq!(error, LayoutError::Bounds);
+        let error = ArrayView::<i32, (usize, usize)>::from_ndarray(array.view(), &[0]).unwrap_err();
+        assert_eq!(error, LayoutError::Bounds);
+
+        let error = ArrayView::<i32, ColumnMajor<(usize, usize)>>::try_from(array.view()).unwrap_err();
+        assert_eq!(error, LayoutError::Strides { axis: 0, expected: 1, found: 3 });
+        assert_eq!(error.to_string(), "expected a stride of 1 elements along axis 0, got 3 elements");
+
+        let error = ArrayView::<i32, (usize, usize)>::try_from(array.slice(s![.., ..;2])).unwrap_err();
+        assert_eq!(error, LayoutError::Strides { axis: 0, expected: 2, found: 3 });
+
+        let error = Array::<i32, DynDims>::try_from(ndarray::ArrayD::zeros(vec![1; 8])).unwrap_err();
+        assert_eq!(error, LayoutError::Bounds);
+    }
+
+    #[test]
+    fn empty() {
+        let array = ndarray::Array2::<f64>::zeros((0, 3));
+        let view = ArrayView::<f64, (usize, Range<i32>)>::from_ndarray(array.view(), &[0, 1]).unwrap();
+        assert_eq!(view.shape(), (0, 1..4));
+        let array = ndarray::Array2::from(view.to_owned());
+        assert_eq!(array.shape(), &[0, 3]);
+    }
+}
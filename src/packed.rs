@@ -0,0 +1,661 @@
+use std::cmp::min;
+use std::ops::{Add, Mul};
+
+use dimensions::check_axis;
+use {Array, ArrayBase, Dimensions, Storage};
+
+/// Upper triangle of a `n x n` matrix, in LAPACK packed storage with
+/// `UPLO = 'U'`: the columns of the triangle are stored one after the other,
+/// using `n (n + 1) / 2` elements. Only the indexes `(i, j)` with `i <= j`
+/// are valid.
+///
+/// ```
+/// use mudi::{Array, UpperTriangular};
+/// let array = Array::from_fn(UpperTriangular(3), |(i, j)| 10 * i + j);
+/// assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![0, 1, 11, 2, 12, 22]);
+/// assert_eq!(array[(1, 2)], 12);
+/// ```
+///
+/// ```should_panic
+/// # use mudi::{Array, UpperTriangular};
+/// let array = Array::from_element(0.0, UpperTriangular(3));
+/// // Not in the upper triangle
+/// array[(2, 1)];
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UpperTriangular(pub usize);
+
+/// Lower triangle of a `n x n` matrix, in LAPACK packed storage with
+/// `UPLO = 'L'`: the columns of the triangle are stored one after the other,
+/// using `n (n + 1) / 2` elements. Only the indexes `(i, j)` with `i >= j`
+/// are valid.
+///
+/// ```
+/// use mudi::{Array, LowerTriangular};
+/// let array = Array::from_fn(LowerTriangular(3), |(i, j)| 10 * i + j);
+/// assert_eq!(array.flat_iter().cloned().collect::<Vec<_>>(), vec![0, 10, 20, 11, 21, 22]);
+/// assert_eq!(array[(2, 1)], 21);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LowerTriangular(pub usize);
+
+/// Symmetric `n x n` matrix, storing only the upper triangle like
+/// [`UpperTriangular`](struct.UpperTriangular.html). All the indexes are
+/// valid, and `(i, j)` refers to the same element as `(j, i)`.
+///
+/// ```
+/// use mudi::{Array, Symmetric};
+/// let mut hessian = Array::from_element(0.0, Symmetric(3));
+/// assert_eq!(hessian.flat_iter().count(), 6);
+///
+/// hessian[(2, 0)] = 4.0;
+/// assert_eq!(hessian[(0, 2)], 4.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symmetric(pub usize);
+
+/// Band matrix of size `n x n`, with `kl` sub-diagonals and `ku`
+/// super-diagonals. Only the indexes `(i, j)` with `j - ku <= i <= j + kl`
+/// are valid.
+///
+/// The columns of the band are stored one after the other, in the same order
+/// as LAPACK general band storage but without the unused elements in the
+/// top-left and bottom-right corners. Use
+/// [`to_band_storage`](struct.ArrayBase.html#method.to_band_storage) and
+/// [`from_band_storage`](struct.ArrayBase.html#method.from_band_storage) to
+/// convert to and from the LAPACK layout, with `kl + ku + 1` elements for
+/// each column.
+///
+/// ```
+/// use mudi::{Array, Banded};
+/// // Tridiagonal matrix
+/// let dims = Banded { n: 4, kl: 1, ku: 1 };
+/// let matrix = Array::from_fn(dims, |(i, j)| if i == j { 2.0 } else { -1.0 });
+/// assert_eq!(matrix[(1, 2)], -1.0);
+/// assert_eq!(matrix.get((0, 2)), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Banded {
+    /// Number of rows and columns of the matrix
+    pub n: usize,
+    /// Number of sub-diagonals
+    pub kl: usize,
+    /// Number of super-diagonals
+    pub ku: usize,
+}
+
+/// Get the offset of `(i, j)`, with `i <= j`, in upper packed storage
+#[inline(always)]
+fn upper_offset(i: usize, j: usize) -> usize {
+    i + j * (j + 1) / 2
+}
+
+/// Get the index at `offset` in upper packed storage. This is the inverse of
+/// `upper_offset`.
+#[inline(always)]
+fn upper_index(offset: usize) -> (usize, usize) {
+    // The column is the largest `j` with `j (j + 1) / 2 <= offset`. The
+    // floating point estimate can be off by one for large offsets.
+    let mut j = (((8.0 * offset as f64 + 1.0).sqrt() - 1.0) / 2.0) as usize;
+    while j * (j + 1) / 2 > offset {
+        j -= 1;
+    }
+    while (j + 1) * (j + 2) / 2 <= offset {
+        j += 1;
+    }
+    (offset - j * (j + 1) / 2, j)
+}
+
+/// Check that `(i, j)` is inside of a `n x n` matrix
+#[inline(always)]
+fn check_index(n: usize, i: usize, j: usize) {
+    assert!(i < n && j < n, "index out of bound: matrix size is {}x{} but index is ({}, {})", n, n, i, j);
+}
+
+impl UpperTriangular {
+    /// Check that `(i, j)` is a valid index
+    #[inline(always)]
+    fn check(&self, i: usize, j: usize) -> (usize, usize) {
+        check_index(self.0, i, j);
+        assert!(i <= j, "index ({}, {}) is outside of the upper triangle", i, j);
+        (i, j)
+    }
+
+    #[inline(always)]
+    fn order(&self) -> usize {
+        self.0
+    }
+
+    #[inline(always)]
+    fn packed_size(&self) -> usize {
+        self.0 * (self.0 + 1) / 2
+    }
+
+    #[inline(always)]
+    fn packed_offset(&self, i: usize, j: usize) -> usize {
+        upper_offset(i, j)
+    }
+
+    #[inline(always)]
+    fn packed_index(&self, offset: usize) -> (usize, usize) {
+        upper_index(offset)
+    }
+}
+
+impl LowerTriangular {
+    /// Check that `(i, j)` is a valid index
+    #[inline(always)]
+    fn check(&self, i: usize, j: usize) -> (usize, usize) {
+        check_index(self.0, i, j);
+        assert!(i >= j, "index ({}, {}) is outside of the lower triangle", i, j);
+        (i, j)
+    }
+
+    #[inline(always)]
+    fn order(&self) -> usize {
+        self.0
+    }
+
+    #[inline(always)]
+    fn packed_size(&self) -> usize {
+        self.0 * (self.0 + 1) / 2
+    }
+
+    // Reversing the lower packed storage gives the upper packed storage of
+    // the matrix with reversed rows and columns.
+
+    #[inline(always)]
+    fn packed_offset(&self, i: usize, j: usize) -> usize {
+        let n = self.0;
+        self.size() - 1 - upper_offset(n - 1 - i, n - 1 - j)
+    }
+
+    #[inline(always)]
+    fn packed_index(&self, offset: usize) -> (usize, usize) {
+        let n = self.0;
+        let (i, j) = upper_index(self.size() - 1 - offset);
+        (n - 1 - i, n - 1 - j)
+    }
+}
+
+impl Symmetric {
+    /// Check that `(i, j)` is a valid index, and get the corresponding index
+    /// in the upper triangle
+    #[inline(always)]
+    fn check(&self, i: usize, j: usize) -> (usize, usize) {
+        check_index(self.0, i, j);
+        if i <= j {
+            (i, j)
+        } else {
+            (j, i)
+        }
+    }
+
+    #[inline(always)]
+    fn order(&self) -> usize {
+        self.0
+    }
+
+    #[inline(always)]
+    fn packed_size(&self) -> usize {
+        self.0 * (self.0 + 1) / 2
+    }
+
+    #[inline(always)]
+    fn packed_offset(&self, i: usize, j: usize) -> usize {
+        upper_offset(i, j)
+    }
+
+    #[inline(always)]
+    fn packed_index(&self, offset: usize) -> (usize, usize) {
+        upper_index(offset)
+    }
+}
+
+impl Banded {
+    /// Check if `(i, j)` is a valid index, *i.e.* if it is inside of the
+    /// matrix and of the band.
+    pub fn contains(&self, index: (usize, usize)) -> bool {
+        let (i, j) = index;
+        i < self.n && j < self.n && self.first_row(j) <= i && i <= j.saturating_add(self.kl)
+    }
+
+    /// Check that `(i, j)` is a valid index
+    #[inline(always)]
+    fn check(&self, i: usize, j: usize) -> (usize, usize) {
+        check_index(self.n, i, j);
+        assert!(self.contains((i, j)), "index ({}, {}) is outside of the band", i, j);
+        (i, j)
+    }
+
+    /// Get the first row of the band in column `j`
+    #[inline(always)]
+    fn first_row(&self, j: usize) -> usize {
+        j.saturating_sub(self.ku)
+    }
+
+    /// Get the offset of the first element of column `j`, for `j <= n`
+    #[inline(always)]
+    fn column_start(&self, j: usize) -> usize {
+        // Diagonals outside of the matrix do not change the storage
+        let kl = min(self.kl, self.n);
+        let ku = min(self.ku, self.n);
+        // Sum of the number of rows up to the end of the band, min(n, c + kl + 1),
+        // for the columns c before j ...
+        let full = min(j, self.n - kl);
+        let rows = full * (full + 2 * kl + 1) / 2 + (j - full) * self.n;
+        // ... minus the number of rows before the start of the band,
+        // max(0, c - ku)
+        let skipped = j.saturating_sub(ku + 1);
+        rows - skipped * (skipped + 1) / 2
+    }
+
+    #[inline(always)]
+    fn order(&self) -> usize {
+        self.n
+    }
+
+    #[inline(always)]
+    fn packed_size(&self) -> usize {
+        self.column_start(self.n)
+    }
+
+    #[inline(always)]
+    fn packed_offset(&self, i: usize, j: usize) -> usize {
+        self.column_start(j) + i - self.first_row(j)
+    }
+
+    #[inline(always)]
+    fn packed_index(&self, offset: usize) -> (usize, usize) {
+        // Find the last column starting before `offset`
+        let (mut first, mut last) = (0, self.n);
+        while last - first > 1 {
+            let middle = first + (last - first) / 2;
+            if self.column_start(middle) <= offset {
+                first = middle;
+            } else {
+                last = middle;
+            }
+        }
+        let j = first;
+        (self.first_row(j) + offset - self.column_start(j), j)
+    }
+}
+
+macro_rules! packed_dimensions {
+    ($($name: ident),*) => {$(
+        impl Dimensions for $name {
+            type Index = (usize, usize);
+            type Bounds = [isize; 2];
+            type Extents = [usize; 2];
+
+            #[inline(always)]
+            fn offset(&self, index: (usize, usize)) -> usize {
+                let (i, j) = self.check(index.0, index.1);
+                self.packed_offset(i, j)
+            }
+
+            #[inline(always)]
+            fn index(&self, offset: usize) -> (usize, usize) {
+                assert!(offset < self.size(), "offset out of bound: size is {} but offset is {}", self.size(), offset);
+                self.packed_index(offset)
+            }
+
+            #[inline(always)]
+            fn size(&self) -> usize {
+                self.packed_size()
+            }
+
+            #[inline(always)]
+            fn lbound(&self, axis: usize) -> isize {
+                check_axis(axis, 2);
+                0
+            }
+
+            #[inline(always)]
+            fn extent(&self, axis: usize) -> usize {
+                check_axis(axis, 2);
+                self.order()
+            }
+
+            fn with_extent(&self, axis: usize, extent: usize) -> $name {
+                check_axis(axis, 2);
+                assert!(extent == self.order(), "can not change the extent of a single axis of a packed matrix");
+                *self
+            }
+
+            #[inline(always)]
+            fn positions(&self, index: (usize, usize)) -> [usize; 2] {
+                self.check(index.0, index.1);
+                [index.0, index.1]
+            }
+
+            #[inline(always)]
+            fn index_from_positions(&self, positions: &[usize]) -> (usize, usize) {
+                assert!(positions.len() == 2, "expected 2 positions, got {}", positions.len());
+                self.check(positions[0], positions[1]);
+                (positions[0], positions[1])
+            }
+
+            fn strides(&self) -> Option<[usize; 2]> {
+                None
+            }
+        }
+    )*};
+}
+
+packed_dimensions!(UpperTriangular, LowerTriangular, Symmetric, Banded);
+
+impl<S, T> ArrayBase<S, Banded> where S: Storage<Item = T> {
+    /// Get a reference to the element at `index`, or `None` if the index is
+    /// outside of the band.
+    ///
+    /// ```
+    /// use mudi::{Array, Banded};
+    /// let matrix = Array::from_element(1.0, Banded { n: 3, kl: 0, ku: 1 });
+    /// assert_eq!(matrix.get((0, 1)), Some(&1.0));
+    /// assert_eq!(matrix.get((1, 0)), None);
+    /// ```
+    pub fn get(&self, index: (usize, usize)) -> Option<&T> {
+        if self.shape().contains(index) {
+            Some(&self[index])
+        } else {
+            None
+        }
+    }
+
+    /// Get a mutable reference to the element at `index`, or `None` if the
+    /// index is outside of the band.
+    pub fn get_mut(&mut self, index: (usize, usize)) -> Option<&mut T> {
+        if self.shape().contains(index) {
+            Some(&mut self[index])
+        } else {
+            None
+        }
+    }
+}
+
+impl<T: Clone> Array<T, Banded> {
+    /// Create a band matrix from data in LAPACK general band storage, with
+    /// `kl + ku + 1` elements for each column and `A(i, j)` stored at
+    /// `AB(ku + i - j, j)`. The unused elements of `data` are ignored.
+    ///
+    /// # Panics
+    /// If `data` does not contain `(kl + ku + 1) n` elements.
+    pub fn from_band_storage(data: &[T], band: Banded) -> Array<T, Banded> {
+        let leading = band.kl + band.ku + 1;
+        assert!(data.len() == leading * band.n, "expected {} elements in band storage, got {}",
+                leading * band.n, data.len());
+        Array::from_fn(band, |(i, j)| data[band.ku + i - j + j * leading].clone())
+    }
+}
+
+impl<S, T> ArrayBase<S, Banded> where S: Storage<Item = T>, T: Clone + Default {
+    /// Convert the band matrix to a dense matrix, using `T::default()` for
+    /// the elements outside of the band.
+    ///
+    /// ```
+    /// use mudi::{Array, Banded};
+    /// let matrix = Array::from_element(1, Banded { n: 3, kl: 1, ku: 0 });
+    /// let dense = matrix.to_dense();
+    /// assert_eq!(dense, Array::from_vector(vec![1, 0, 0, 1, 1, 0, 0, 1, 1], (3, 3)));
+    /// ```
+    pub fn to_dense(&self) -> Array<T, (usize, usize)> {
+        let n = self.shape().n;
+        Array::from_fn((n, n), |index| self.get(index).cloned().unwrap_or_default())
+    }
+
+    /// Copy the band matrix in LAPACK general band storage, with
+    /// `kl + ku + 1` elements for each column and `A(i, j)` stored at
+    /// `AB(ku + i - j, j)`. The unused elements are set to `T::default()`.
+    ///
+    /// ```
+    /// use mudi::{Array, Banded};
+    /// let matrix = Array::from_fn(Banded { n: 3, kl: 1, ku: 0 }, |(i, j)| 10 * i + j + 1);
+    /// assert_eq!(matrix.to_band_storage(), vec![1, 11, 12, 22, 23, 0]);
+    /// ```
+    pub fn to_band_storage(&self) -> Vec<T> {
+        let band = self.shape();
+        let leading = band.kl + band.ku + 1;
+        let mut data = vec![T::default(); leading * band.n];
+        for j in 0..band.n {
+            for i in band.first_row(j)..min(j.saturating_add(band.kl).saturating_add(1), band.n) {
+                data[band.ku + i - j + j * leading] = self[(i, j)].clone();
+            }
+        }
+        data
+    }
+
+    /// Compute the matrix-vector product of this matrix with `x`. The result
+    /// has the same dimensions as `x`, and the elements of `x` are taken in
+    /// order along its single axis, starting at its lower bound.
+    ///
+    /// ```
+    /// use mudi::{Array, Banded};
+    /// // Second order finite differences on the interior of a grid
+    /// let laplacian = Array::from_fn(Banded { n: 4, kl: 1, ku: 1 }, |(i, j)| if i == j { -2 } else { 1 });
+    /// let x = Array::from_vector(vec![1, 4, 9, 16], 1..5);
+    /// let y = laplacian.dot(&x);
+    /// assert_eq!(y[2], 2);
+    /// assert_eq!(y[4], -23);
+    /// ```
+    ///
+    /// # Panics
+    /// If the extent of `x` is not the size of the matrix.
+    pub fn dot<S2, D>(&self, x: &ArrayBase<S2, D>) -> Array<T, D>
+        where S2: Storage<Item = T>, D: Dimensions<Bounds = [isize; 1]>, T: Add<Output = T> + Mul<Output = T> {
+        let band = self.shape();
+        let dims = x.shape();
+        assert!(dims.extent(0) == band.n, "expected a vector with {} elements, got {}", band.n, dims.extent(0));
+        Array::from_fn(dims.clone(), |index| {
+            let i = dims.positions(index).as_ref()[0];
+            let first = i.saturating_sub(band.kl);
+            let last = min(i.saturating_add(band.ku).saturating_add(1), band.n);
+            (first..last).fold(T::default(), |sum, j| sum + self[(i, j)].clone() * x[dims.index_from_positions(&[j])].clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Array;
+
+    #[test]
+    fn upper() {
+        let n = 5;
+        let dims = UpperTriangular(n);
+        assert_eq!(dims.size(), 15);
+        assert_eq!(dims.extents(), [5, 5]);
+        for offset in 0..dims.size() {
+            let (i, j) = dims.index(offset);
+            assert!(i <= j);
+            assert_eq!(dims.offset((i, j)), offset);
+            // LAPACK: AP(i + (j - 1) j / 2) = A(i, j) with 1-based indexes
+            let (i, j) = (i + 1, j + 1);
+            assert_eq!(offset + 1, i + (j - 1) * j / 2);
+        }
+    }
+
+    #[test]
+    fn lower() {
+        let n = 5;
+        let dims = LowerTriangular(n);
+        assert_eq!(dims.size(), 15);
+        for offset in 0..dims.size() {
+            let (i, j) = dims.index(offset);
+            assert!(i >= j);
+            assert_eq!(dims.offset((i, j)), offset);
+            // LAPACK: AP(i + (j - 1) (2n - j) / 2) = A(i, j) with 1-based indexes
+            let (i, j) = (i + 1, j + 1);
+            assert_eq!(offset + 1, i + (j - 1) * (2 * n - j) / 2);
+        }
+    }
+
+    #[test]
+    fn large() {
+        let dims = UpperTriangular(100_000);
+        let last = dims.size() - 1;
+        assert_eq!(dims.index(last), (99_999, 99_999));
+        assert_eq!(dims.index(last - 99_999), (0, 99_999));
+        assert_eq!(dims.index(last - 100_000), (99_998, 99_998));
+    }
+
+    #[test]
+    fn symmetric() {
+        let array = Array::from_fn(Symmetric(4), |(i, j)| 10 * i + j);
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(array[(i, j)], array[(j, i)]);
+            }
+        }
+        assert_eq!(array[(3, 1)], 13);
+        assert_eq!(array.shape().positions((3, 1)), [3, 1]);
+    }
+
+    #[test]
+    fn views() {
+        let array = Array::from_fn(UpperTriangular(4), |(i, j)| 10 * i + j);
+        let view = array.subview(UpperTriangular(2));
+        assert_eq!(view.iter().cloned().collect::<Vec<_>>(), vec![0, 1, 11]);
+        assert_eq!(view.to_owned(), Array::from_vector(vec![0, 1, 11], UpperTriangular(2)));
+        assert_eq!(array.shape().strides(), None);
+    }
+
+    #[test]
+    fn banded() {
+        let (n, kl, ku) = (6, 2, 1);
+        let dims = Banded { n, kl, ku };
+        // 6 elements on the diagonal, 5 + 4 below and 5 above
+        assert_eq!(dims.size(), 20);
+        assert_eq!(dims.extents(), [6, 6]);
+        let mut expected = 0;
+        for j in 0..n {
+            for i in 0..n {
+                if dims.contains((i, j)) {
+                    assert_eq!(dims.offset((i, j)), expected);
+                    assert_eq!(dims.index(expected), (i, j));
+                    expected += 1;
+                }
+            }
+        }
+        assert_eq!(expected, dims.size());
+
+        for &(n, kl, ku) in &[(0, 1, 1), (1, 0, 0), (5, 0, 0), (5, 4, 0), (5, 0, 7), (4, 10, 10), (7, 2, 3)] {
+            let dims = Banded { n, kl, ku };
+            let count = (0..n).flat_map(|i| (0..n).map(move |j| (i, j))).filter(|&index| dims.contains(index)).count();
+            assert_eq!(dims.size(), count);
+            for offset in 0..dims.size() {
+                assert_eq!(dims.offset(dims.index(offset)), offset);
+            }
+        }
+
+        let matrix = Array::from_fn(dims, |(i, j)| 10 * i + j);
+        assert_eq!(matrix[(3, 1)], 31);
+        assert_eq!(matrix.get((3, 1)), Some(&31));
+        assert_eq!(matrix.get((0, 2)), None);
+        assert_eq!(matrix.get((3, 0)), None);
+        assert_eq!(matrix.get((6, 6)), None);
+    }
+
+    #[test]
+    fn banded_dense() {
+        let mut matrix = Array::from_fn(Banded { n: 5, kl: 1, ku: 2 }, |(i, j)| i as i32 - 2 * j as i32);
+        *matrix.get_mut((4, 3)).unwrap() = 7;
+        assert!(matrix.get_mut((4, 2)).is_none());
+
+        let dense = matrix.to_dense();
+        for i in 0..5 {
+            for j in 0..5 {
+                assert_eq!(dense[(i, j)], matrix.get((i, j)).cloned().unwrap_or(0));
+            }
+        }
+
+        let x = Array::from_fn(-2..3, |i| i * i + 1);
+        let y = matrix.dot(&x);
+        assert_eq!(y.shape(), -2..3);
+        for i in 0..5 {
+            let expected = (0..5).map(|j| dense[(i, j)] * x[j as i32 - 2]).sum::<i32>();
+            assert_eq!(y[i as i32 - 2], expected);
+        }
+    }
+
+    #[test]
+    fn band_storage() {
+        let (n, kl, ku) = (5, 2, 1);
+        let matrix = Array::from_fn(Banded { n, kl, ku }, |(i, j)| 10 * i + j + 1);
+        let data = matrix.to_band_storage();
+        assert_eq!(data.len(), 20);
+        for i in 0..n {
+            for j in 0..n {
+                if let Some(&value) = matrix.get((i, j)) {
+                    // LAPACK: AB(ku + 1 + i - j, j) = A(i, j) with 1-based
+                    // indexes and a leading dimension of kl + ku + 1
+                    let (i, j) = (i + 1, j + 1);
+                    assert_eq!(data[ku + i - j + (j - 1) * (kl + ku + 1)], value);
+                }
+            }
+        }
+        // Unused elements in the corners
+        assert_eq!(data[0], 0);
+        assert_eq!(data[19], 0);
+        assert_eq!(Array::from_band_storage(&data, Banded { n, kl, ku }), matrix);
+    }
+
+    #[test]
+    fn unique_elements() {
+        let dims = [Banded { n: 4, kl: 1, ku: 1 }, Banded { n: 6, kl: 0, ku: 2 }, Banded { n: 3, kl: 5, ku: 5 }];
+        for &dims in &dims {
+            let mut matrix = Array::from_element(0, dims);
+            for element in matrix.view_mut().iter_mut() {
+                *element += 1;
+            }
+            assert!(matrix.flat_iter().all(|&count| count == 1));
+            assert_eq!(matrix.view().iter().count(), dims.size());
+        }
+        let mut matrix = Array::from_element(0, Symmetric(4));
+        for element in matrix.view_mut().iter_mut() {
+            *element += 1;
+        }
+        assert!(matrix.flat_iter().all(|&count| count == 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "index (3, 0) is outside of the band")]
+    fn outside_band() {
+        let mut matrix = Array::from_element(0.0, Banded { n: 4, kl: 2, ku: 0 });
+        matrix[(3, 0)] = 1.0;
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a vector with 4 elements, got 3")]
+    fn banded_dot() {
+        let matrix = Array::from_element(1, Banded { n: 4, kl: 1, ku: 1 });
+        let _ = matrix.dot(&Array::from_element(1, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "index (2, 1) is outside of the upper triangle")]
+    fn outside_upper() {
+        UpperTriangular(3).offset((2, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "index (0, 1) is outside of the lower triangle")]
+    fn outside_lower() {
+        LowerTriangular(3).offset((0, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bound: matrix size is 3x3 but index is (3, 0)")]
+    fn out_of_bounds() {
+        Symmetric(3).offset((3, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "windows and stencils require dimensions with strided storage")]
+    fn windows() {
+        let array = Array::from_element(0, Symmetric(3));
+        let _ = array.windows([2, 2]);
+    }
+}
@@ -0,0 +1,220 @@
+use {ArrayBase, Dimensions, Storage};
+
+/// Rearrange `data` in place, so that `data[i]` gets the value previously
+/// stored at `data[source(i)]`. `source` must be a permutation.
+fn permute<T, F>(data: &mut [T], source: F) where F: Fn(usize) -> usize {
+    let mut done = vec![false; data.len()];
+    for start in 0..data.len() {
+        if done[start] {
+            continue;
+        }
+        // Follow the cycle of the permutation starting at `start`, moving
+        // the initial value along until the cycle closes.
+        let mut current = start;
+        loop {
+            done[current] = true;
+            let next = source(current);
+            if next == start {
+                break;
+            }
+            data.swap(current, next);
+            current = next;
+        }
+    }
+}
+
+/// Get the offset of the element `shift` positions away from the element at
+/// `offset` along `axis`, or `None` if this position is outside of the
+/// dimensions. If `circular` is true, the position wraps around instead.
+fn shifted<D: Dimensions>(dims: &D, offset: usize, shift: isize, axis: usize, circular: bool) -> Option<usize> {
+    let extent = dims.extent(axis) as isize;
+    let mut positions = dims.positions(dims.index(offset));
+    let position = positions.as_ref()[axis] as isize + shift;
+    let position = if circular {
+        position.rem_euclid(extent)
+    } else if 0 <= position && position < extent {
+        position
+    } else {
+        return None;
+    };
+    positions.as_mut()[axis] = position as usize;
+    Some(dims.offset(dims.index_from_positions(positions.as_ref())))
+}
+
+impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
+    /// Circularly shift the elements of the array along `axis`, like the
+    /// Fortran `CSHIFT(array, shift, dim)` intrinsic, with axes numbered from
+    /// 0. The element at position `i` along `axis` is replaced by the element
+    /// at position `i + shift`, wrapping around at the ends of the axis.
+    /// Positive shifts thus move the elements toward the lower bound.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let mut array = Array::from_vector(vec![1, 2, 3, 4, 5, 6], (2, -1..2));
+    /// array.cshift_inplace(1, 1);
+    /// assert_eq!(array, Array::from_vector(vec![2, 3, 1, 5, 6, 4], (2, -1..2)));
+    /// ```
+    pub fn cshift_inplace(&mut self, shift: isize, axis: usize) {
+        let dims = self.shape();
+        if dims.extent(axis) == 0 {
+            return;
+        }
+        let data = self.flat_iter_mut().into_slice();
+        permute(data, |offset| {
+            shifted(&dims, offset, shift, axis, true).expect("circular shifts are always inside the array")
+        });
+    }
+
+    /// Shift the elements of the array along `axis`, like the Fortran
+    /// `EOSHIFT(array, shift, boundary, dim)` intrinsic, with axes numbered
+    /// from 0. The element at position `i` along `axis` is replaced by the
+    /// element at position `i + shift`, or by `boundary` if this position is
+    /// outside of the array.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let mut array = Array::from_vector(vec![1, 2, 3, 4, 5, 6], (2, -1..2));
+    /// array.eoshift_inplace(-1, 0, 1);
+    /// assert_eq!(array, Array::from_vector(vec![0, 1, 2, 0, 4, 5], (2, -1..2)));
+    /// ```
+    pub fn eoshift_inplace(&mut self, shift: isize, boundary: S::Item, axis: usize) where S::Item: Clone {
+        let dims = self.shape();
+        if dims.extent(axis) == 0 {
+            return;
+        }
+        // Do a circular shift, and then replace the elements which wrapped
+        // around by the boundary value.
+        self.cshift_inplace(shift, axis);
+        for (offset, value) in self.flat_iter_mut().enumerate() {
+            if shifted(&dims, offset, shift, axis, false).is_none() {
+                *value = boundary.clone();
+            }
+        }
+    }
+
+    /// Get a copy of this array, circularly shifted along `axis`. See
+    /// [`ArrayBase::cshift_inplace`](#method.cshift_inplace).
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_vector(vec![1, 2, 3, 4, 5, 6], (-1..2, 2));
+    /// let shifted = array.cshift(-1, 0);
+    /// assert_eq!(shifted[(-1, 0)], 5);
+    /// assert_eq!(shifted[(0, 1)], 2);
+    /// ```
+    pub fn cshift(&self, shift: isize, axis: usize) -> ArrayBase<S, D> where S::Item: Clone {
+        let dims = self.shape();
+        let data = self.flat_iter().as_slice();
+        ArrayBase::from_fn(dims.clone(), |index| {
+            let source = shifted(&dims, dims.offset(index), shift, axis, true);
+            data[source.expect("circular shifts are always inside the array")].clone()
+        })
+    }
+
+    /// Get a copy of this array, shifted along `axis` and filled with
+    /// `boundary`. See [`ArrayBase::eoshift_inplace`](#method.eoshift_inplace).
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_vector(vec![1, 2, 3, 4, 5, 6], (-1..2, 2));
+    /// let shifted = array.eoshift(2, 0, 0);
+    /// assert_eq!(shifted, Array::from_vector(vec![5, 6, 0, 0, 0, 0], (-1..2, 2)));
+    /// ```
+    pub fn eoshift(&self, shift: isize, boundary: S::Item, axis: usize) -> ArrayBase<S, D> where S::Item: Clone {
+        let dims = self.shape();
+        let data = self.flat_iter().as_slice();
+        ArrayBase::from_fn(dims.clone(), |index| {
+            match shifted(&dims, dims.offset(index), shift, axis, false) {
+                Some(source) => data[source].clone(),
+                None => boundary.clone(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Array, Periodic};
+
+    fn values<D: ::Dimensions>(array: &Array<i32, D>) -> Vec<i32> {
+        array.flat_iter().cloned().collect()
+    }
+
+    fn copy<D: ::Dimensions>(array: &Array<i32, D>) -> Array<i32, D> {
+        Array::from_shape_iter(array.shape(), array.flat_iter().cloned())
+    }
+
+    #[test]
+    fn cshift_1d() {
+        let array = Array::from_vector(vec![1, 2, 3, 4, 5], -2..3);
+        assert_eq!(values(&array.cshift(2, 0)), vec![3, 4, 5, 1, 2]);
+        assert_eq!(values(&array.cshift(-1, 0)), vec![5, 1, 2, 3, 4]);
+        assert_eq!(values(&array.cshift(7, 0)), vec![3, 4, 5, 1, 2]);
+        assert_eq!(values(&array.cshift(0, 0)), vec![1, 2, 3, 4, 5]);
+        assert_eq!(array.cshift(2, 0).shape(), -2..3);
+
+        let mut inplace = copy(&array);
+        inplace.cshift_inplace(-1, 0);
+        assert_eq!(values(&inplace), vec![5, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn cshift_3d() {
+        let array = Array::from_fn((2, 3, -1..3), |(i, j, k)| 100 * i as i32 + 10 * j as i32 + k);
+        for axis in 0..3 {
+            for &shift in &[-4, -1, 1, 2, 5] {
+                let shifted = array.cshift(shift, axis);
+                let mut inplace = copy(&array);
+                inplace.cshift_inplace(shift, axis);
+                assert_eq!(shifted, inplace);
+
+                for i in 0..2 {
+                    for j in 0..3 {
+                        for k in -1..3 {
+                            let mut index = [i as isize, j as isize, k as isize + 1];
+                            let extent = [2, 3, 4][axis];
+                            index[axis] = (index[axis] + shift).rem_euclid(extent);
+                            let source = (index[0] as usize, index[1] as usize, index[2] as i32 - 1);
+                            assert_eq!(shifted[(i, j, k)], array[source]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn eoshift() {
+        let array = Array::from_vector(vec![1, 2, 3, 4, 5, 6], (2, 1..=3));
+        assert_eq!(values(&array.eoshift(1, -1, 1)), vec![2, 3, -1, 5, 6, -1]);
+        assert_eq!(values(&array.eoshift(-2, -1, 1)), vec![-1, -1, 1, -1, -1, 4]);
+        assert_eq!(values(&array.eoshift(5, -1, 1)), vec![-1; 6]);
+        assert_eq!(values(&array.eoshift(-1, 0, 0)), vec![0, 0, 0, 1, 2, 3]);
+
+        let mut inplace = copy(&array);
+        inplace.eoshift_inplace(-2, -1, 1);
+        assert_eq!(values(&inplace), vec![-1, -1, 1, -1, -1, 4]);
+    }
+
+    #[test]
+    fn periodic() {
+        let array = Array::from_vector(vec![1, 2, 3, 4], Periodic(0..4));
+        assert_eq!(values(&array.cshift(1, 0)), vec![2, 3, 4, 1]);
+        assert_eq!(values(&array.eoshift(1, 0, 0)), vec![2, 3, 4, 0]);
+    }
+
+    #[test]
+    fn empty() {
+        let mut array = Array::<i32, _>::from_vector(vec![], (0, 3));
+        array.cshift_inplace(1, 0);
+        array.eoshift_inplace(1, 0, 1);
+        assert_eq!(array.cshift(2, 1).shape(), (0, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "axis 2 is out of bound for 2-dimensional dimensions")]
+    fn invalid_axis() {
+        let array = Array::from_element(0, (2, 3));
+        array.cshift(1, 2);
+    }
+}
@@ -0,0 +1,334 @@
+use std::ops::{Add, Index, Mul};
+
+use {Array, ArrayBase, Dimensions, FromBounds, Storage};
+
+/// Sparse matrix in coordinate (COO) format, used to assemble a
+/// [`CsrMatrix`](struct.CsrMatrix.html). The matrix uses rank-2 `Dimensions`
+/// for its bounds and indexes, and stores a list of `(index, value)` entries.
+/// Entries with the same index are summed when converting to CSR.
+///
+/// ```
+/// use mudi::CooMatrix;
+/// let mut coo = CooMatrix::new((-1..2, 0..3));
+/// coo.push((-1, 0), 2.0);
+/// coo.push((1, 2), 1.0);
+/// coo.push((-1, 0), 3.0);
+/// let csr = coo.to_csr();
+/// assert_eq!(csr[(-1, 0)], 5.0);
+/// assert_eq!(csr[(0, 1)], 0.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CooMatrix<T, D> where D: Dimensions<Bounds = [isize; 2]> {
+    dims: D,
+    entries: Vec<(usize, usize, T)>,
+}
+
+impl<T, D> CooMatrix<T, D> where D: Dimensions<Bounds = [isize; 2]> {
+    /// Create an empty matrix with the given dimensions
+    pub fn new(dims: D) -> CooMatrix<T, D> {
+        CooMatrix {
+            dims,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add `value` at `index` in the matrix.
+    ///
+    /// # Panics
+    /// If the index is out of bounds.
+    pub fn push(&mut self, index: D::Index, value: T) {
+        let positions = self.dims.positions(index);
+        let positions = positions.as_ref();
+        self.entries.push((positions[0], positions[1], value));
+    }
+
+    /// Get the shape of the matrix
+    pub fn shape(&self) -> D {
+        self.dims.clone()
+    }
+
+    /// Get the number of entries in the matrix, including duplicates
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the matrix does not contain any entry
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<T, D> CooMatrix<T, D> where D: Dimensions<Bounds = [isize; 2]>, T: Clone + Default + Add<Output = T> {
+    /// Convert the matrix to CSR format, summing the entries with the same
+    /// index.
+    pub fn to_csr(&self) -> CsrMatrix<T, D> {
+        let mut entries = self.entries.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|entry| (entry.0, entry.1));
+
+        let rows = self.dims.extent(0);
+        let mut offsets = vec![0; rows + 1];
+        let mut columns: Vec<usize> = Vec::new();
+        let mut values: Vec<T> = Vec::new();
+        let mut previous = None;
+        for &&(i, j, ref value) in &entries {
+            if previous == Some((i, j)) {
+                let last = values.last_mut().expect("duplicated entries have a previous value");
+                *last = last.clone() + value.clone();
+            } else {
+                offsets[i + 1] += 1;
+                columns.push(j);
+                values.push(value.clone());
+                previous = Some((i, j));
+            }
+        }
+        for i in 0..rows {
+            offsets[i + 1] += offsets[i];
+        }
+
+        CsrMatrix {
+            dims: self.dims.clone(),
+            offsets,
+            columns,
+            values,
+            zero: T::default(),
+        }
+    }
+}
+
+/// Sparse matrix in compressed sparse row (CSR) format. The matrix uses
+/// rank-2 `Dimensions` for its bounds and indexes, and only stores the
+/// non-zero elements. Indexing outside of the stored elements returns a
+/// shared zero, `T::default()`.
+///
+/// ```
+/// use mudi::{Array, CsrMatrix};
+/// let dense = Array::from_fn((1..4, -1..2), |(i, j)| if i + j == 2 { 1 } else { 0 });
+/// let sparse = CsrMatrix::from_dense(&dense);
+/// assert_eq!(sparse.nnz(), 3);
+/// assert_eq!(sparse[(3, -1)], 1);
+/// assert_eq!(sparse.get((3, 0)), None);
+/// assert_eq!(sparse.to_dense(), dense);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix<T, D> where D: Dimensions<Bounds = [isize; 2]> {
+    dims: D,
+    /// Offset of the first element of each row in `columns` and `values`,
+    /// with an additional offset for the end of the last row
+    offsets: Vec<usize>,
+    /// Column position of each element, sorted in each row
+    columns: Vec<usize>,
+    values: Vec<T>,
+    zero: T,
+}
+
+impl<T, D> CsrMatrix<T, D> where D: Dimensions<Bounds = [isize; 2]> {
+    /// Get the shape of the matrix
+    pub fn shape(&self) -> D {
+        self.dims.clone()
+    }
+
+    /// Get the number of stored elements
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Get the position of the element at `index` in `values`, if it is
+    /// stored.
+    fn find(&self, index: D::Index) -> Option<usize> {
+        let positions = self.dims.positions(index);
+        let positions = positions.as_ref();
+        let (start, end) = (self.offsets[positions[0]], self.offsets[positions[0] + 1]);
+        self.columns[start..end].binary_search(&positions[1]).ok().map(|found| start + found)
+    }
+
+    /// Get a reference to the element at `index`, or `None` if it is not
+    /// stored.
+    ///
+    /// # Panics
+    /// If the index is out of bounds.
+    pub fn get(&self, index: D::Index) -> Option<&T> {
+        self.find(index).map(|found| &self.values[found])
+    }
+
+    /// Get a mutable reference to the element at `index`, or `None` if it is
+    /// not stored. Elements can not be added to a CSR matrix, use a
+    /// [`CooMatrix`](struct.CooMatrix.html) to assemble it instead.
+    ///
+    /// # Panics
+    /// If the index is out of bounds.
+    pub fn get_mut(&mut self, index: D::Index) -> Option<&mut T> {
+        self.find(index).map(move |found| &mut self.values[found])
+    }
+
+    /// Iterate over the stored elements and their indexes, row by row.
+    ///
+    /// ```
+    /// use mudi::CooMatrix;
+    /// let mut coo = CooMatrix::new((2, -1..1));
+    /// coo.push((1, -1), 5);
+    /// coo.push((0, 0), 7);
+    /// let csr = coo.to_csr();
+    /// assert_eq!(csr.iter().collect::<Vec<_>>(), vec![((0, 0), &7), ((1, -1), &5)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (D::Index, &T)> + '_ {
+        (0..self.dims.extent(0)).flat_map(move |i| {
+            let (start, end) = (self.offsets[i], self.offsets[i + 1]);
+            (start..end).map(move |k| (self.dims.index_from_positions(&[i, self.columns[k]]), &self.values[k]))
+        })
+    }
+}
+
+impl<T, D> CsrMatrix<T, D> where D: Dimensions<Bounds = [isize; 2]>, T: Clone + Default + PartialEq {
+    /// Create a sparse matrix from the elements of `array` which are not
+    /// equal to `T::default()`.
+    pub fn from_dense<S>(array: &ArrayBase<S, D>) -> CsrMatrix<T, D> where S: Storage<Item = T> {
+        let dims = array.shape();
+        let zero = T::default();
+        let mut offsets = Vec::with_capacity(dims.extent(0) + 1);
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        offsets.push(0);
+        for i in 0..dims.extent(0) {
+            for j in 0..dims.extent(1) {
+                let value = &array[dims.index_from_positions(&[i, j])];
+                if *value != zero {
+                    columns.push(j);
+                    values.push(value.clone());
+                }
+            }
+            offsets.push(values.len());
+        }
+        CsrMatrix {
+            dims,
+            offsets,
+            columns,
+            values,
+            zero,
+        }
+    }
+}
+
+impl<T, D> CsrMatrix<T, D> where D: Dimensions<Bounds = [isize; 2]>, T: Clone {
+    /// Convert the matrix to a dense array, using `T::default()` for the
+    /// elements which are not stored.
+    pub fn to_dense(&self) -> Array<T, D> {
+        Array::from_fn(self.dims.clone(), |index| self[index].clone())
+    }
+
+    /// Compute the matrix-vector product of this matrix with `x`. The
+    /// elements of `x` are taken in order along its single axis, starting at
+    /// its lower bound, and the result has the same bounds as the rows of the
+    /// matrix.
+    ///
+    /// ```
+    /// use mudi::{Array, CooMatrix};
+    /// let mut coo = CooMatrix::new((-1..1, 3));
+    /// coo.push((-1, 2), 2);
+    /// coo.push((0, 0), 1);
+    /// coo.push((0, 1), -1);
+    /// let x = Array::from_vector(vec![1, 2, 3], 1..4);
+    /// let y = coo.to_csr().dot(&x);
+    /// assert_eq!(y, Array::from_vector(vec![6, -1], -1..1));
+    /// ```
+    ///
+    /// # Panics
+    /// If the extent of `x` is not the number of columns of the matrix, or if
+    /// `D2` can not use the bounds of the matrix rows.
+    pub fn dot<S, D2>(&self, x: &ArrayBase<S, D2>) -> Array<T, D2>
+        where S: Storage<Item = T>, D2: FromBounds<Bounds = [isize; 1]>, T: Add<Output = T> + Mul<Output = T> {
+        let xdims = x.shape();
+        let columns = self.dims.extent(1);
+        assert!(xdims.extent(0) == columns, "expected a vector with {} elements, got {}", columns, xdims.extent(0));
+        let dims = D2::from_bounds(&[self.dims.lbound(0)], &[self.dims.extent(0)])
+            .expect("the result dimensions should accept the bounds of the matrix rows");
+        Array::from_fn(dims.clone(), |index| {
+            let i = dims.positions(index).as_ref()[0];
+            (self.offsets[i]..self.offsets[i + 1]).fold(self.zero.clone(), |sum, k| {
+                let j = xdims.index_from_positions(&[self.columns[k]]);
+                sum + self.values[k].clone() * x[j].clone()
+            })
+        })
+    }
+}
+
+impl<T, D, I: Copy> Index<I> for CsrMatrix<T, D> where D: Dimensions<Index = I, Bounds = [isize; 2]> {
+    type Output = T;
+    fn index(&self, index: I) -> &T {
+        self.get(index).unwrap_or(&self.zero)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assembly() {
+        let mut coo = CooMatrix::new((-2..2, -1..3));
+        assert!(coo.is_empty());
+        coo.push((1, 2), 1);
+        coo.push((-2, -1), 2);
+        coo.push((1, 0), 3);
+        coo.push((1, 2), 4);
+        coo.push((-1, 0), 5);
+        assert_eq!(coo.len(), 5);
+
+        let csr = coo.to_csr();
+        assert_eq!(csr.shape(), (-2..2, -1..3));
+        assert_eq!(csr.nnz(), 4);
+        assert_eq!(csr.offsets, vec![0, 1, 2, 2, 4]);
+        assert_eq!(csr.columns, vec![0, 1, 1, 3]);
+        assert_eq!(csr.values, vec![2, 5, 3, 5]);
+        assert_eq!(csr[(1, 2)], 5);
+        assert_eq!(csr[(0, 0)], 0);
+        assert_eq!(csr.get((0, 0)), None);
+    }
+
+    #[test]
+    fn dense() {
+        let dense = Array::from_fn((-1..3, 0..5), |(i, j)| if (i + j) % 3 == 0 { i * 10 + j } else { 0 });
+        let mut csr = CsrMatrix::from_dense(&dense);
+        assert_eq!(csr.nnz(), 6);
+        assert_eq!(csr.to_dense(), dense);
+        assert_eq!(csr.iter().count(), 6);
+        for (index, value) in csr.iter() {
+            assert_eq!(dense[index], *value);
+        }
+
+        *csr.get_mut((2, 4)).unwrap() = 1;
+        assert!(csr.get_mut((2, 3)).is_none());
+        assert_eq!(csr[(2, 4)], 1);
+
+        let x = Array::from_fn(-2..3, |i| i * i - 1);
+        let y = csr.dot(&x);
+        assert_eq!(y.shape(), -1..3);
+        let dense = csr.to_dense();
+        for i in -1..3 {
+            let expected = (0..5).map(|j| dense[(i, j)] * x[j - 2]).sum::<i32>();
+            assert_eq!(y[i], expected);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let coo = CooMatrix::<f64, _>::new((3, 0));
+        let csr = coo.to_csr();
+        assert_eq!(csr.nnz(), 0);
+        assert_eq!(csr.to_dense(), Array::from_element(0.0, (3, 0)));
+        assert_eq!(csr.dot(&Array::from_element(1.0, 0)), Array::from_element(0.0, 3));
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bound")]
+    fn out_of_bounds() {
+        let mut coo = CooMatrix::new((-1..1, 2));
+        coo.push((1, 0), 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "expected a vector with 2 elements, got 3")]
+    fn dot() {
+        let csr = CooMatrix::<f64, _>::new((3, 2)).to_csr();
+        let _ = csr.dot(&Array::from_element(1.0, 3));
+    }
+}
@@ -0,0 +1,540 @@
+use std::fmt;
+use std::ops::Index;
+
+use {ArrayBase, ArrayView, Dimensions, Storage};
+use views::region_origin;
+
+/// How to handle the points close to the edges of the array in a
+/// [`Stencil`](struct.Stencil.html).
+pub enum StencilBoundary<'a, T: 'a> {
+    /// Only visit the points with a neighborhood entirely inside the array
+    Interior,
+    /// Visit all the points, using the given value for neighbors outside of
+    /// the array
+    Pad(&'a T),
+    /// Visit all the points, wrapping neighbors outside of the array around
+    /// to the other side of the array
+    Periodic,
+}
+
+impl<'a, T> Clone for StencilBoundary<'a, T> {
+    fn clone(&self) -> StencilBoundary<'a, T> {
+        *self
+    }
+}
+
+impl<'a, T> Copy for StencilBoundary<'a, T> {}
+
+impl<'a, T: fmt::Debug> fmt::Debug for StencilBoundary<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StencilBoundary::Interior => write!(f, "Interior"),
+            StencilBoundary::Pad(value) => write!(f, "Pad({:?})", value),
+            StencilBoundary::Periodic => write!(f, "Periodic"),
+        }
+    }
+}
+
+/// Get positions with the same number of axes as `like`, all set to 0
+fn zeros<E: AsMut<[usize]> + Clone>(like: &E) -> E {
+    let mut zeros = like.clone();
+    for value in zeros.as_mut() {
+        *value = 0;
+    }
+    zeros
+}
+
+/// Get the storage offset of `positions`
+fn offset(positions: &[usize], strides: &[usize]) -> usize {
+    positions.iter().zip(strides).map(|(position, stride)| position * stride).sum()
+}
+
+/// Move `positions` to the next positions in storage order inside of the
+/// `[lower, upper)` region, returning `false` when the region is exhausted.
+fn advance(positions: &mut [usize], lower: &[usize], upper: &[usize]) -> bool {
+    for axis in (0..positions.len()).rev() {
+        positions[axis] += 1;
+        if positions[axis] < upper[axis] {
+            return true;
+        }
+        positions[axis] = lower[axis];
+    }
+    false
+}
+
+/// Get the first positions of the `[lower, upper)` region, or `None` if the
+/// region is empty.
+fn first<E: AsRef<[usize]> + Clone>(lower: &E, upper: &E) -> Option<E> {
+    if lower.as_ref().iter().zip(upper.as_ref()).all(|(lower, upper)| lower < upper) {
+        Some(lower.clone())
+    } else {
+        None
+    }
+}
+
+/// Get the positions of the `dims` region inside of `layout`, as a
+/// `[lower, upper)` pair. Regions wrapping around a periodic axis of the
+/// layout are not supported.
+fn region<D: Dimensions>(layout: &D, dims: &D) -> (D::Extents, D::Extents) {
+    let lower = region_origin(layout, dims);
+    let mut upper = lower.clone();
+    for (upper, extent) in upper.as_mut().iter_mut().zip(dims.extents().as_ref()) {
+        *upper += extent;
+    }
+    (lower, upper)
+}
+
+/// Iterator over all the windows of a given shape in an array, created by
+/// [`ArrayBase::windows`](struct.ArrayBase.html#method.windows).
+pub struct Windows<'a, T: 'a, D> where D: Dimensions {
+    data: &'a [T],
+    layout: D,
+    strides: D::Extents,
+    shape: D::Extents,
+    lower: D::Extents,
+    upper: D::Extents,
+    next: Option<D::Extents>,
+}
+
+impl<'a, T, D> Windows<'a, T, D> where D: Dimensions {
+    fn new(data: &'a [T], layout: D, dims: &D, shape: D::Extents) -> Windows<'a, T, D> {
+        assert!(shape.as_ref().iter().all(|&extent| extent != 0), "window extents must be non-zero");
+        let (lower, mut upper) = region(&layout, dims);
+        // Windows origins go from the lower bound to `upper - shape`, if the
+        // window fits in the region
+        for ((upper, lower), extent) in upper.as_mut().iter_mut().zip(lower.as_ref()).zip(shape.as_ref()) {
+            *upper = if *upper - lower >= *extent { *upper - extent + 1 } else { *lower };
+        }
+        Windows {
+            data,
+            strides: layout.strides().expect("windows and stencils require dimensions with strided storage"),
+            layout,
+            shape,
+            next: first(&lower, &upper),
+            lower,
+            upper,
+        }
+    }
+}
+
+impl<'a, T, D> Iterator for Windows<'a, T, D> where D: Dimensions {
+    type Item = Window<'a, T, D>;
+
+    fn next(&mut self) -> Option<Window<'a, T, D>> {
+        let origin = self.next.take()?;
+        let mut next = origin.clone();
+        if advance(next.as_mut(), self.lower.as_ref(), self.upper.as_ref()) {
+            self.next = Some(next);
+        }
+        Some(Window {
+            data: self.data,
+            index: self.layout.index_from_positions(origin.as_ref()),
+            offset: offset(origin.as_ref(), self.strides.as_ref()),
+            strides: self.strides.clone(),
+            shape: self.shape.clone(),
+        })
+    }
+}
+
+/// A rectangular window in an array, indexed by the positions of the
+/// elements relative to the first element of the window.
+pub struct Window<'a, T: 'a, D> where D: Dimensions {
+    data: &'a [T],
+    index: D::Index,
+    offset: usize,
+    strides: D::Extents,
+    shape: D::Extents,
+}
+
+impl<'a, T, D> Window<'a, T, D> where D: Dimensions {
+    /// Get the array index of the first element in this window.
+    pub fn origin(&self) -> D::Index {
+        self.index
+    }
+
+    /// Get the number of elements along each axis of this window.
+    pub fn shape(&self) -> D::Extents {
+        self.shape.clone()
+    }
+
+    /// Iterate over the elements in this window, in storage order.
+    pub fn iter(&self) -> WindowIter<'a, T, D> {
+        WindowIter {
+            data: self.data,
+            offset: self.offset,
+            strides: self.strides.clone(),
+            lower: zeros(&self.shape),
+            next: first(&zeros(&self.shape), &self.shape),
+            shape: self.shape.clone(),
+        }
+    }
+}
+
+impl<'a, T, D> Index<D::Extents> for Window<'a, T, D> where D: Dimensions {
+    type Output = T;
+    fn index(&self, positions: D::Extents) -> &T {
+        for (axis, (&position, &extent)) in positions.as_ref().iter().zip(self.shape.as_ref()).enumerate() {
+            assert!(position < extent, "position {} is outside of the window along axis {}: extent is {}",
+                    position, axis, extent);
+        }
+        &self.data[self.offset + offset(positions.as_ref(), self.strides.as_ref())]
+    }
+}
+
+/// Iterator over the elements of a `Window`.
+pub struct WindowIter<'a, T: 'a, D> where D: Dimensions {
+    data: &'a [T],
+    offset: usize,
+    strides: D::Extents,
+    lower: D::Extents,
+    shape: D::Extents,
+    next: Option<D::Extents>,
+}
+
+impl<'a, T, D> Iterator for WindowIter<'a, T, D> where D: Dimensions {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let mut positions = self.next.take()?;
+        let value = &self.data[self.offset + offset(positions.as_ref(), self.strides.as_ref())];
+        if advance(positions.as_mut(), self.lower.as_ref(), self.shape.as_ref()) {
+            self.next = Some(positions);
+        }
+        Some(value)
+    }
+}
+
+/// Iterator over the points of an array together with their neighborhood,
+/// created by [`ArrayBase::stencil`](struct.ArrayBase.html#method.stencil).
+pub struct Stencil<'a, T: 'a, D> where D: Dimensions {
+    data: &'a [T],
+    layout: D,
+    extents: D::Extents,
+    strides: D::Extents,
+    radius: D::Extents,
+    boundary: StencilBoundary<'a, T>,
+    lower: D::Extents,
+    upper: D::Extents,
+    next: Option<D::Extents>,
+}
+
+impl<'a, T, D> Stencil<'a, T, D> where D: Dimensions {
+    fn new(data: &'a [T], layout: D, dims: &D, radius: D::Extents, boundary: StencilBoundary<'a, T>) -> Stencil<'a, T, D> {
+        let extents = layout.extents();
+        let (mut lower, mut upper) = region(&layout, dims);
+        if let StencilBoundary::Interior = boundary {
+            // Only keep the points at least `radius` away from the edges of
+            // the underlying data, neighbors can still be outside of the
+            // region.
+            for axis in 0..lower.as_ref().len() {
+                let radius = radius.as_ref()[axis];
+                let lower = &mut lower.as_mut()[axis];
+                *lower = ::std::cmp::max(*lower, radius);
+                let upper = &mut upper.as_mut()[axis];
+                *upper = ::std::cmp::min(*upper, extents.as_ref()[axis].saturating_sub(radius));
+            }
+        }
+        Stencil {
+            data,
+            strides: layout.strides().expect("windows and stencils require dimensions with strided storage"),
+            extents,
+            layout,
+            radius,
+            boundary,
+            next: first(&lower, &upper),
+            lower,
+            upper,
+        }
+    }
+}
+
+impl<'a, T, D> Iterator for Stencil<'a, T, D> where D: Dimensions {
+    type Item = (D::Index, Neighborhood<'a, T, D>);
+
+    fn next(&mut self) -> Option<(D::Index, Neighborhood<'a, T, D>)> {
+        let center = self.next.take()?;
+        let mut next = center.clone();
+        if advance(next.as_mut(), self.lower.as_ref(), self.upper.as_ref()) {
+            self.next = Some(next);
+        }
+        let index = self.layout.index_from_positions(center.as_ref());
+        let neighborhood = Neighborhood {
+            data: self.data,
+            offset: offset(center.as_ref(), self.strides.as_ref()),
+            center,
+            extents: self.extents.clone(),
+            strides: self.strides.clone(),
+            radius: self.radius.clone(),
+            boundary: self.boundary,
+        };
+        Some((index, neighborhood))
+    }
+}
+
+/// The neighborhood of a point in a [`Stencil`](struct.Stencil.html). The
+/// neighbors are indexed by their offset from the central point along each
+/// axis, up to the stencil radius: `neighborhood[[0, 0]]` is the central
+/// point and `neighborhood[[-1, 0]]` is the previous point along the first
+/// axis.
+pub struct Neighborhood<'a, T: 'a, D> where D: Dimensions {
+    data: &'a [T],
+    offset: usize,
+    center: D::Extents,
+    extents: D::Extents,
+    strides: D::Extents,
+    radius: D::Extents,
+    boundary: StencilBoundary<'a, T>,
+}
+
+impl<'a, T, D> Neighborhood<'a, T, D> where D: Dimensions {
+    /// Get the value at the central point of this neighborhood.
+    pub fn center(&self) -> &'a T {
+        &self.data[self.offset]
+    }
+
+    /// Get the radius of this neighborhood along each axis.
+    pub fn radius(&self) -> D::Extents {
+        self.radius.clone()
+    }
+}
+
+impl<'a, T, D> Index<D::Bounds> for Neighborhood<'a, T, D> where D: Dimensions {
+    type Output = T;
+    fn index(&self, delta: D::Bounds) -> &T {
+        let mut offset = self.offset as isize;
+        let mut outside = false;
+        for (axis, &delta) in delta.as_ref().iter().enumerate() {
+            let radius = self.radius.as_ref()[axis];
+            assert!(delta.unsigned_abs() <= radius, "offset {} is outside of the stencil along axis {}: radius is {}",
+                    delta, axis, radius);
+            let center = self.center.as_ref()[axis] as isize;
+            let extent = self.extents.as_ref()[axis] as isize;
+            let mut position = center + delta;
+            if position < 0 || position >= extent {
+                match self.boundary {
+                    StencilBoundary::Pad(_) => outside = true,
+                    StencilBoundary::Periodic => position = position.rem_euclid(extent),
+                    StencilBoundary::Interior => unreachable!("interior stencils stay inside the array"),
+                }
+            }
+            offset += (position - center) * self.strides.as_ref()[axis] as isize;
+        }
+        match self.boundary {
+            StencilBoundary::Pad(value) if outside => value,
+            _ => &self.data[offset as usize],
+        }
+    }
+}
+
+impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
+    /// Iterate over all the windows with the given `shape` fitting in the
+    /// array, in storage order of their first element. Windows are indexed
+    /// by positions relative to their first element.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_vector(vec![1, 2, 3, 4, 5, 6], (2, -1..2));
+    /// let sums = array.windows([2, 2]).map(|window| window.iter().sum()).collect::<Vec<i32>>();
+    /// assert_eq!(sums, vec![12, 16]);
+    ///
+    /// let window = array.windows([2, 2]).last().unwrap();
+    /// assert_eq!(window.origin(), (0, 0));
+    /// assert_eq!(window[[1, 0]], 5);
+    /// ```
+    ///
+    /// # Panics
+    /// If one of the window extents is zero, or if the dimensions do not
+    /// have strided storage.
+    pub fn windows(&self, shape: D::Extents) -> Windows<'_, S::Item, D> {
+        let dims = self.shape();
+        Windows::new(self.flat_iter().as_slice(), dims.clone(), &dims, shape)
+    }
+
+    /// Iterate over the points of the array, together with a neighborhood
+    /// extending up to `radius` points along each axis. The `boundary` policy
+    /// controls which points are visited and how neighbors outside of the
+    /// array are handled.
+    ///
+    /// ```
+    /// use mudi::{Array, StencilBoundary};
+    /// let array = Array::from_fn((-1..3, 4), |(i, j)| 10 * i + j as i32);
+    ///
+    /// // Points on the edges of the array are skipped
+    /// let laplacian = array.stencil([1, 1], StencilBoundary::Interior).map(|(index, n)| {
+    ///     (index, n[[-1, 0]] + n[[1, 0]] + n[[0, -1]] + n[[0, 1]] - 4 * n[[0, 0]])
+    /// }).collect::<Vec<_>>();
+    /// assert_eq!(laplacian, vec![((0, 1), 0), ((0, 2), 0), ((1, 1), 0), ((1, 2), 0)]);
+    ///
+    /// // Neighbors outside of the array use the padding value
+    /// let (index, neighborhood) = array.stencil([1, 1], StencilBoundary::Pad(&-1)).next().unwrap();
+    /// assert_eq!(index, (-1, 0));
+    /// assert_eq!(neighborhood[[-1, -1]], -1);
+    /// assert_eq!(neighborhood[[1, 1]], 1);
+    ///
+    /// // Or wrap around
+    /// let (_, neighborhood) = array.stencil([1, 1], StencilBoundary::Periodic).next().unwrap();
+    /// assert_eq!(neighborhood[[-1, -1]], 23);
+    /// ```
+    pub fn stencil<'a>(&'a self, radius: D::Extents, boundary: StencilBoundary<'a, S::Item>) -> Stencil<'a, S::Item, D> {
+        let dims = self.shape();
+        Stencil::new(self.flat_iter().as_slice(), dims.clone(), &dims, radius, boundary)
+    }
+}
+
+impl<'a, T, D> ArrayView<'a, T, D> where D: Dimensions {
+    /// Iterate over all the windows with the given `shape` fitting in the
+    /// view. See [`ArrayBase::windows`](struct.ArrayBase.html#method.windows).
+    ///
+    /// # Panics
+    /// If the view wraps around a periodic axis of the array, in addition to
+    /// the cases where `ArrayBase::windows` panics.
+    pub fn windows(&self, shape: D::Extents) -> Windows<'a, T, D> {
+        let (data, layout, dims) = self.raw_parts();
+        Windows::new(data, layout.clone(), dims, shape)
+    }
+
+    /// Iterate over the points of the view, together with their
+    /// neighborhood. See [`ArrayBase::stencil`](struct.ArrayBase.html#method.stencil).
+    ///
+    /// The neighbors are taken from the array the view was created from, and
+    /// can be outside of the view. `StencilBoundary::Interior` only skips the
+    /// points with neighbors outside of the underlying array, which makes it
+    /// possible to use the halo around a view of an array interior.
+    ///
+    /// ```
+    /// use mudi::{Array, StencilBoundary};
+    /// let array = Array::from_fn((-1..3, -1..3), |(i, j)| 10 * i + j);
+    /// let interior = array.subview((0..2, 0..2));
+    /// let points = interior.stencil([1, 1], StencilBoundary::Interior).collect::<Vec<_>>();
+    /// assert_eq!(points.len(), 4);
+    /// assert_eq!(points[0].1[[-1, -1]], -11);
+    /// ```
+    ///
+    /// # Panics
+    /// If the view wraps around a periodic axis of the array.
+    pub fn stencil(&self, radius: D::Extents, boundary: StencilBoundary<'a, T>) -> Stencil<'a, T, D> {
+        let (data, layout, dims) = self.raw_parts();
+        Stencil::new(data, layout.clone(), dims, radius, boundary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {Array, ColumnMajor, Periodic};
+    use super::StencilBoundary;
+
+    #[test]
+    fn windows() {
+        let array = Array::from_fn((3, -1..3), |(i, j)| 10 * i as i32 + j);
+        let windows = array.windows([2, 3]).collect::<Vec<_>>();
+        assert_eq!(windows.len(), 4);
+        assert_eq!(windows[1].origin(), (0, 0));
+        assert_eq!(windows[3].origin(), (1, 0));
+        assert_eq!(windows[3].shape(), [2, 3]);
+        assert_eq!(windows[3].iter().cloned().collect::<Vec<_>>(), vec![10, 11, 12, 20, 21, 22]);
+        assert_eq!(windows[2][[1, 2]], 21);
+
+        assert_eq!(array.windows([1, 1]).count(), 12);
+        assert_eq!(array.windows([3, 4]).count(), 1);
+        assert_eq!(array.windows([4, 1]).count(), 0);
+    }
+
+    #[test]
+    fn windows_view() {
+        let array = Array::from_fn((-1..3, -1..3), |(i, j)| 10 * i + j);
+        let view = array.subview((0..2, 0..3));
+        let windows = view.windows([2, 2]).collect::<Vec<_>>();
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].origin(), (0, 0));
+        assert_eq!(windows[1].iter().cloned().collect::<Vec<_>>(), vec![1, 2, 11, 12]);
+    }
+
+    #[test]
+    fn periodic_view() {
+        let array = Array::from_fn((0..2, Periodic(0..4)), |(i, j)| 10 * i + j);
+        let view = array.subview((0..1, Periodic(5..7)));
+        let window = view.windows([1, 2]).next().unwrap();
+        assert_eq!(window.origin(), (0, 1));
+        assert_eq!(window.iter().cloned().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "the region wraps around axis 1 of the array")]
+    fn wrapping_view() {
+        let array = Array::from_fn((0..2, Periodic(0..4)), |(i, j)| 10 * i + j);
+        let _ = array.subview((0..1, Periodic(3..5))).windows([1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "position 2 is outside of the window along axis 0: extent is 2")]
+    fn window_out_of_bounds() {
+        let array = Array::from_element(0, (3, 3));
+        let window = array.windows([2, 2]).next().unwrap();
+        let _ = window[[2, 0]];
+    }
+
+    #[test]
+    #[should_panic(expected = "window extents must be non-zero")]
+    fn empty_window() {
+        let array = Array::from_element(0, (3, 3));
+        let _ = array.windows([0, 2]);
+    }
+
+    #[test]
+    fn stencil_3d() {
+        let array = Array::from_fn((4, 5, -1..5), |(i, j, k)| 100 * i as i32 + 10 * j as i32 + k);
+        let mut count = 0;
+        for (index, neighborhood) in array.stencil([1, 1, 1], StencilBoundary::Interior) {
+            let (i, j, k) = index;
+            assert_eq!(*neighborhood.center(), array[index]);
+            for di in -1..2 {
+                for dj in -1..2 {
+                    for dk in -1..2 {
+                        let neighbor = ((i as isize + di) as usize, (j as isize + dj) as usize, k + dk as i32);
+                        assert_eq!(neighborhood[[di, dj, dk]], array[neighbor]);
+                    }
+                }
+            }
+            count += 1;
+        }
+        assert_eq!(count, 2 * 3 * 4);
+
+        assert_eq!(array.stencil([2, 3, 0], StencilBoundary::Interior).count(), 0);
+        assert_eq!(array.stencil([0, 0, 0], StencilBoundary::Interior).count(), 4 * 5 * 6);
+    }
+
+    #[test]
+    fn stencil_boundaries() {
+        let array = Array::from_vector(vec![1, 2, 3, 4], -2_i32..2);
+
+        let sums = array.stencil([1], StencilBoundary::Pad(&0)).map(|(_, n)| n[[-1]] + n[[0]] + n[[1]]);
+        assert_eq!(sums.collect::<Vec<_>>(), vec![3, 6, 9, 7]);
+
+        let sums = array.stencil([2], StencilBoundary::Periodic).map(|(_, n)| n[[-2]] + n[[2]]);
+        assert_eq!(sums.collect::<Vec<_>>(), vec![6, 8, 2, 4]);
+
+        let periodic = Array::from_vector(vec![1, 2, 3, 4], Periodic(0_i32..4));
+        let indexes = periodic.stencil([1], StencilBoundary::Interior).map(|(index, _)| index);
+        assert_eq!(indexes.collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn column_major() {
+        let array = Array::from_fn(ColumnMajor((3, -1_i32..3)), |(i, j)| 10 * i as i32 + j);
+        let window = array.windows([2, 3]).last().unwrap();
+        assert_eq!(window.iter().cloned().collect::<Vec<_>>(), vec![10, 11, 12, 20, 21, 22]);
+
+        for ((i, j), neighborhood) in array.stencil([1, 1], StencilBoundary::Periodic) {
+            assert_eq!(neighborhood[[1, 0]], array[((i + 1) % 3, j)]);
+            assert_eq!(neighborhood[[0, -1]], array[(i, if j == -1 { 2 } else { j - 1 })]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "offset -2 is outside of the stencil along axis 1: radius is 1")]
+    fn outside_radius() {
+        let array = Array::from_element(0, (3, 3));
+        let (_, neighborhood) = array.stencil([1, 1], StencilBoundary::Pad(&0)).next().unwrap();
+        let _ = neighborhood[[0, -2]];
+    }
+}
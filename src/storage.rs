@@ -1,3 +1,9 @@
+use std::alloc::{self, Layout};
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ptr::{self, NonNull};
+use std::slice;
 
 /// Storage of data from an array
 pub trait Storage {
@@ -9,6 +15,34 @@ pub trait Storage {
     fn as_ref(&self) -> &[Self::Item];
     /// Convert the storage to a mutable slice
     fn as_mut(&mut self) -> &mut [Self::Item];
+
+    /// Create the storage with `size` elements, taken in order from the
+    /// iterator.
+    ///
+    /// # Panics
+    /// If the iterator yields less than `size` elements.
+    fn from_iter<I>(size: usize, iter: I) -> Self
+        where I: Iterator<Item = Self::Item>, Self: Sized {
+        match Self::try_from_iter(size, iter) {
+            Ok(storage) => storage,
+            Err(_) => panic!("Iterator length does not match the storage size"),
+        }
+    }
+
+    /// Create the storage with `size` elements, taken in order from the
+    /// iterator, or get the number of elements in the iterator if it yields
+    /// less than `size` elements. Storages which can not reuse a `Vec`
+    /// allocation should override this to write the elements directly in
+    /// place.
+    fn try_from_iter<I>(size: usize, iter: I) -> Result<Self, usize>
+        where I: Iterator<Item = Self::Item>, Self: Sized {
+        let vector = iter.take(size).collect::<Vec<_>>();
+        if vector.len() == size {
+            Ok(Self::from_vec(vector))
+        } else {
+            Err(vector.len())
+        }
+    }
 }
 
 impl<T> Storage for Box<[T]> {
@@ -18,6 +52,7 @@ impl<T> Storage for Box<[T]> {
         vector.into_boxed_slice()
     }
 
+    #[allow(clippy::needless_borrow)]
     fn as_ref(&self) -> &[T] {
         &self
     }
@@ -26,3 +61,209 @@ impl<T> Storage for Box<[T]> {
         &mut *self
     }
 }
+
+/// Storage with the first element aligned on `ALIGN` bytes, for use with
+/// SIMD kernels. `ALIGN` must be a power of two, typically 32 or 64 for
+/// vector registers, or 4096 for page alignment. The alignment is never lower
+/// than the natural alignment of `T`.
+///
+/// Only the start of the buffer is aligned. For every innermost lane of an
+/// array to be aligned, the size of the last dimension must be a multiple of
+/// `ALIGN / size_of::<T>()`.
+///
+/// A `Vec` can not be re-aligned, so `Storage::from_vec` copies the data in
+/// a new buffer. Prefer the constructors going through `Storage::from_iter`,
+/// such as `ArrayBase::from_element`, which write the elements in place.
+///
+/// ```
+/// use mudi::AlignedArray;
+/// let array = AlignedArray::<f32, _, 64>::from_element(0.0, (4, 16));
+/// assert_eq!(array.flat_iter().as_slice().as_ptr() as usize % 64, 0);
+/// ```
+pub struct AlignedStorage<T, const ALIGN: usize> {
+    ptr: NonNull<T>,
+    /// Number of initialized elements
+    len: usize,
+    /// Number of allocated elements
+    capacity: usize,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Send, const ALIGN: usize> Send for AlignedStorage<T, ALIGN> {}
+unsafe impl<T: Sync, const ALIGN: usize> Sync for AlignedStorage<T, ALIGN> {}
+
+impl<T, const ALIGN: usize> AlignedStorage<T, ALIGN> {
+    fn layout(capacity: usize) -> Layout {
+        let size = capacity.checked_mul(std::mem::size_of::<T>())
+                           .expect("Aligned storage size overflows usize");
+        let align = std::cmp::max(ALIGN, std::mem::align_of::<T>());
+        Layout::from_size_align(size, align).expect("Invalid aligned storage layout")
+    }
+
+    /// Allocate space for `capacity` elements, without initializing them.
+    fn allocate(capacity: usize) -> AlignedStorage<T, ALIGN> {
+        const { assert!(ALIGN.is_power_of_two(), "ALIGN must be a power of two") };
+        let layout = Self::layout(capacity);
+        let ptr = if layout.size() == 0 {
+            // Zero-sized allocations are not allowed, use a dangling but
+            // correctly aligned pointer instead.
+            NonNull::new(layout.align() as *mut T).expect("alignment is not zero")
+        } else {
+            let ptr = unsafe { alloc::alloc(layout) };
+            match NonNull::new(ptr as *mut T) {
+                Some(ptr) => ptr,
+                None => alloc::handle_alloc_error(layout),
+            }
+        };
+        AlignedStorage {
+            ptr,
+            len: 0,
+            capacity,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, const ALIGN: usize> AlignedStorage<MaybeUninit<T>, ALIGN> {
+    /// Allocate storage for `len` uninitialized elements.
+    pub(crate) fn uninit(len: usize) -> AlignedStorage<MaybeUninit<T>, ALIGN> {
+        let mut storage = AlignedStorage::allocate(len);
+        // `MaybeUninit` elements do not need to be initialized
+        storage.len = len;
+        storage
+    }
+
+    /// Convert the storage to `AlignedStorage<T, ALIGN>`, without moving the
+    /// data.
+    ///
+    /// # Safety
+    /// All the elements must have been initialized.
+    pub unsafe fn assume_init(self) -> AlignedStorage<T, ALIGN> {
+        let storage = ManuallyDrop::new(self);
+        AlignedStorage {
+            ptr: storage.ptr.cast(),
+            len: storage.len,
+            capacity: storage.capacity,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T, const ALIGN: usize> Storage for AlignedStorage<T, ALIGN> {
+    type Item = T;
+
+    fn from_vec(vector: Vec<T>) -> AlignedStorage<T, ALIGN> {
+        let size = vector.len();
+        Self::from_iter(size, vector.into_iter())
+    }
+
+    fn try_from_iter<I>(size: usize, iter: I) -> Result<AlignedStorage<T, ALIGN>, usize>
+        where I: Iterator<Item = T> {
+        let mut storage = AlignedStorage::<T, ALIGN>::allocate(size);
+        for value in iter.take(size) {
+            unsafe {
+                storage.ptr.as_ptr().add(storage.len).write(value);
+            }
+            // Only count the element once it is written, so that a panic in
+            // the iterator drops exactly the initialized elements.
+            storage.len += 1;
+        }
+        if storage.len == size {
+            Ok(storage)
+        } else {
+            Err(storage.len)
+        }
+    }
+
+    fn as_ref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    fn as_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const ALIGN: usize> Drop for AlignedStorage<T, ALIGN> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(self.as_mut());
+            let layout = Self::layout(self.capacity);
+            if layout.size() != 0 {
+                alloc::dealloc(self.ptr.as_ptr() as *mut u8, layout);
+            }
+        }
+    }
+}
+
+impl<T: Clone, const ALIGN: usize> Clone for AlignedStorage<T, ALIGN> {
+    fn clone(&self) -> AlignedStorage<T, ALIGN> {
+        AlignedStorage::from_iter(self.len, self.as_ref().iter().cloned())
+    }
+}
+
+impl<T: fmt::Debug, const ALIGN: usize> fmt::Debug for AlignedStorage<T, ALIGN> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.as_ref()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn is_aligned<T>(slice: &[T], align: usize) -> bool {
+        (slice.as_ptr() as usize) % align == 0
+    }
+
+    #[test]
+    fn alignment() {
+        let storage = AlignedStorage::<u8, 32>::from_vec(vec![1, 2, 3]);
+        assert!(is_aligned(storage.as_ref(), 32));
+        assert_eq!(storage.as_ref(), &[1, 2, 3]);
+
+        let storage = AlignedStorage::<f64, 64>::from_iter(13, std::iter::repeat(4.0));
+        assert!(is_aligned(storage.as_ref(), 64));
+        assert_eq!(storage.as_ref().len(), 13);
+
+        let storage = AlignedStorage::<usize, 4096>::from_iter(1000, 0..);
+        assert!(is_aligned(storage.as_ref(), 4096));
+        assert_eq!(storage.as_ref()[999], 999);
+    }
+
+    #[test]
+    fn empty() {
+        let mut storage = AlignedStorage::<u64, 64>::from_vec(Vec::new());
+        assert!(is_aligned(storage.as_ref(), 64));
+        assert!(storage.as_mut().is_empty());
+
+        let storage = AlignedStorage::<(), 64>::from_iter(10, std::iter::repeat(()));
+        assert_eq!(storage.as_ref().len(), 10);
+    }
+
+    #[test]
+    fn drop_elements() {
+        let rc = Rc::new(0);
+        let storage = AlignedStorage::<_, 32>::from_iter(5, std::iter::repeat(rc.clone()));
+        assert_eq!(Rc::strong_count(&rc), 6);
+        drop(storage);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn clone() {
+        let rc = Rc::new(0);
+        let storage = AlignedStorage::<_, 128>::from_iter(3, std::iter::repeat(rc.clone()));
+        let copy = storage.clone();
+        assert!(is_aligned(copy.as_ref(), 128));
+        assert_eq!(copy.as_ref().len(), 3);
+        assert_eq!(Rc::strong_count(&rc), 7);
+    }
+
+    #[test]
+    #[should_panic(expected = "Iterator length does not match the storage size")]
+    fn short_iterator() {
+        let _ = AlignedStorage::<u8, 32>::from_iter(5, 0..3);
+    }
+}
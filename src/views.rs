@@ -0,0 +1,430 @@
+use std::marker::PhantomData;
+use std::ops::{Index, IndexMut};
+
+use Dimensions;
+use Array;
+
+/// Check that `dims` is not larger than `layout` along any axis, and that
+/// the first and last indexes of `dims` are also valid indexes for `layout`.
+/// Periodic axes of the layout accept any index, and can not contain the
+/// same element twice with a smaller extent.
+fn check_region<D: Dimensions>(layout: &D, dims: &D) {
+    assert_eq!(dims.rank(), layout.rank(), "the region and the layout have a different rank");
+    for axis in 0..dims.rank() {
+        assert!(dims.extent(axis) <= layout.extent(axis), "the region contains {} elements along axis {}, but the layout only {}",
+                dims.extent(axis), axis, layout.extent(axis));
+    }
+    if dims.size() != 0 {
+        let mut positions = dims.extents();
+        for position in positions.as_mut() {
+            *position = 0;
+        }
+        layout.offset(dims.index_from_positions(positions.as_ref()));
+        for (position, extent) in positions.as_mut().iter_mut().zip(dims.extents().as_ref()) {
+            *position = extent - 1;
+        }
+        layout.offset(dims.index_from_positions(positions.as_ref()));
+    }
+}
+
+/// Get the positions in `layout` of the first element of the `dims` region,
+/// checking that the region is stored as a single block of the layout data.
+///
+/// # Panics
+/// If the region wraps around a periodic axis of the layout.
+pub(crate) fn region_origin<D: Dimensions>(layout: &D, dims: &D) -> D::Extents {
+    if dims.size() == 0 {
+        let mut origin = dims.extents();
+        for position in origin.as_mut() {
+            *position = 0;
+        }
+        return origin;
+    }
+    let origin = layout.positions(dims.index(0));
+    for (axis, (&position, &extent)) in origin.as_ref().iter().zip(dims.extents().as_ref()).enumerate() {
+        assert!(position + extent <= layout.extent(axis),
+                "the region wraps around axis {} of the array, and is not stored as a single block", axis);
+    }
+    origin
+}
+
+/// Get the offset in the `layout` data of `index` in the `dims` region.
+/// Indexes along the periodic axes of the region wrap around the region, and
+/// not around the whole layout.
+fn region_offset<D: Dimensions>(layout: &D, dims: &D, index: D::Index) -> usize {
+    layout.offset(dims.index_from_positions(dims.positions(index).as_ref()))
+}
+
+/// A view into a region of an array. The view uses the same indexes as the
+/// array it was created from: a view of the `(0..4, 0..4)` region of a
+/// `(-2..6, -2..6)` array is indexed with `(0, 0)` to `(3, 3)`. Indexes along
+/// periodic axes wrap around the view, like for an array with the view shape.
+///
+/// ```
+/// use mudi::Array;
+/// let array = Array::from_fn((-2..6, -2..6), |(i, j)| 10 * i + j);
+/// let view = array.subview((0..4, 0..4));
+/// assert_eq!(view[(0, 0)], 0);
+/// assert_eq!(view[(3, 1)], 31);
+/// assert_eq!(view.iter().count(), 16);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArrayView<'a, T: 'a, D> where D: Dimensions {
+    data: &'a [T],
+    layout: D,
+    dims: D,
+}
+
+impl<'a, T, D> ArrayView<'a, T, D> where D: Dimensions {
+    /// Create a view of the `dims` region, in data stored with the given
+    /// `layout`.
+    pub(crate) fn new(data: &'a [T], layout: D, dims: D) -> ArrayView<'a, T, D> {
+        debug_assert_eq!(data.len(), layout.size());
+        check_region(&layout, &dims);
+        ArrayView {
+            data,
+            layout,
+            dims,
+        }
+    }
+
+    /// Get the shape of the viewed region.
+    pub fn shape(&self) -> D {
+        self.dims.clone()
+    }
+
+    /// Get a view of a smaller region of this view.
+    ///
+    /// # Panics
+    /// If `dims` is not contained in the region of this view.
+    pub fn subview(&self, dims: D) -> ArrayView<'a, T, D> {
+        check_region(&self.dims, &dims);
+        ArrayView::new(self.data, self.layout.clone(), dims)
+    }
+
+    /// Iterate over the elements in the view, in the same order as
+    /// `ArrayBase::flat_iter` would for an array with the view shape.
+    pub fn iter(&self) -> ViewIter<'a, T, D> {
+        ViewIter {
+            data: self.data,
+            layout: self.layout.clone(),
+            dims: self.dims.clone(),
+            offset: 0,
+        }
+    }
+
+    /// Copy the viewed region in a new array.
+    pub fn to_owned(&self) -> Array<T, D> where T: Clone {
+        Array::from_shape_iter(self.dims.clone(), self.iter().cloned())
+    }
+
+    /// Get the underlying data, the layout of this data and the viewed region
+    pub(crate) fn raw_parts(&self) -> (&'a [T], &D, &D) {
+        (self.data, &self.layout, &self.dims)
+    }
+}
+
+impl<'a, T, D, I: Copy> Index<I> for ArrayView<'a, T, D> where D: Dimensions<Index = I> {
+    type Output = T;
+    fn index(&self, index: I) -> &T {
+        &self.data[region_offset(&self.layout, &self.dims, index)]
+    }
+}
+
+/// A mutable view into a region of an array. See
+/// [`ArrayView`](struct.ArrayView.html) for more information.
+#[derive(Debug)]
+pub struct ArrayViewMut<'a, T: 'a, D> where D: Dimensions {
+    data: &'a mut [T],
+    layout: D,
+    dims: D,
+}
+
+impl<'a, T, D> ArrayViewMut<'a, T, D> where D: Dimensions {
+    /// Create a mutable view of the `dims` region, in data stored with the
+    /// given `layout`.
+    pub(crate) fn new(data: &'a mut [T], layout: D, dims: D) -> ArrayViewMut<'a, T, D> {
+        debug_assert_eq!(data.len(), layout.size());
+        check_region(&layout, &dims);
+        ArrayViewMut {
+            data,
+            layout,
+            dims,
+        }
+    }
+
+    /// Get the shape of the viewed region.
+    pub fn shape(&self) -> D {
+        self.dims.clone()
+    }
+
+    /// Get a non mutable view of the same region.
+    pub fn view(&self) -> ArrayView<'_, T, D> {
+        ArrayView::new(self.data, self.layout.clone(), self.dims.clone())
+    }
+
+    /// Get a mutable view of a smaller region of this view.
+    ///
+    /// # Panics
+    /// If `dims` is not contained in the region of this view.
+    pub fn subview_mut(&mut self, dims: D) -> ArrayViewMut<'_, T, D> {
+        check_region(&self.dims, &dims);
+        ArrayViewMut::new(self.data, self.layout.clone(), dims)
+    }
+
+    /// Iterate over the elements in the view. See
+    /// [`ArrayView::iter`](struct.ArrayView.html#method.iter).
+    pub fn iter(&self) -> ViewIter<'_, T, D> {
+        self.view().iter()
+    }
+
+    /// Iterate over mutable elements in the view. See
+    /// [`ArrayView::iter`](struct.ArrayView.html#method.iter).
+    pub fn iter_mut(&mut self) -> ViewIterMut<'_, T, D> {
+        ViewIterMut::new(self.data, self.layout.clone(), self.dims.clone())
+    }
+
+    /// Set all the elements in the view to `value`.
+    pub fn fill(&mut self, value: T) where T: Clone {
+        for element in self.iter_mut() {
+            *element = value.clone();
+        }
+    }
+
+    /// Get the underlying mutable data, the layout of this data and the
+    /// viewed region
+    pub(crate) fn raw_parts_mut(&mut self) -> (&mut [T], &D, &D) {
+        (self.data, &self.layout, &self.dims)
+    }
+
+    /// Get the underlying mutable data for the whole view lifetime, the
+    /// layout of this data and the viewed region
+    #[cfg(any(feature = "ndarray", feature = "nalgebra"))]
+    pub(crate) fn into_raw_parts(self) -> (&'a mut [T], D, D) {
+        (self.data, self.layout, self.dims)
+    }
+}
+
+impl<'a, T, D> IntoIterator for ArrayView<'a, T, D> where D: Dimensions {
+    type Item = &'a T;
+    type IntoIter = ViewIter<'a, T, D>;
+
+    fn into_iter(self) -> ViewIter<'a, T, D> {
+        self.iter()
+    }
+}
+
+impl<'a, T, D> IntoIterator for ArrayViewMut<'a, T, D> where D: Dimensions {
+    type Item = &'a mut T;
+    type IntoIter = ViewIterMut<'a, T, D>;
+
+    fn into_iter(self) -> ViewIterMut<'a, T, D> {
+        ViewIterMut::new(self.data, self.layout, self.dims)
+    }
+}
+
+impl<'a, T, D, I: Copy> Index<I> for ArrayViewMut<'a, T, D> where D: Dimensions<Index = I> {
+    type Output = T;
+    fn index(&self, index: I) -> &T {
+        &self.data[region_offset(&self.layout, &self.dims, index)]
+    }
+}
+
+impl<'a, T, D, I: Copy> IndexMut<I> for ArrayViewMut<'a, T, D> where D: Dimensions<Index = I> {
+    fn index_mut(&mut self, index: I) -> &mut T {
+        &mut self.data[region_offset(&self.layout, &self.dims, index)]
+    }
+}
+
+/// Iterator over the elements of an `ArrayView`.
+pub struct ViewIter<'a, T: 'a, D> where D: Dimensions {
+    data: &'a [T],
+    layout: D,
+    dims: D,
+    offset: usize,
+}
+
+impl<'a, T, D> Iterator for ViewIter<'a, T, D> where D: Dimensions {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.offset < self.dims.size() {
+            let index = self.dims.index(self.offset);
+            self.offset += 1;
+            Some(&self.data[self.layout.offset(index)])
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.dims.size() - self.offset;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T, D> ExactSizeIterator for ViewIter<'a, T, D> where D: Dimensions {}
+
+/// Iterator over the mutable elements of an `ArrayViewMut`.
+pub struct ViewIterMut<'a, T: 'a, D> where D: Dimensions {
+    data: *mut T,
+    len: usize,
+    layout: D,
+    dims: D,
+    offset: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T, D> ViewIterMut<'a, T, D> where D: Dimensions {
+    fn new(data: &'a mut [T], layout: D, dims: D) -> ViewIterMut<'a, T, D> {
+        ViewIterMut {
+            len: data.len(),
+            data: data.as_mut_ptr(),
+            layout,
+            dims,
+            offset: 0,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T, D> Iterator for ViewIterMut<'a, T, D> where D: Dimensions {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        if self.offset < self.dims.size() {
+            let index = self.dims.index(self.offset);
+            self.offset += 1;
+            let offset = self.layout.offset(index);
+            assert!(offset < self.len, "offset out of bound: size is {} but offset is {}", self.len, offset);
+            // SAFETY: `offset` is inside of the mutably borrowed data. The
+            // indexes of the region are all different, and the layout gives
+            // different offsets to different indexes: `check_region` ensured
+            // that the region is not longer than the layout along periodic
+            // axes, so that no two indexes wrap around to the same element.
+            // Each offset is thus returned at most once, and the references
+            // never alias.
+            unsafe {
+                Some(&mut *self.data.add(offset))
+            }
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.dims.size() - self.offset;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T, D> ExactSizeIterator for ViewIterMut<'a, T, D> where D: Dimensions {}
+
+#[cfg(test)]
+mod tests {
+    use {Array, Periodic};
+
+    #[test]
+    fn view() {
+        let a = Array::from_fn((3, -2..2), |(i, j)| (i, j));
+        let view = a.view();
+        assert_eq!(view.shape(), (3, -2..2));
+        assert_eq!(view[(2, -1)], (2, -1));
+        assert!(view.iter().eq(a.flat_iter()));
+    }
+
+    #[test]
+    fn subview() {
+        let a = Array::from_fn((-1..4, -1..4), |(i, j)| 10 * i + j);
+        let view = a.subview((0..3, 1..3));
+        assert_eq!(view[(2, 1)], 21);
+        let values = view.iter().cloned().collect::<Vec<_>>();
+        assert_eq!(values, vec![1, 2, 11, 12, 21, 22]);
+
+        let view = view.subview((1..2, 1..3));
+        assert_eq!(view.to_owned(), Array::from_vector(vec![11, 12], (1..2, 1..3)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_outside_view() {
+        let a = Array::from_element(0, (-1..4, -1..4));
+        let view = a.subview((0..3, 0..3));
+        let _ = view[(-1, 0)];
+    }
+
+    #[test]
+    #[should_panic]
+    fn subview_outside_array() {
+        let a = Array::from_element(0, (0..4, 0..4));
+        let _ = a.subview((2..5, 0..3));
+    }
+
+    #[test]
+    fn view_mut() {
+        let mut a = Array::from_element(0, (-1..3, -1..3));
+        {
+            let mut view = a.subview_mut((0..2, 0..2));
+            view.fill(1);
+            view[(1, 1)] = 2;
+            for value in view.subview_mut((0..1, 0..2)).iter_mut() {
+                *value += 10;
+            }
+        }
+        assert_eq!(a[(-1, -1)], 0);
+        assert_eq!(a[(0, 0)], 11);
+        assert_eq!(a[(0, 1)], 11);
+        assert_eq!(a[(1, 0)], 1);
+        assert_eq!(a[(1, 1)], 2);
+        assert_eq!(a[(2, 2)], 0);
+        assert_eq!(a.flat_iter().sum::<i32>(), 25);
+    }
+
+    #[test]
+    #[should_panic(expected = "the region contains 8 elements along axis 0, but the layout only 4")]
+    fn larger_periodic_region() {
+        let mut a = Array::from_element(0, Periodic(0..4));
+        let _ = a.subview_mut(Periodic(0..8));
+    }
+
+    #[test]
+    #[should_panic(expected = "the region contains 8 elements along axis 1, but the layout only 4")]
+    fn longer_periodic_axis() {
+        let mut a = Array::from_element(0, (0..2, Periodic(0..4)));
+        let _ = a.subview_mut((0..1, Periodic(0..8)));
+    }
+
+    #[test]
+    fn shifted_periodic_region() {
+        let mut a = Array::from_element(0, Periodic(0..4));
+        for value in a.subview_mut(Periodic(2..6)) {
+            *value += 1;
+        }
+        assert!(a.flat_iter().all(|&value| value == 1));
+    }
+
+    #[test]
+    fn index_periodic_view() {
+        let mut a = Array::from_fn(Periodic(0..4), |i| i);
+        {
+            let view = a.subview(Periodic(0..2));
+            assert_eq!(view.iter().cloned().collect::<Vec<_>>(), vec![0, 1]);
+            assert_eq!(view[2], 0);
+            assert_eq!(view[-1], 1);
+        }
+        let mut view = a.subview_mut(Periodic(1..3));
+        assert_eq!(view[3], 1);
+        view[4] = 10;
+        assert_eq!(a.flat_iter().cloned().collect::<Vec<_>>(), vec![0, 1, 10, 3]);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut a = Array::from_element(0, (-1..3, -1..3));
+        for value in a.subview_mut((0..2, 1..3)) {
+            *value = 1;
+        }
+        let view = a.subview((0..3, 0..3));
+        assert_eq!(view.into_iter().cloned().collect::<Vec<_>>(), vec![0, 1, 1, 0, 1, 1, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,339 @@
+use std::slice::{Iter, IterMut};
+
+use {Array, ArrayBase, ArrayView, ArrayViewMut, Dimensions, Storage};
+use views::{ViewIter, ViewIterMut};
+
+impl<S, D> ArrayBase<S, D> where D: Dimensions, S: Storage {
+    /// Create a new array with the same dimensions, by calling `function` on
+    /// a reference to each element.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_vector(vec![1, 2, 3, 4], (2, -1..1));
+    /// let strings = array.map(|value| value.to_string());
+    /// assert_eq!(strings[(1, -1)], "3");
+    /// ```
+    pub fn map<U, F>(&self, function: F) -> Array<U, D> where F: FnMut(&S::Item) -> U {
+        Array::from_shape_iter(self.shape(), self.flat_iter().map(function))
+    }
+
+    /// Create a new array with the same dimensions, by calling `function` on
+    /// a copy of each element.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let array = Array::from_vector(vec![1, 2, 3, 4], (2, -1..1));
+    /// assert_eq!(array.mapv(|value| value * 2), Array::from_vector(vec![2, 4, 6, 8], (2, -1..1)));
+    /// ```
+    pub fn mapv<U, F>(&self, mut function: F) -> Array<U, D> where F: FnMut(S::Item) -> U, S::Item: Clone {
+        Array::from_shape_iter(self.shape(), self.flat_iter().map(|value| function(value.clone())))
+    }
+
+    /// Modify all the elements of the array in place, by calling `function`
+    /// on a mutable reference to each element.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let mut array = Array::from_vector(vec![1, 2, 3, 4], 4);
+    /// array.map_inplace(|value| *value *= *value);
+    /// assert_eq!(array, Array::from_vector(vec![1, 4, 9, 16], 4));
+    /// ```
+    pub fn map_inplace<F>(&mut self, function: F) where F: FnMut(&mut S::Item) {
+        self.flat_iter_mut().for_each(function);
+    }
+
+    /// Create a new array by calling `function` on each pair of elements of
+    /// this array and `other`. Use [`Zip`](struct.Zip.html) to work with
+    /// more than two arrays.
+    ///
+    /// ```
+    /// use mudi::Array;
+    /// let a = Array::from_vector(vec![1, 2, 3], 1..=3);
+    /// let b = Array::from_vector(vec![0.5, 1.0, 1.5], 1..=3);
+    /// let c = a.zip_with(&b, |&a, &b| a as f64 * b);
+    /// assert_eq!(c, Array::from_vector(vec![0.5, 2.0, 4.5], 1..=3));
+    /// ```
+    ///
+    /// # Panics
+    /// If the dimensions of the arrays do not match.
+    pub fn zip_with<R, U, F>(&self, other: &ArrayBase<R, D>, mut function: F) -> Array<U, D>
+        where R: Storage, F: FnMut(&S::Item, &R::Item) -> U {
+        assert!(self.shape() == other.shape(), "dimensions mismatch in zip_with");
+        let values = self.flat_iter().zip(other.flat_iter()).map(|(a, b)| function(a, b));
+        Array::from_shape_iter(self.shape(), values)
+    }
+}
+
+/// Arrays and views which can be walked in lockstep by a
+/// [`Zip`](struct.Zip.html).
+pub trait ZipProducer {
+    /// The dimensions of the producer
+    type Dims: Dimensions;
+    /// The type of the produced elements
+    type Item;
+    /// Iterator over the elements of the producer
+    type Iter: Iterator<Item = Self::Item>;
+    /// Get the dimensions of this producer, and an iterator over its
+    /// elements in storage order.
+    fn into_producer(self) -> (Self::Dims, Self::Iter);
+}
+
+impl<'a, S, D> ZipProducer for &'a ArrayBase<S, D> where D: Dimensions, S: Storage {
+    type Dims = D;
+    type Item = &'a S::Item;
+    type Iter = Iter<'a, S::Item>;
+
+    fn into_producer(self) -> (D, Self::Iter) {
+        (self.shape(), self.flat_iter())
+    }
+}
+
+impl<'a, S, D> ZipProducer for &'a mut ArrayBase<S, D> where D: Dimensions, S: Storage {
+    type Dims = D;
+    type Item = &'a mut S::Item;
+    type Iter = IterMut<'a, S::Item>;
+
+    fn into_producer(self) -> (D, Self::Iter) {
+        (self.shape(), self.flat_iter_mut())
+    }
+}
+
+impl<'a, T, D> ZipProducer for ArrayView<'a, T, D> where D: Dimensions {
+    type Dims = D;
+    type Item = &'a T;
+    type Iter = ViewIter<'a, T, D>;
+
+    fn into_producer(self) -> (D, Self::Iter) {
+        (self.shape(), self.iter())
+    }
+}
+
+impl<'a, T, D> ZipProducer for ArrayViewMut<'a, T, D> where D: Dimensions {
+    type Dims = D;
+    type Item = &'a mut T;
+    type Iter = ViewIterMut<'a, T, D>;
+
+    fn into_producer(self) -> (D, Self::Iter) {
+        (self.shape(), self.into_iter())
+    }
+}
+
+/// Walk up to six arrays or views with the same dimensions in lockstep.
+///
+/// Producers are added with `Zip::from` and `Zip::and`, and can be
+/// references to arrays (`&array` or `&mut array`) or views. The elements
+/// are visited in storage order with `Zip::apply`, or used to create a new
+/// array with `Zip::map_collect`. Calling `Zip::indexed` also gives the
+/// index of the elements to the closures.
+///
+/// ```
+/// use mudi::{Array, Zip};
+/// let mut a = Array::from_element(0.0, (2, -1..1));
+/// let b = Array::from_vector(vec![1.0, 2.0, 3.0, 4.0], (2, -1..1));
+/// let c = Array::from_element(10.0, (2, -1..1));
+///
+/// Zip::from(&mut a).and(&b).and(&c).apply(|a, &b, &c| *a = b * c);
+/// assert_eq!(a[(1, 0)], 40.0);
+///
+/// let sum = Zip::from(&a).and(&b).map_collect(|a, b| a + b);
+/// assert_eq!(sum[(0, -1)], 11.0);
+///
+/// Zip::from(&mut a).indexed().apply(|(i, j), a| *a = i as f64 + j as f64);
+/// assert_eq!(a[(1, -1)], 0.0);
+/// ```
+///
+/// # Panics
+/// When adding a producer with different dimensions.
+pub struct Zip<P, D> {
+    dims: D,
+    producers: P,
+}
+
+/// A [`Zip`](struct.Zip.html) giving the index of the elements to the
+/// closures, created by `Zip::indexed`.
+pub struct IndexedZip<P, D> {
+    dims: D,
+    producers: P,
+}
+
+impl<D> Zip<(), D> where D: Dimensions {
+    /// Create a new `Zip` walking a single producer.
+    pub fn from<P>(producer: P) -> Zip<(P::Iter,), D> where P: ZipProducer<Dims = D> {
+        let (dims, iter) = producer.into_producer();
+        Zip {
+            dims,
+            producers: (iter,),
+        }
+    }
+}
+
+/// Check that the dimensions of a new producer match the `Zip` dimensions
+fn check_dims<D: Dimensions>(expected: &D, dims: &D) {
+    assert!(expected == dims, "all the producers in a Zip must have the same dimensions");
+}
+
+macro_rules! zip_impl {
+    ($($P: ident $p: ident),+) => {
+        impl<D, $($P),+> Zip<($($P,)+), D> where D: Dimensions, $($P: Iterator),+ {
+            /// Get an indexed version of this `Zip`, giving the index of the
+            /// elements as the first argument of the closures.
+            pub fn indexed(self) -> IndexedZip<($($P,)+), D> {
+                IndexedZip {
+                    dims: self.dims,
+                    producers: self.producers,
+                }
+            }
+
+            /// Call `function` on all the elements of the producers, in
+            /// storage order.
+            pub fn apply<F>(self, mut function: F) where F: FnMut($($P::Item),+) {
+                let ($(mut $p,)+) = self.producers;
+                for _ in 0..self.dims.size() {
+                    function($($p.next().expect("producers have the same size")),+);
+                }
+            }
+
+            /// Create a new array from the result of calling `function` on
+            /// all the elements of the producers.
+            pub fn map_collect<U, F>(self, mut function: F) -> Array<U, D> where F: FnMut($($P::Item),+) -> U {
+                let ($(mut $p,)+) = self.producers;
+                let values = (0..self.dims.size()).map(|_| {
+                    function($($p.next().expect("producers have the same size")),+)
+                });
+                Array::from_shape_iter(self.dims.clone(), values)
+            }
+        }
+
+        impl<D, $($P),+> IndexedZip<($($P,)+), D> where D: Dimensions, $($P: Iterator),+ {
+            /// Call `function` on the index and all the elements of the
+            /// producers, in storage order.
+            pub fn apply<F>(self, mut function: F) where F: FnMut(D::Index, $($P::Item),+) {
+                let ($(mut $p,)+) = self.producers;
+                for offset in 0..self.dims.size() {
+                    function(self.dims.index(offset), $($p.next().expect("producers have the same size")),+);
+                }
+            }
+
+            /// Create a new array from the result of calling `function` on
+            /// the index and all the elements of the producers.
+            pub fn map_collect<U, F>(self, mut function: F) -> Array<U, D> where F: FnMut(D::Index, $($P::Item),+) -> U {
+                let ($(mut $p,)+) = self.producers;
+                let dims = &self.dims;
+                let values = (0..dims.size()).map(|offset| {
+                    function(dims.index(offset), $($p.next().expect("producers have the same size")),+)
+                });
+                Array::from_shape_iter(self.dims.clone(), values)
+            }
+        }
+    };
+}
+
+macro_rules! zip_and {
+    ($($P: ident $p: ident),+) => {
+        impl<D, $($P),+> Zip<($($P,)+), D> where D: Dimensions, $($P: Iterator),+ {
+            /// Add a new producer to this `Zip`.
+            ///
+            /// # Panics
+            /// If the producer dimensions do not match the `Zip` dimensions.
+            pub fn and<Q>(self, producer: Q) -> Zip<($($P,)+ Q::Iter,), D> where Q: ZipProducer<Dims = D> {
+                let (dims, iter) = producer.into_producer();
+                check_dims(&self.dims, &dims);
+                let ($($p,)+) = self.producers;
+                Zip {
+                    dims: self.dims,
+                    producers: ($($p,)+ iter,),
+                }
+            }
+        }
+    };
+}
+
+zip_impl!(P1 p1);
+zip_impl!(P1 p1, P2 p2);
+zip_impl!(P1 p1, P2 p2, P3 p3);
+zip_impl!(P1 p1, P2 p2, P3 p3, P4 p4);
+zip_impl!(P1 p1, P2 p2, P3 p3, P4 p4, P5 p5);
+zip_impl!(P1 p1, P2 p2, P3 p3, P4 p4, P5 p5, P6 p6);
+
+zip_and!(P1 p1);
+zip_and!(P1 p1, P2 p2);
+zip_and!(P1 p1, P2 p2, P3 p3);
+zip_and!(P1 p1, P2 p2, P3 p3, P4 p4);
+zip_and!(P1 p1, P2 p2, P3 p3, P4 p4, P5 p5);
+
+#[cfg(test)]
+mod tests {
+    use {Array, Zip};
+
+    #[test]
+    fn map() {
+        let array = Array::from_fn((2, -1..2), |(i, j)| 10 * i as i32 + j);
+        let mapped = array.map(|&value| value as f64 / 2.0);
+        assert_eq!(mapped.shape(), (2, -1..2));
+        assert_eq!(mapped[(1, 1)], 5.5);
+
+        let mapped = array.mapv(|value| value > 0);
+        assert_eq!(mapped, Array::from_vector(vec![false, false, true, true, true, true], (2, -1..2)));
+
+        let mut array = array;
+        array.map_inplace(|value| *value = -*value);
+        assert_eq!(array[(1, -1)], -9);
+    }
+
+    #[test]
+    fn zip_with() {
+        let a = Array::from_fn((2, -1..2), |(i, j)| 10 * i as i32 + j);
+        let b = Array::from_element(2, (2, -1..2));
+        let c = a.zip_with(&b, |a, b| a * b);
+        assert_eq!(c[(1, 1)], 22);
+    }
+
+    #[test]
+    #[should_panic(expected = "dimensions mismatch in zip_with")]
+    fn zip_with_mismatch() {
+        let a = Array::from_element(0, (2, 3));
+        let b = Array::from_element(0, (3, 2));
+        let _ = a.zip_with(&b, |a, b| a + b);
+    }
+
+    #[test]
+    fn zip_six() {
+        let mut out = Array::from_element(0, (2, 1..3));
+        let a = Array::from_element(1, (2, 1..3));
+        let b = Array::from_element(2, (2, 1..3));
+        let c = Array::from_element(3, (2, 1..3));
+        let d = Array::from_element(4, (2, 1..3));
+        let e = Array::from_element(5, (2, 1..3));
+        Zip::from(&mut out).and(&a).and(&b).and(&c).and(&d).and(&e).apply(|out, a, b, c, d, e| {
+            *out = a + b + c + d + e;
+        });
+        assert_eq!(out, Array::from_element(15, (2, 1..3)));
+
+        let indexes = Zip::from(&a).and(&b).indexed().map_collect(|index, a, b| (index, a + b));
+        assert_eq!(indexes[(1, 2)], ((1, 2), 3));
+    }
+
+    #[test]
+    fn zip_views() {
+        let mut a = Array::from_element(0, (-1..3, -1..3));
+        let b = Array::from_fn((0..2, 0..2), |(i, j)| 10 * i + j);
+        Zip::from(a.subview_mut((0..2, 0..2))).and(b.view()).apply(|a, b| *a = *b);
+        assert_eq!(a[(1, 1)], 11);
+        assert_eq!(a.flat_iter().sum::<i32>(), 22);
+
+        let mut count = 0;
+        Zip::from(a.subview((0..2, 0..2))).indexed().apply(|index, value| {
+            assert_eq!(b[index], *value);
+            count += 1;
+        });
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "all the producers in a Zip must have the same dimensions")]
+    fn zip_mismatch() {
+        let a = Array::from_element(0, (2, 0..3));
+        let b = Array::from_element(0, (2, 1..4));
+        let _ = Zip::from(&a).and(&b);
+    }
+}
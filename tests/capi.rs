@@ -0,0 +1,29 @@
+//! Check that the generated C header can be used from C and C++. The tests
+//! are skipped if there is no C compiler.
+#![cfg(feature = "capi")]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+fn check_syntax(language: &str) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let status = Command::new("cc")
+        .args(["-fsyntax-only", "-Wall", "-Werror", "-x", language, "-I"])
+        .arg(root.join("include"))
+        .arg(root.join("tests").join("capi").join("usage.c"))
+        .status();
+    match status {
+        Ok(status) => assert!(status.success(), "the C API header does not compile as {}", language),
+        Err(_) => eprintln!("could not run the C compiler, skipping the test"),
+    }
+}
+
+#[test]
+fn c_header() {
+    check_syntax("c");
+}
+
+#[test]
+fn cxx_header() {
+    check_syntax("c++");
+}
@@ -0,0 +1,36 @@
+/* Typical usage of the C API, only checked for compilation */
+#include <assert.h>
+#include "mudi.h"
+
+double sum_all(mudi_array_t* array) {
+    size_t size = 0;
+    double* data = NULL;
+    mudi_dtype_t dtype;
+    assert(mudi_array_dtype(array, &dtype) == MUDI_SUCCESS && dtype == MUDI_F64);
+    mudi_array_size(array, &size);
+    mudi_array_data(array, (void**)&data);
+
+    double sum = 0;
+    for (size_t i = 0; i < size; i++) {
+        sum += data[i];
+    }
+    return sum;
+}
+
+void usage(void) {
+    ptrdiff_t lbounds[2] = {1, -1};
+    size_t extents[2] = {3, 2};
+    mudi_array_t* array = mudi_array_new(MUDI_F64, 2, lbounds, extents);
+
+    ptrdiff_t index[2] = {3, 0};
+    double value = 4.0;
+    mudi_array_set(array, index, &value);
+
+    ptrdiff_t ubound = 0;
+    mudi_array_ubound(array, 0, &ubound);
+    size_t offset = 0;
+    mudi_array_offset(array, index, &offset);
+
+    sum_all(array);
+    mudi_array_free(array);
+}
@@ -0,0 +1,181 @@
+//! Check the C descriptors against a C shim using the gfortran layout of
+//! `CFI_cdesc_t`. The shim is compiled with the system C compiler and loaded
+//! at runtime, and the tests are skipped if there is no C compiler.
+#![cfg(target_os = "linux")]
+
+extern crate mudi;
+
+use std::ffi::CString;
+use std::mem;
+use std::ops::{Range, RangeInclusive};
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use mudi::ffi::{CfiDescriptor, CfiDim, CFI_MAX_RANK};
+use mudi::{Array, ArrayView, ColumnMajor};
+
+#[link(name = "dl")]
+extern "C" {
+    fn dlopen(filename: *const c_char, flag: c_int) -> *mut c_void;
+    fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+}
+
+const RTLD_NOW: c_int = 2;
+
+struct Shim {
+    layout: extern "C" fn(*mut usize),
+    dims: extern "C" fn(*const CfiDescriptor, *mut isize) -> c_int,
+    sum: extern "C" fn(*const CfiDescriptor) -> f64,
+    get2: extern "C" fn(*const CfiDescriptor, isize, isize) -> f64,
+    scale: extern "C" fn(*mut CfiDescriptor, f64),
+    fortran_array: extern "C" fn() -> *const CfiDescriptor,
+}
+
+/// Get the C shim, compiling and loading it on the first call
+fn shim() -> Option<&'static Shim> {
+    static SHIM: OnceLock<Option<Shim>> = OnceLock::new();
+    SHIM.get_or_init(load).as_ref()
+}
+
+fn load() -> Option<Shim> {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let library = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("libcfi_shim.so");
+    let status = Command::new("cc")
+        .args(["-shared", "-fPIC", "-std=c99", "-o"])
+        .arg(&library)
+        .arg(manifest.join("tests").join("cfi").join("shim.c"))
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        _ => {
+            eprintln!("could not compile the C shim, skipping the test");
+            return None;
+        }
+    }
+
+    unsafe {
+        let path = CString::new(library.to_str().unwrap()).unwrap();
+        let handle = dlopen(path.as_ptr(), RTLD_NOW);
+        assert!(!handle.is_null(), "could not load the C shim");
+        Some(Shim {
+            layout: symbol(handle, "shim_layout"),
+            dims: symbol(handle, "shim_dims"),
+            sum: symbol(handle, "shim_sum"),
+            get2: symbol(handle, "shim_get2"),
+            scale: symbol(handle, "shim_scale"),
+            fortran_array: symbol(handle, "shim_fortran_array"),
+        })
+    }
+}
+
+/// Get the function called `name` in the library loaded as `handle`
+unsafe fn symbol<F: Copy>(handle: *mut c_void, name: &str) -> F {
+    let name = CString::new(name).unwrap();
+    let symbol = dlsym(handle, name.as_ptr());
+    assert!(!symbol.is_null(), "missing symbol in the C shim");
+    mem::transmute_copy(&symbol)
+}
+
+fn dims(shim: &Shim, descriptor: &CfiDescriptor) -> Vec<CfiDim> {
+    let mut raw = [0; 3 * CFI_MAX_RANK];
+    let rank = (shim.dims)(descriptor, raw.as_mut_ptr());
+    raw.chunks(3).take(rank as usize).map(|dim| CfiDim {
+        lower_bound: dim[0],
+        extent: dim[1],
+        sm: dim[2],
+    }).collect()
+}
+
+#[test]
+fn layout() {
+    let shim = match shim() {
+        Some(shim) => shim,
+        None => return,
+    };
+    let mut layout = [0; 9];
+    (shim.layout)(layout.as_mut_ptr());
+    assert_eq!(layout, [
+        mem::size_of::<CfiDim>(),
+        mem::offset_of!(CfiDescriptor, base_addr),
+        mem::offset_of!(CfiDescriptor, elem_len),
+        mem::offset_of!(CfiDescriptor, version),
+        mem::offset_of!(CfiDescriptor, rank),
+        mem::offset_of!(CfiDescriptor, attribute),
+        mem::offset_of!(CfiDescriptor, type_),
+        mem::offset_of!(CfiDescriptor, dim),
+        mem::size_of::<CfiDescriptor>(),
+    ]);
+}
+
+#[test]
+fn export() {
+    let shim = match shim() {
+        Some(shim) => shim,
+        None => return,
+    };
+    let array = Array::from_fn((-1..2, 1..=4), |(i, j)| 10.0 * i as f64 + j as f64);
+    let descriptor = array.cfi_descriptor();
+    assert_eq!(dims(shim, &descriptor), [
+        CfiDim { lower_bound: -1, extent: 3, sm: 32 },
+        CfiDim { lower_bound: 1, extent: 4, sm: 8 },
+    ]);
+    assert_eq!((shim.sum)(&descriptor), array.flat_iter().sum::<f64>());
+    assert_eq!((shim.get2)(&descriptor, -1, 3), array[(-1, 3)]);
+    assert_eq!((shim.get2)(&descriptor, 1, 2), array[(1, 2)]);
+
+    let view = array.subview((0..2, 2..=3));
+    let descriptor = view.cfi_descriptor();
+    assert_eq!(dims(shim, &descriptor), [
+        CfiDim { lower_bound: 0, extent: 2, sm: 32 },
+        CfiDim { lower_bound: 2, extent: 2, sm: 8 },
+    ]);
+    assert_eq!((shim.sum)(&descriptor), view.iter().sum::<f64>());
+    assert_eq!((shim.get2)(&descriptor, 1, 2), array[(1, 2)]);
+
+    let array = Array::from_fn(ColumnMajor((-1..2, 1..=4)), |(i, j)| 10.0 * i as f64 + j as f64);
+    let descriptor = array.cfi_descriptor();
+    assert_eq!(dims(shim, &descriptor), [
+        CfiDim { lower_bound: -1, extent: 3, sm: 8 },
+        CfiDim { lower_bound: 1, extent: 4, sm: 24 },
+    ]);
+    assert_eq!((shim.get2)(&descriptor, 0, 4), array[(0, 4)]);
+}
+
+#[test]
+fn export_mut() {
+    let shim = match shim() {
+        Some(shim) => shim,
+        None => return,
+    };
+    let mut array = Array::from_element(1.0, (0..3, 0..3));
+    {
+        let mut view = array.subview_mut((1..3, 0..2));
+        let mut descriptor = view.cfi_descriptor_mut();
+        (shim.scale)(&mut descriptor, 3.0);
+    }
+    assert_eq!(array, Array::from_vector(vec![1.0, 1.0, 1.0, 3.0, 3.0, 1.0, 3.0, 3.0, 1.0], (0..3, 0..3)));
+}
+
+#[test]
+fn import() {
+    let shim = match shim() {
+        Some(shim) => shim,
+        None => return,
+    };
+    let descriptor = (shim.fortran_array)();
+
+    type Dims = ColumnMajor<(Range<i32>, RangeInclusive<i32>)>;
+    let view = unsafe { ArrayView::<f64, Dims>::from_cfi(descriptor) }.unwrap();
+    assert_eq!(view.shape(), ColumnMajor((0..3, -1..=0)));
+    assert_eq!(view[(0, -1)], 1.0);
+    assert_eq!(view[(1, -1)], 2.0);
+    assert_eq!(view[(0, 0)], 4.0);
+    assert_eq!(view[(2, 0)], 6.0);
+
+    let view = unsafe { ArrayView::<f64, ColumnMajor<(usize, usize)>>::from_cfi(descriptor) };
+    assert!(view.is_err());
+    let view = unsafe { ArrayView::<f64, (Range<i32>, Range<i32>)>::from_cfi(descriptor) };
+    assert!(view.is_err());
+}
@@ -0,0 +1,146 @@
+/* A small stand-in for a Fortran procedure receiving assumed-shape arrays,
+ * using the gfortran layout of the descriptors from ISO_Fortran_binding.h */
+#include <stddef.h>
+#include <stdint.h>
+
+#define CFI_MAX_RANK 15
+#define CFI_VERSION 1
+#define CFI_attribute_other 2
+#define CFI_type_double 2051
+
+typedef ptrdiff_t CFI_index_t;
+typedef signed char CFI_rank_t;
+typedef signed char CFI_attribute_t;
+typedef int16_t CFI_type_t;
+
+typedef struct CFI_dim_t {
+    CFI_index_t lower_bound;
+    CFI_index_t extent;
+    CFI_index_t sm;
+} CFI_dim_t;
+
+typedef struct CFI_cdesc_t {
+    void* base_addr;
+    size_t elem_len;
+    int version;
+    CFI_rank_t rank;
+    CFI_attribute_t attribute;
+    CFI_type_t type;
+    CFI_dim_t dim[];
+} CFI_cdesc_t;
+
+#define CFI_CDESC_T(r) struct { \
+    void* base_addr;             \
+    size_t elem_len;             \
+    int version;                 \
+    CFI_rank_t rank;             \
+    CFI_attribute_t attribute;   \
+    CFI_type_t type;             \
+    CFI_dim_t dim[r];            \
+}
+
+/* Write the size and field offsets of the descriptor types in `out` */
+void shim_layout(size_t out[9]) {
+    out[0] = sizeof(CFI_dim_t);
+    out[1] = offsetof(CFI_cdesc_t, base_addr);
+    out[2] = offsetof(CFI_cdesc_t, elem_len);
+    out[3] = offsetof(CFI_cdesc_t, version);
+    out[4] = offsetof(CFI_cdesc_t, rank);
+    out[5] = offsetof(CFI_cdesc_t, attribute);
+    out[6] = offsetof(CFI_cdesc_t, type);
+    out[7] = offsetof(CFI_cdesc_t, dim);
+    out[8] = sizeof(CFI_CDESC_T(CFI_MAX_RANK));
+}
+
+/* Write the lower bound, extent and stride of each axis in `out` */
+int shim_dims(const CFI_cdesc_t* desc, CFI_index_t* out) {
+    for (int i = 0; i < desc->rank; i++) {
+        out[3 * i + 0] = desc->dim[i].lower_bound;
+        out[3 * i + 1] = desc->dim[i].extent;
+        out[3 * i + 2] = desc->dim[i].sm;
+    }
+    return desc->rank;
+}
+
+/* Get the address of the element at `positions`, counted from the lower
+ * bounds */
+static char* element(const CFI_cdesc_t* desc, const CFI_index_t* positions) {
+    char* address = desc->base_addr;
+    for (int i = 0; i < desc->rank; i++) {
+        address += positions[i] * desc->dim[i].sm;
+    }
+    return address;
+}
+
+/* Advance `positions` to the next element in Fortran order, returning 0 at
+ * the end of the array */
+static int next(const CFI_cdesc_t* desc, CFI_index_t* positions) {
+    for (int i = 0; i < desc->rank; i++) {
+        positions[i] += 1;
+        if (positions[i] < desc->dim[i].extent) {
+            return 1;
+        }
+        positions[i] = 0;
+    }
+    return 0;
+}
+
+static int is_empty(const CFI_cdesc_t* desc) {
+    for (int i = 0; i < desc->rank; i++) {
+        if (desc->dim[i].extent == 0) {
+            return 1;
+        }
+    }
+    return 0;
+}
+
+/* Sum of a double array, like SUM(array) */
+double shim_sum(const CFI_cdesc_t* desc) {
+    CFI_index_t positions[CFI_MAX_RANK] = {0};
+    double sum = 0;
+    if (desc->type != CFI_type_double || is_empty(desc)) {
+        return sum;
+    }
+    do {
+        sum += *(double*)element(desc, positions);
+    } while (next(desc, positions));
+    return sum;
+}
+
+/* Get the element at the Fortran indexes (i, j) of a rank-2 double array */
+double shim_get2(const CFI_cdesc_t* desc, CFI_index_t i, CFI_index_t j) {
+    CFI_index_t positions[2] = {i - desc->dim[0].lower_bound, j - desc->dim[1].lower_bound};
+    return *(double*)element(desc, positions);
+}
+
+/* Multiply all the elements of a double array by `factor` */
+void shim_scale(CFI_cdesc_t* desc, double factor) {
+    CFI_index_t positions[CFI_MAX_RANK] = {0};
+    if (desc->type != CFI_type_double || is_empty(desc)) {
+        return;
+    }
+    do {
+        *(double*)element(desc, positions) *= factor;
+    } while (next(desc, positions));
+}
+
+/* A Fortran array `real(8) :: data(0:2, -1:0)`, stored in column-major order */
+static double DATA[6] = {1, 2, 3, 4, 5, 6};
+static CFI_CDESC_T(2) DESCRIPTOR;
+
+/* Get a rank-2 descriptor for a column-major Fortran array */
+const CFI_cdesc_t* shim_fortran_array(void) {
+    DESCRIPTOR.base_addr = DATA;
+    DESCRIPTOR.elem_len = sizeof(double);
+    DESCRIPTOR.version = CFI_VERSION;
+    DESCRIPTOR.rank = 2;
+    DESCRIPTOR.attribute = CFI_attribute_other;
+    DESCRIPTOR.type = CFI_type_double;
+    DESCRIPTOR.dim[0].lower_bound = 0;
+    DESCRIPTOR.dim[0].extent = 3;
+    DESCRIPTOR.dim[0].sm = sizeof(double);
+    DESCRIPTOR.dim[1].lower_bound = -1;
+    DESCRIPTOR.dim[1].extent = 2;
+    DESCRIPTOR.dim[1].sm = 3 * sizeof(double);
+    return (const CFI_cdesc_t*)&DESCRIPTOR;
+}
@@ -0,0 +1,7 @@
+extern crate mudi;
+use mudi::{Array, Banded};
+
+fn main() {
+    let array = Array::from_element(0.0, (Banded { n: 4, kl: 1, ku: 1 }, 3));
+    //~^ error: type mismatch resolving
+}